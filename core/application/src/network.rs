@@ -15,7 +15,8 @@ pub fn genesis(&self) -> Result<Genesis> {
             Network::LocalnetExample => include_str!("../networks/localnet-example/genesis.toml"),
             Network::TestnetStable => include_str!("../networks/testnet-stable/genesis.toml"),
         };
-        let genesis = toml::from_str(raw).context("Failed to parse genesis file")?;
+        let genesis: Genesis = toml::from_str(raw).context("Failed to parse genesis file")?;
+        genesis.validate().context("Invalid genesis")?;
 
         Ok(genesis)
     }
@@ -28,8 +28,10 @@
     MintInfo,
     NodeIndex,
     NodeInfo,
+    NodeInfoWithIndex,
     NodeServed,
     Nonce,
+    Participation,
     ProtocolParamKey,
     ProtocolParamValue,
     ReportedReputationMeasurements,
@@ -226,6 +228,25 @@ fn get_node_table_iter<V>(&self, closure: impl FnOnce(KeyIterator<NodeIndex>) ->
             .run(|ctx| closure(self.node_table.get(ctx).keys()))
     }
 
+    fn get_participating_nodes(&self) -> Vec<NodeInfoWithIndex> {
+        self.get_nodes_by_participation(true)
+    }
+
+    fn get_nodes_by_participation(&self, active: bool) -> Vec<NodeInfoWithIndex> {
+        self.inner.run(|ctx| {
+            let node_table = self.node_table.get(ctx);
+            node_table
+                .keys()
+                .filter_map(|index| {
+                    node_table
+                        .get(index)
+                        .map(|info| NodeInfoWithIndex { index, info })
+                })
+                .filter(|node| (node.info.participation == Participation::True) == active)
+                .collect()
+        })
+    }
+
     fn pubkey_to_index(&self, pub_key: &NodePublicKey) -> Option<NodeIndex> {
         self.inner
             .run(|ctx| self.pub_key_to_index.get(ctx).get(pub_key))
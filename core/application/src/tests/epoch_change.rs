@@ -15,11 +15,7 @@
 };
 use lightning_interfaces::SyncQueryRunnerInterface;
 use lightning_test_utils::consensus::MockConsensusConfig;
-use lightning_test_utils::e2e::{
-    DowncastToTestFullNode,
-    TestFullNodeComponentsWithMockConsensus,
-    TestNetwork,
-};
+use lightning_test_utils::e2e::{TestFullNodeComponentsWithMockConsensus, TestNetwork};
 use lightning_utils::application::QueryRunnerExt;
 use lightning_utils::poll::{poll_until, PollUntilError};
 use tempfile::tempdir;
@@ -647,19 +643,15 @@ async fn test_distribute_rewards() {
 
     // Deposit and stake FLK tokens, and stake lock in node 2.
     network
-        .node(0)
-        .downcast::<TestFullNodeComponentsWithMockConsensus>()
-        .deposit_and_stake(10_000_u64.into(), &node1.get_owner_secret_key())
+        .deposit_and_stake(0, 10_000_u64.into(), &node1.get_owner_secret_key())
         .await
         .unwrap();
-    node2
-        .downcast::<TestFullNodeComponentsWithMockConsensus>()
-        .deposit_and_stake(10_000_u64.into(), &node2.get_owner_secret_key())
+    network
+        .deposit_and_stake(1, 10_000_u64.into(), &node2.get_owner_secret_key())
         .await
         .unwrap();
-    node2
-        .downcast::<TestFullNodeComponentsWithMockConsensus>()
-        .stake_lock(1460, &node2.get_owner_secret_key())
+    network
+        .stake_lock(1, 1460, &node2.get_owner_secret_key())
         .await
         .unwrap();
 
@@ -833,8 +825,7 @@ async fn test_supply_across_epoch() {
     let service_share = HpUfixed::from(genesis.service_builder_share) / &percentage_divisor;
 
     // Deposit and stake FLK tokens.
-    node.downcast::<TestFullNodeComponentsWithMockConsensus>()
-        .deposit_and_stake(10_000_u64.into(), &node.get_owner_secret_key())
+    node.deposit_and_stake(10_000_u64.into(), &node.get_owner_secret_key())
         .await
         .unwrap();
 
@@ -882,15 +873,9 @@ async fn test_supply_across_epoch() {
         // Start commit phase.
         let new_epoch = network.change_epoch().await.unwrap();
 
-        // Wait for the commit phase to end.
-        tokio::time::sleep(Duration::from_millis(commit_phase_duration)).await;
-        // Send the commit phase timeout transaction from 2/3+1 committee nodes.
-        network.commit_phase_timeout(0).await.unwrap();
-
-        // Wait for the reveal phase to end.
-        tokio::time::sleep(Duration::from_millis(reveal_phase_duration)).await;
-        // Send the reveal phase timeout transaction from 2/3+1 committee nodes.
-        network.reveal_phase_timeout(0).await.unwrap();
+        // Time out the commit and reveal phases.
+        network.advance_beacon_phase().await.unwrap();
+        network.advance_beacon_phase().await.unwrap();
 
         // Wait for the epoch change to complete.
         network.wait_for_epoch_change(new_epoch).await.unwrap();
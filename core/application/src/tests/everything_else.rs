@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::time::{Duration, UNIX_EPOCH};
 
 use fleek_crypto::{AccountOwnerSecretKey, EthAddress, NodeSecretKey, SecretKey};
 use hp_fixed::unsigned::HpUfixed;
@@ -204,6 +205,107 @@ async fn test_get_node_registry() {
     );
 }
 
+#[tokio::test]
+async fn test_time_until_epoch_end() {
+    let temp_dir = tempdir().unwrap();
+
+    let committee_size = 4;
+    let (committee, _keystore) = create_genesis_committee(committee_size);
+    let (_update_socket, query_runner) = test_init_app(&temp_dir, committee);
+
+    let epoch_end = UNIX_EPOCH + Duration::from_millis(query_runner.get_epoch_info().epoch_end);
+    assert_eq!(
+        query_runner.time_until_epoch_end(epoch_end - Duration::from_secs(1)),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(query_runner.time_until_epoch_end(epoch_end), None);
+    assert_eq!(
+        query_runner.time_until_epoch_end(epoch_end + Duration::from_secs(1)),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_is_in_committee() {
+    let temp_dir = tempdir().unwrap();
+
+    let committee_size = 4;
+    let (committee, keystore) = create_genesis_committee(committee_size);
+    let (update_socket, query_runner) = test_init_app(&temp_dir, committee);
+
+    for member in &keystore {
+        assert!(query_runner.is_in_committee(&member.node_secret_key.to_pk()));
+    }
+
+    // A staked node that is not in the committee.
+    let owner_secret_key = AccountOwnerSecretKey::generate();
+    let node_secret_key = NodeSecretKey::generate();
+    let minimum_stake_amount = query_runner.get_staking_amount().into();
+    deposit_and_stake(
+        &update_socket,
+        &owner_secret_key,
+        1,
+        &minimum_stake_amount,
+        &node_secret_key.to_pk(),
+        [0; 96].into(),
+    )
+    .await;
+    assert!(!query_runner.is_in_committee(&node_secret_key.to_pk()));
+
+    // An unknown node.
+    assert!(!query_runner.is_in_committee(&NodeSecretKey::generate().to_pk()));
+}
+
+#[tokio::test]
+async fn test_get_nodes_by_participation() {
+    let temp_dir = tempdir().unwrap();
+
+    let committee_size = 4;
+    let (committee, keystore) = create_genesis_committee(committee_size);
+    let (update_socket, query_runner) = test_init_app(&temp_dir, committee);
+
+    // A newly staked node does not participate until it opts in and the epoch changes.
+    let owner_secret_key = AccountOwnerSecretKey::generate();
+    let node_secret_key = NodeSecretKey::generate();
+    let minimum_stake_amount = query_runner.get_staking_amount().into();
+    deposit_and_stake(
+        &update_socket,
+        &owner_secret_key,
+        1,
+        &minimum_stake_amount,
+        &node_secret_key.to_pk(),
+        [0; 96].into(),
+    )
+    .await;
+    let node_index = get_node_index(&query_runner, &node_secret_key.to_pk());
+
+    let participating = query_runner.get_participating_nodes();
+    assert_eq!(participating.len(), keystore.len());
+    assert!(participating
+        .iter()
+        .all(|node| node.info.participation == Participation::True));
+    assert!(!participating.iter().any(|node| node.index == node_index));
+    assert_eq!(
+        query_runner
+            .get_nodes_by_participation(true)
+            .into_iter()
+            .map(|node| node.index)
+            .collect::<Vec<_>>(),
+        participating
+            .into_iter()
+            .map(|node| node.index)
+            .collect::<Vec<_>>()
+    );
+
+    let not_participating = query_runner.get_nodes_by_participation(false);
+    assert_eq!(not_participating.len(), 1);
+    assert_eq!(not_participating[0].index, node_index);
+    assert_eq!(
+        not_participating[0].info.participation,
+        Participation::False
+    );
+}
+
 #[tokio::test]
 async fn test_invalid_chain_id() {
     let temp_dir = tempdir().unwrap();
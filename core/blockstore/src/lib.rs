@@ -1,7 +1,9 @@
 pub mod blockstore;
 pub mod config;
+pub mod put;
 
 pub use blockstore::Blockstore;
+pub use put::{put_reader, put_reader_with_chunk_size, PutReaderError};
 
 //#[cfg(test)]
 //mod tests {
@@ -0,0 +1,213 @@
+use std::io;
+
+use b3fs::bucket::errors::{CommitError, WriteError};
+use lightning_interfaces::prelude::*;
+use lightning_interfaces::types::Blake3Hash;
+use lightning_interfaces::FileTrustedWriter;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// The default number of bytes read from the source before they are written to the blockstore.
+pub const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PutReaderError {
+    #[error("Failed to read the content: {0}")]
+    Read(#[from] io::Error),
+    #[error("Failed to write the content: {0}")]
+    Write(#[from] WriteError),
+    #[error("Failed to commit the content: {0}")]
+    Commit(#[from] CommitError),
+}
+
+/// Read all of the content from the reader, put it into the blockstore and return its hash.
+pub async fn put_reader<C, B, R>(blockstore: &B, reader: R) -> Result<Blake3Hash, PutReaderError>
+where
+    C: NodeComponents,
+    B: BlockstoreInterface<C>,
+    R: AsyncRead + Unpin,
+{
+    put_reader_with_chunk_size(blockstore, reader, DEFAULT_CHUNK_SIZE).await
+}
+
+/// Same as [`put_reader`], but reads the content in chunks of the given size.
+pub async fn put_reader_with_chunk_size<C, B, R>(
+    blockstore: &B,
+    reader: R,
+    chunk_size: usize,
+) -> Result<Blake3Hash, PutReaderError>
+where
+    C: NodeComponents,
+    B: BlockstoreInterface<C>,
+    R: AsyncRead + Unpin,
+{
+    let writer = blockstore.file_writer().await?;
+    write_reader(writer, reader, chunk_size).await
+}
+
+/// Write all of the content from the reader to the given writer and commit it. If reading or
+/// writing fails, the writer is rolled back.
+pub async fn write_reader<W, R>(
+    mut writer: W,
+    mut reader: R,
+    chunk_size: usize,
+) -> Result<Blake3Hash, PutReaderError>
+where
+    W: FileTrustedWriter,
+    R: AsyncRead + Unpin,
+{
+    assert!(chunk_size > 0, "chunk size must be greater than zero");
+
+    if let Err(e) = write_chunks(&mut writer, &mut reader, chunk_size).await {
+        if let Err(rollback_err) = writer.rollback().await {
+            tracing::error!("Failed to rollback the blockstore writer: {rollback_err}");
+        }
+        return Err(e);
+    }
+
+    Ok(writer.commit().await?)
+}
+
+async fn write_chunks<W, R>(
+    writer: &mut W,
+    reader: &mut R,
+    chunk_size: usize,
+) -> Result<(), PutReaderError>
+where
+    W: FileTrustedWriter,
+    R: AsyncRead + Unpin,
+{
+    // We always read one chunk ahead, so we know whether the current chunk is the last one.
+    let mut current = Vec::with_capacity(chunk_size);
+    let mut next = Vec::with_capacity(chunk_size);
+    read_chunk(reader, &mut current, chunk_size).await?;
+
+    loop {
+        read_chunk(reader, &mut next, chunk_size).await?;
+        let last_bytes = next.is_empty();
+        writer.write(&current, last_bytes).await?;
+        if last_bytes {
+            return Ok(());
+        }
+        std::mem::swap(&mut current, &mut next);
+    }
+}
+
+// Fills the buffer with up to `chunk_size` bytes. The buffer is only partially filled if the
+// reader reaches the end of the content.
+async fn read_chunk<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    chunk_size: usize,
+) -> io::Result<()> {
+    buffer.clear();
+    reader
+        .take(chunk_size as u64)
+        .read_to_end(buffer)
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use blake3_tree::blake3;
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Written {
+        content: Vec<u8>,
+        writes: Vec<(usize, bool)>,
+        committed: bool,
+        rolled_back: bool,
+    }
+
+    struct TestWriter(Arc<Mutex<Written>>);
+
+    impl FileTrustedWriter for TestWriter {
+        async fn write(&mut self, content: &[u8], last_bytes: bool) -> Result<(), WriteError> {
+            let mut written = self.0.lock().unwrap();
+            written.content.extend_from_slice(content);
+            written.writes.push((content.len(), last_bytes));
+            Ok(())
+        }
+
+        async fn commit(self) -> Result<Blake3Hash, CommitError> {
+            let mut written = self.0.lock().unwrap();
+            written.committed = true;
+            Ok(*blake3::hash(&written.content).as_bytes())
+        }
+
+        async fn rollback(self) -> Result<(), io::Error> {
+            self.0.lock().unwrap().rolled_back = true;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_reader_from_duplex() {
+        let content: Vec<u8> = (0..10_000).map(|i| (i % 251) as u8).collect();
+        let (mut client, server) = tokio::io::duplex(1024);
+        let input = content.clone();
+        tokio::spawn(async move {
+            client.write_all(&input).await.unwrap();
+        });
+
+        let written = Arc::new(Mutex::new(Written::default()));
+        let hash = write_reader(TestWriter(written.clone()), server, 4096)
+            .await
+            .unwrap();
+
+        let written = written.lock().unwrap();
+        assert_eq!(hash, *blake3::hash(&content).as_bytes());
+        assert_eq!(written.content, content);
+        assert_eq!(
+            written.writes,
+            vec![(4096, false), (4096, false), (1808, true)]
+        );
+        assert!(written.committed);
+        assert!(!written.rolled_back);
+    }
+
+    async fn write_content(content: &[u8]) -> Written {
+        let written = Arc::new(Mutex::new(Written::default()));
+        let hash = write_reader(TestWriter(written.clone()), content, DEFAULT_CHUNK_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(hash, *blake3::hash(content).as_bytes());
+
+        Arc::into_inner(written).unwrap().into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_write_reader_chunk_boundaries() {
+        // Content of exactly one chunk is written in a single final write.
+        let content = vec![7; DEFAULT_CHUNK_SIZE];
+        let written = write_content(&content).await;
+        assert_eq!(written.content, content);
+        assert_eq!(written.writes, vec![(DEFAULT_CHUNK_SIZE, true)]);
+
+        // One more byte spills over into a second chunk.
+        let content = vec![7; DEFAULT_CHUNK_SIZE + 1];
+        let written = write_content(&content).await;
+        assert_eq!(written.content, content);
+        assert_eq!(written.writes, vec![(DEFAULT_CHUNK_SIZE, false), (1, true)]);
+    }
+
+    #[tokio::test]
+    async fn test_write_reader_empty() {
+        let (client, server) = tokio::io::duplex(1024);
+        drop(client);
+
+        let written = Arc::new(Mutex::new(Written::default()));
+        write_reader(TestWriter(written.clone()), server, 4096)
+            .await
+            .unwrap();
+
+        let written = written.lock().unwrap();
+        assert_eq!(written.writes, vec![(0, true)]);
+        assert!(written.committed);
+    }
+}
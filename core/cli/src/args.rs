@@ -108,8 +108,22 @@ pub enum DevSubCmd {
         /// The Blake3 hash of the content that we want to download.
         hash: String,
     },
+    /// Read a file from the local blockstore and verify it against its Blake3 hash.
+    Read {
+        /// The Blake3 hash of the content that we want to read.
+        hash: String,
+        /// The path to write the content to. Defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Clear the state tree and rebuild it from scratch.
     ResetStateTree,
+    /// Export the local application state to JSON.
+    DumpState {
+        /// The path to write the state to. Defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, PartialEq, Eq)]
@@ -119,6 +133,13 @@ pub enum KeySubCmd {
     /// Generate new private keys.
     /// This command will fail if the keys already exist.
     Generate,
+    /// Replace the node's keys with newly generated keys.
+    /// The existing keys are backed up next to the originals with a timestamp suffix.
+    Rotate {
+        /// Do not ask for confirmation.
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
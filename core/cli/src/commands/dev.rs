@@ -2,10 +2,14 @@
 
 use anyhow::{Context, Result};
 use lightning_interfaces::prelude::*;
+use lightning_interfaces::types::{Blake3Hash, Metadata, ProtocolParamKey};
 use lightning_node::Node;
 use lightning_rpc::interface::Admin;
+use lightning_utils::application::QueryRunnerExt;
 use lightning_utils::config::TomlConfigProvider;
 use resolved_pathbuf::ResolvedPathBuf;
+use serde_json::{json, Map, Value as JsonValue};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use crate::args::DevSubCmd;
 
@@ -17,7 +21,9 @@ pub async fn exec<C>(cmd: DevSubCmd, config_path: ResolvedPathBuf) -> Result<()>
         DevSubCmd::DepGraph => dep_graph::<C>().await,
         DevSubCmd::Store { input } => store::<C>(config_path, input).await,
         DevSubCmd::Fetch { remote, hash } => fetch::<C>(config_path, hash, remote).await,
+        DevSubCmd::Read { hash, output } => read::<C>(config_path, hash, output).await,
         DevSubCmd::ResetStateTree => reset_state_tree::<C>(config_path).await,
+        DevSubCmd::DumpState { output } => dump_state::<C>(config_path, output).await,
     }
 }
 
@@ -70,28 +76,7 @@ async fn fetch<C: NodeComponents<ConfigProviderInterface = TomlConfigProvider<C>
     hash_string: String,
     peer: u32,
 ) -> Result<()> {
-    let hash: [u8; 32] = if hash_string.starts_with('[') {
-        let pat: &[_] = &['[', ']'];
-        let numbers: Vec<u8> = hash_string
-            .trim_matches(pat)
-            .split(',')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.parse().expect("expected number"))
-            .collect();
-
-        if numbers.len() != 32 {
-            anyhow::bail!("Failed to parse hash.");
-        }
-
-        let mut result = [0u8; 32];
-        result.copy_from_slice(&numbers);
-        result
-    } else {
-        fleek_blake3::Hash::from_hex(hash_string.as_bytes())
-            .context("Invalid blake3 hash.")?
-            .into()
-    };
+    let hash = parse_hash(&hash_string)?;
     let hash_string = fleek_blake3::Hash::from(hash).to_string();
 
     let config = TomlConfigProvider::<C>::load(config_path)?;
@@ -129,6 +114,207 @@ async fn fetch<C: NodeComponents<ConfigProviderInterface = TomlConfigProvider<C>
     Ok(())
 }
 
+async fn read<C: NodeComponents<ConfigProviderInterface = TomlConfigProvider<C>>>(
+    config_path: ResolvedPathBuf,
+    hash_string: String,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let hash = parse_hash(&hash_string)?;
+    let hash_string = fleek_blake3::Hash::from(hash).to_string();
+
+    let config = TomlConfigProvider::<C>::load(config_path)?;
+    let mut node = Node::<C>::init(config)
+        .map_err(|e| anyhow::anyhow!("Node Initialization failed: {e:?}"))
+        .context("Could not initialize the node.")?;
+
+    let bucket = node.provider.get::<C::BlockstoreInterface>().get_bucket();
+    let result = async {
+        let header = bucket
+            .get(&hash)
+            .await
+            .map_err(|e| anyhow::anyhow!("{hash_string} is not in the local blockstore: {e:?}"))?;
+        let num_blocks = header.blocks();
+        let file = header
+            .into_file()
+            .with_context(|| format!("{hash_string} is a directory, not a file."))?;
+        let mut tree = file
+            .hashtree()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read the hash tree: {e:?}"))?;
+
+        let mut writer: Box<dyn AsyncWrite + Unpin> = match &output {
+            Some(path) => Box::new(tokio::fs::File::create(path).await?),
+            None => Box::new(tokio::io::stdout()),
+        };
+        let mut hasher = fleek_blake3::Hasher::new();
+        for block in 0..num_blocks {
+            let block_hash = tree
+                .get_hash(block)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read the hash of block {block}: {e:?}"))?
+                .with_context(|| format!("Missing the hash of block {block}."))?;
+            let content = bucket
+                .get_block_content(&block_hash)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read block {block}: {e:?}"))?
+                .with_context(|| format!("Block {block} is not in the local blockstore."))?;
+            hasher.update(&content);
+            writer.write_all(&content).await?;
+        }
+        writer.flush().await?;
+
+        if *hasher.finalize().as_bytes() != hash {
+            anyhow::bail!("The content does not match the hash {hash_string}.");
+        }
+        Ok(())
+    }
+    .await;
+
+    if result.is_err() {
+        if let Some(path) = &output {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+    }
+
+    node.shutdown().await;
+
+    result
+}
+
+const METADATA_KEYS: [Metadata; 17] = [
+    Metadata::ChainId,
+    Metadata::Epoch,
+    Metadata::BlockNumber,
+    Metadata::SupplyYearStart,
+    Metadata::TotalSupply,
+    Metadata::ProtocolFundAddress,
+    Metadata::NextNodeIndex,
+    Metadata::GovernanceAddress,
+    Metadata::LastEpochHash,
+    Metadata::LastBlockHash,
+    Metadata::GenesisCommittee,
+    Metadata::SubDagIndex,
+    Metadata::SubDagRound,
+    Metadata::CommitteeSelectionBeaconPhase,
+    Metadata::EpochEra,
+    Metadata::WithdrawId,
+    Metadata::TimeInterval,
+];
+
+const PROTOCOL_PARAM_KEYS: [ProtocolParamKey; 22] = [
+    ProtocolParamKey::EpochTime,
+    ProtocolParamKey::CommitteeSize,
+    ProtocolParamKey::NodeCount,
+    ProtocolParamKey::MinimumNodeStake,
+    ProtocolParamKey::EligibilityTime,
+    ProtocolParamKey::LockTime,
+    ProtocolParamKey::ProtocolShare,
+    ProtocolParamKey::NodeShare,
+    ProtocolParamKey::ServiceBuilderShare,
+    ProtocolParamKey::MaxInflation,
+    ProtocolParamKey::MaxBoost,
+    ProtocolParamKey::MaxStakeLockTime,
+    ProtocolParamKey::MinNumMeasurements,
+    ProtocolParamKey::SGXSharedPubKey,
+    ProtocolParamKey::EpochsPerYear,
+    ProtocolParamKey::ReputationPingTimeout,
+    ProtocolParamKey::TopologyTargetK,
+    ProtocolParamKey::TopologyMinNodes,
+    ProtocolParamKey::CommitteeSelectionBeaconCommitPhaseDuration,
+    ProtocolParamKey::CommitteeSelectionBeaconRevealPhaseDuration,
+    ProtocolParamKey::CommitteeSelectionBeaconNonRevealSlashAmount,
+    ProtocolParamKey::TotalTimeIntervals,
+];
+
+async fn dump_state<C: NodeComponents<ConfigProviderInterface = TomlConfigProvider<C>>>(
+    config_path: ResolvedPathBuf,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let config = TomlConfigProvider::<C>::load(config_path)?;
+    let node = Node::<C>::init(config)
+        .map_err(|e| anyhow::anyhow!("Node Initialization failed: {e:?}"))
+        .context("Could not initialize the node.")?;
+
+    let query_runner = node.provider.get::<C::ApplicationInterface>().sync_query();
+    let state = collect_state(&query_runner);
+    node.shutdown().await;
+
+    let state = serde_json::to_string_pretty(&state?)?;
+    match output {
+        Some(path) => std::fs::write(path, state)?,
+        None => println!("{state}"),
+    }
+
+    Ok(())
+}
+
+fn collect_state<Q: SyncQueryRunnerInterface>(query_runner: &Q) -> Result<JsonValue> {
+    let nodes = query_runner.get_node_registry(None);
+
+    // The account table can't be iterated, so we only dump the accounts that own a node.
+    let mut accounts = Map::new();
+    for node in &nodes {
+        let owner = node.info.owner;
+        if let Some(account) = query_runner.get_account_info(&owner, |a| a) {
+            accounts.insert(owner.to_string(), serde_json::to_value(account)?);
+        }
+    }
+
+    let epoch = query_runner.get_current_epoch();
+    let committee = query_runner.get_committee_info(&epoch, |c| c);
+
+    let mut metadata = Map::new();
+    for key in METADATA_KEYS {
+        if let Some(value) = query_runner.get_metadata(&key) {
+            metadata.insert(format!("{key:?}"), serde_json::to_value(value)?);
+        }
+    }
+
+    let mut protocol_params = Map::new();
+    for key in PROTOCOL_PARAM_KEYS {
+        if let Some(value) = query_runner.get_protocol_param(&key) {
+            protocol_params.insert(format!("{key:?}"), serde_json::to_value(value)?);
+        }
+    }
+
+    let state_root = query_runner.get_state_root()?;
+
+    Ok(json!({
+        "state_root": state_root,
+        "epoch_info": query_runner.get_epoch_info(),
+        "committee": committee,
+        "nodes": nodes,
+        "accounts": accounts,
+        "metadata": metadata,
+        "protocol_params": protocol_params,
+    }))
+}
+
+fn parse_hash(hash_string: &str) -> Result<Blake3Hash> {
+    if hash_string.starts_with('[') {
+        let pat: &[_] = &['[', ']'];
+        let numbers: Vec<u8> = hash_string
+            .trim_matches(pat)
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().expect("expected number"))
+            .collect();
+
+        if numbers.len() != 32 {
+            anyhow::bail!("Failed to parse hash.");
+        }
+
+        let mut result = [0u8; 32];
+        result.copy_from_slice(&numbers);
+        Ok(result)
+    } else {
+        Ok(fleek_blake3::Hash::from_hex(hash_string.as_bytes())
+            .context("Invalid blake3 hash.")?
+            .into())
+    }
+}
+
 struct ByteBuf<'a>(&'a [u8]);
 
 impl std::fmt::LowerHex for ByteBuf<'_> {
@@ -1,3 +1,5 @@
+use std::io::stdin;
+
 use anyhow::Result;
 use lightning_interfaces::prelude::*;
 use lightning_utils::config::TomlConfigProvider;
@@ -16,5 +18,49 @@ pub async fn exec<C: NodeComponents>(cmd: KeySubCmd, config_path: ResolvedPathBu
             let mut g = C::build_graph();
             g.init_one::<C::KeystoreInterface>(&mut provider)
         },
+        KeySubCmd::Rotate { force } => rotate::<C>(config, force),
+    }
+}
+
+fn rotate<C: NodeComponents>(
+    config: <C::KeystoreInterface as ConfigConsumer>::Config,
+    force: bool,
+) -> Result<()> {
+    if !force {
+        println!(
+            "Rotating the keys will replace the keys your node is staked with. Do you want to continue? (y/N)"
+        );
+        if !get_user_confirmation()? {
+            println!("Not rotating the keys.");
+            return Ok(());
+        }
+    }
+
+    let keys = C::KeystoreInterface::rotate_keys(config)?;
+
+    match keys.old_node_pk {
+        Some(pk) => println!("Old node public key: {pk}"),
+        None => println!("Old node public key: none"),
+    }
+    match keys.old_consensus_pk {
+        Some(pk) => println!("Old consensus public key: {pk}"),
+        None => println!("Old consensus public key: none"),
+    }
+    println!("New node public key: {}", keys.new_node_pk);
+    println!("New consensus public key: {}", keys.new_consensus_pk);
+    println!("Remember to stake with the new keys before restarting your node.");
+
+    Ok(())
+}
+
+fn get_user_confirmation() -> Result<bool> {
+    loop {
+        let mut input = String::new();
+        stdin().read_line(&mut input)?;
+        match input.trim_end() {
+            "y" => return Ok(true),
+            "N" | "" => return Ok(false),
+            _ => println!("Invalid input, please type `y` for yes or `N` for no, and hit ENTER."),
+        }
     }
 }
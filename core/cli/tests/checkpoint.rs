@@ -337,6 +337,7 @@ fn build_node_config(
             .join("data/narwhal_store")
             .try_into()
             .expect("Failed to resolve path"),
+        ..Default::default()
     });
 
     config.inject::<Keystore<FullNodeComponents>>(keystore_config);
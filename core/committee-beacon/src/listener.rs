@@ -57,20 +57,34 @@ pub async fn new(
     pub async fn start(&self) -> Result<(), CommitteeBeaconError> {
         tracing::debug!("starting committee beacon listener");
 
-        // Subscribe to notifications for executed blocks.
+        // Subscribe to notifications for executed blocks and committee changes. The committee
+        // membership is only queried once, and then kept up to date by the notifications.
         let mut block_sub = self.notifier.subscribe_block_executed();
+        let mut committee_sub = self.notifier.subscribe_committee_changed();
+        let mut on_committee = self
+            .app_query
+            .get_committee_members_by_index()
+            .contains(&self.node_index);
         loop {
-            let notification = block_sub.recv().await;
-
-            // Check that the notifier is still running.
-            let Some(notification) = notification else {
-                tracing::debug!("notifier is not running, shutting down");
-                break;
-            };
-            let response = notification.response;
-
-            // Handle the executed block.
-            self.handle_executed_block(response).await?;
+            tokio::select! {
+                // The committee change is emitted right after the block that caused it, so it has
+                // to be handled before the blocks that follow.
+                biased;
+                Some(notification) = committee_sub.recv() => {
+                    on_committee = notification.new_committee.contains(&self.node_index);
+                },
+                notification = block_sub.recv() => {
+                    // Check that the notifier is still running.
+                    let Some(notification) = notification else {
+                        tracing::debug!("notifier is not running, shutting down");
+                        break;
+                    };
+                    let response = notification.response;
+
+                    // Handle the executed block.
+                    self.handle_executed_block(response, on_committee).await?;
+                },
+            }
         }
 
         tracing::debug!("shutdown committee beacon listener");
@@ -80,6 +94,7 @@ pub async fn start(&self) -> Result<(), CommitteeBeaconError> {
     async fn handle_executed_block(
         &self,
         response: BlockExecutionResponse,
+        on_committee: bool,
     ) -> Result<(), CommitteeBeaconError> {
         tracing::trace!("handling block execution response: {:?}", response);
 
@@ -101,8 +116,6 @@ async fn handle_executed_block(
         }
 
         // The node only submits the commit and reveal transactions if it is on the committee.
-        let committee = self.app_query.get_committee_members_by_index();
-        let on_committee = committee.contains(&self.node_index);
         if !on_committee {
             return Ok(());
         }
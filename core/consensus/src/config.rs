@@ -3,9 +3,13 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConsensusConfig {
     /// Path to the database used by the narwhal implementation.
     pub store_path: ResolvedPathBuf,
+    /// Whether to persist the parcels and attestations that were received but not executed yet,
+    /// so that a restarted node doesn't have to request them again.
+    pub persist_transaction_store: bool,
 }
 
 impl Default for ConsensusConfig {
@@ -15,6 +19,7 @@ fn default() -> Self {
                 .join("data/narwhal_store")
                 .try_into()
                 .expect("Failed to resolve path"),
+            persist_transaction_store: false,
         }
     }
 }
@@ -6,7 +6,7 @@
 use fleek_crypto::SecretKey;
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::Topic;
-use lightning_interfaces::Events;
+use lightning_interfaces::{Events, ExecutionProgress};
 use mysten_metrics::RegistryService;
 use narwhal_crypto::traits::KeyPair as _;
 use narwhal_crypto::{KeyPair, NetworkKeyPair};
@@ -14,7 +14,7 @@
 use ready::tokio::TokioReadyWaiter;
 use ready::ReadyWaiter;
 use serde::{Deserialize, Serialize};
-use tokio::sync::{mpsc, oneshot, Notify};
+use tokio::sync::{mpsc, oneshot, watch, Notify};
 use tokio::{pin, select};
 use typed_store::DBMetrics;
 
@@ -44,6 +44,8 @@ pub struct Consensus<C: NodeComponents> {
     shutdown_notify_epoch_state: Arc<Notify>,
     /// To notify the epoch state when consensus is ready
     ready: ConsensusReadyWaiter,
+    /// The progress of the execution of the parcels received from the committee.
+    execution_progress: watch::Receiver<ExecutionProgress>,
 }
 
 impl<C: NodeComponents> Consensus<C> {
@@ -127,6 +129,10 @@ impl<C: NodeComponents> ConsensusInterface<C> for Consensus<C> {
     async fn wait_for_ready(&self) -> Self::ReadyState {
         self.ready.wait().await
     }
+
+    fn execution_progress(&self) -> watch::Receiver<ExecutionProgress> {
+        self.execution_progress.clone()
+    }
 }
 
 impl<C: NodeComponents> Consensus<C> {
@@ -150,7 +156,7 @@ fn init(
         DBMetrics::init(&registry);
 
         let (consensus_sk, primary_sk) = (keystore.get_bls_sk(), keystore.get_ed25519_sk());
-        let (consensus_pk, primary_pk) = (consensus_sk.to_pk(), primary_sk.to_pk());
+        let consensus_pk = consensus_sk.to_pk();
         let reconfigure_notify = Arc::new(Notify::new());
         let networking_keypair = NetworkKeyPair::from(Ed25519PrivateKey::from(&primary_sk));
         let primary_keypair = KeyPair::from(BLS12381PrivateKey::from(&consensus_sk));
@@ -164,6 +170,8 @@ fn init(
         // Todo(dalton): Figure out better default channel size
         let (consensus_output_tx, consensus_output_rx) = mpsc::channel(1000);
         let (event_tx_tx, event_tx_rx) = oneshot::channel();
+        let (execution_progress_tx, execution_progress) =
+            watch::channel(ExecutionProgress::default());
 
         let shutdown_notify_epoch_state = Arc::new(Notify::new());
 
@@ -171,17 +179,19 @@ fn init(
 
         let epoch_state = EpochState::new(
             executor,
-            primary_pk,
+            primary_sk,
             consensus_pk,
             query_runner,
             narwhal_args,
             config.store_path,
+            config.persist_transaction_store,
             signer.get_socket(),
             notifier.get_emitter(),
             pubsub,
             consensus_output_tx,
             consensus_output_rx,
             event_tx_rx,
+            execution_progress_tx,
             shutdown_notify_epoch_state.clone(),
             ready.clone(),
         );
@@ -192,6 +202,7 @@ fn init(
             reconfigure_notify,
             shutdown_notify_epoch_state,
             ready,
+            execution_progress,
         })
     }
 
@@ -209,6 +220,12 @@ pub enum PubSubMsg {
     Transactions(AuthenticStampedParcel),
     Attestation(CommitteeAttestation),
     RequestTransactions(Digest),
+    /// Requests the parcels that lead from `from` (exclusive) to `to` (inclusive), so that a
+    /// node that fell behind can recover a gap in the parcel chain in one round.
+    RequestChainSegment {
+        from: Digest,
+        to: Digest,
+    },
 }
 
 impl AutoImplSerde for PubSubMsg {}
@@ -5,7 +5,7 @@
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use fleek_crypto::{ConsensusPublicKey, NodePublicKey};
+use fleek_crypto::{ConsensusPublicKey, NodePublicKey, NodeSecretKey, SecretKey};
 use gethostname::gethostname;
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::{
@@ -15,7 +15,7 @@
     EpochInfo,
     UpdateMethod,
 };
-use lightning_interfaces::Events;
+use lightning_interfaces::{Events, ExecutionProgress};
 use lightning_utils::application::QueryRunnerExt;
 use mysten_network::Multiaddr;
 use narwhal_config::{Committee, CommitteeBuilder, WorkerCache, WorkerIndex, WorkerInfo};
@@ -26,7 +26,7 @@
 use resolved_pathbuf::ResolvedPathBuf;
 use tokio::sync::futures::Notified;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::{oneshot, Notify};
+use tokio::sync::{oneshot, watch, Notify};
 use tokio::{pin, task, time};
 use tracing::{error, info};
 use types::EpochEra;
@@ -36,10 +36,20 @@
 use crate::execution::worker::ExecutionWorker;
 use crate::narwhal::{NarwhalArgs, NarwhalService};
 
+/// The file in the store directory where the execution worker persists its estimate of the time
+/// between parcel executions. It is not named after an epoch, so it is never garbage collected.
+const TBE_FILE_NAME: &str = "execution_tbe";
+
+/// The directory in the store directory where the execution worker persists its transaction
+/// store, if enabled.
+const TXN_STORE_DIR_NAME: &str = "transaction_store";
+
 /// This struct contains mutable state only for the current epoch.
 pub struct EpochState<Q: SyncQueryRunnerInterface, P: PubSub<PubSubMsg> + 'static, NE: Emitter> {
     /// Execute transactions.
     executor: ExecutionEngineSocket,
+    /// The node secret key of the node, used to sign parcel attestations.
+    node_secret_key: NodeSecretKey,
     /// The node public key of the node.
     node_public_key: NodePublicKey,
     /// The consensus public key of the node.
@@ -54,6 +64,8 @@ pub struct EpochState<Q: SyncQueryRunnerInterface, P: PubSub<PubSubMsg> + 'stati
     notifier: NE,
     /// Path to the database used by the narwhal implementation
     pub store_path: ResolvedPathBuf,
+    /// Whether the execution worker persists its transaction store.
+    persist_transaction_store: bool,
     /// Used to send transactions to consensus
     /// We still use this socket on consensus struct because a node is not always on the committee,
     /// so its not always sending     a transaction to its own mempool. The signer interface
@@ -67,6 +79,8 @@ pub struct EpochState<Q: SyncQueryRunnerInterface, P: PubSub<PubSubMsg> + 'stati
     consensus_output_rx: Option<Receiver<FilteredConsensusOutput>>,
     /// Receive the rpc event sender in the execution worker.
     event_tx_rx: Option<oneshot::Receiver<Events>>,
+    /// Publish the execution progress from the execution worker.
+    execution_progress_tx: Option<watch::Sender<ExecutionProgress>>,
     /// To notify when consensus is shutting down.
     shutdown_notify: Arc<Notify>,
     /// To notify when consensus is ready.
@@ -79,34 +93,39 @@ impl<Q: SyncQueryRunnerInterface, P: PubSub<PubSubMsg> + 'static, NE: Emitter>
 {
     pub fn new(
         executor: ExecutionEngineSocket,
-        node_public_key: NodePublicKey,
+        node_secret_key: NodeSecretKey,
         consensus_public_key: ConsensusPublicKey,
         query_runner: Q,
         narwhal_args: NarwhalArgs,
         store_path: ResolvedPathBuf,
+        persist_transaction_store: bool,
         txn_socket: SignerSubmitTxSocket,
         notifier: NE,
         pub_sub: P,
         consensus_output_tx: Sender<FilteredConsensusOutput>,
         consensus_output_rx: Receiver<FilteredConsensusOutput>,
         event_tx_rx: oneshot::Receiver<Events>,
+        execution_progress_tx: watch::Sender<ExecutionProgress>,
         shutdown_notify: Arc<Notify>,
         ready: ConsensusReadyWaiter,
     ) -> Self {
         Self {
             executor,
-            node_public_key,
+            node_public_key: node_secret_key.to_pk(),
+            node_secret_key,
             consensus_public_key,
             consensus: None,
             query_runner,
             narwhal_args,
             notifier,
             store_path,
+            persist_transaction_store,
             txn_socket,
             pub_sub,
             consensus_output_tx,
             consensus_output_rx: Some(consensus_output_rx),
             event_tx_rx: Some(event_tx_rx),
+            execution_progress_tx: Some(execution_progress_tx),
             shutdown_notify,
             ready,
         }
@@ -120,14 +139,16 @@ pub fn spawn_execution_worker(&mut self, reconfigure_notify: Arc<Notify>) -> Exe
                 .expect("consensus_output_rx is missing"),
             self.pub_sub.clone(),
             self.query_runner.clone(),
-            self.narwhal_args
-                .primary_network_keypair
-                .public()
-                .to_owned()
-                .into(),
+            self.node_secret_key.clone(),
             reconfigure_notify,
             self.notifier.clone(),
             self.event_tx_rx.take().expect("event_tx_rx is missing"),
+            self.execution_progress_tx
+                .take()
+                .expect("execution_progress_tx is missing"),
+            self.store_path.join(TBE_FILE_NAME),
+            self.persist_transaction_store
+                .then(|| self.store_path.join(TXN_STORE_DIR_NAME)),
         )
     }
 
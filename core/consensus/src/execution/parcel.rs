@@ -1,5 +1,6 @@
 use fastcrypto::hash::HashFunction;
 use fleek_blake3 as blake3;
+use fleek_crypto::{NodePublicKey, NodeSecretKey, NodeSignature, PublicKey, SecretKey};
 use lightning_interfaces::types::{Epoch, NodeIndex};
 use lightning_interfaces::{ToDigest, TranscriptBuilder};
 use narwhal_crypto::DefaultHashFunction;
@@ -8,6 +9,8 @@
 
 pub type Digest = [u8; 32];
 
+const PARCEL_DOMAIN: &str = "fleek_network_consensus_parcel";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AuthenticStampedParcel {
     pub transactions: Vec<Transaction>,
@@ -19,21 +22,16 @@ pub struct AuthenticStampedParcel {
 
 impl ToDigest for AuthenticStampedParcel {
     fn transcript(&self) -> TranscriptBuilder {
-        panic!("We don't need this here");
-    }
-
-    fn to_digest(&self) -> Digest {
         let batch_digest =
             BatchDigest::new(DefaultHashFunction::digest_iterator(self.transactions.iter()).into());
 
-        let mut bytes = Vec::new();
-        bytes.extend_from_slice(&(self.transactions.len() as u32).to_le_bytes());
-        bytes.extend_from_slice(&batch_digest.0);
-        bytes.extend_from_slice(&self.last_executed);
-        bytes.extend_from_slice(&self.sub_dag_index.to_le_bytes());
-        bytes.extend_from_slice(&self.sub_dag_round.to_le_bytes());
-
-        blake3::hash(&bytes).into()
+        TranscriptBuilder::empty(PARCEL_DOMAIN)
+            .with("transaction_count", &(self.transactions.len() as u32))
+            .with("batch_digest", &batch_digest.0)
+            .with("last_executed", &self.last_executed)
+            .with("epoch", &self.epoch)
+            .with("sub_dag_index", &self.sub_dag_index)
+            .with("sub_dag_round", &self.sub_dag_round)
     }
 }
 
@@ -43,8 +41,38 @@ fn to_digest(&self) -> Digest {
 pub struct CommitteeAttestation {
     /// The digest we are attesting is correct
     pub digest: Digest,
-    /// We send random bytes with this message so it gives it a unique hash and differentiates it
-    /// from the other committee members attestation broadcasts
+    /// The index of the committee member that is attesting. This also gives the message a unique
+    /// hash and differentiates it from the other committee members attestation broadcasts
     pub node_index: NodeIndex,
     pub epoch: Epoch,
+    /// The committee member's signature over `(digest, epoch, node_index)`
+    pub signature: NodeSignature,
+}
+
+impl CommitteeAttestation {
+    /// Create an attestation for the given parcel digest signed with the node's secret key.
+    pub fn new(digest: Digest, node_index: NodeIndex, epoch: Epoch, sk: &NodeSecretKey) -> Self {
+        let signature = sk.sign(&Self::signing_digest(&digest, node_index, epoch));
+        Self {
+            digest,
+            node_index,
+            epoch,
+            signature,
+        }
+    }
+
+    /// Returns true if the attestation was signed by the owner of the given public key.
+    pub fn verify(&self, pk: &NodePublicKey) -> bool {
+        let digest = Self::signing_digest(&self.digest, self.node_index, self.epoch);
+        pk.verify(&self.signature, &digest).unwrap_or(false)
+    }
+
+    fn signing_digest(digest: &Digest, node_index: NodeIndex, epoch: Epoch) -> Digest {
+        let mut bytes = Vec::with_capacity(44);
+        bytes.extend_from_slice(digest);
+        bytes.extend_from_slice(&epoch.to_le_bytes());
+        bytes.extend_from_slice(&node_index.to_le_bytes());
+
+        blake3::hash(&bytes).into()
+    }
 }
@@ -1,8 +1,16 @@
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
 
 use lightning_interfaces::prelude::*;
-use lightning_interfaces::types::{Digest as BroadcastDigest, NodeIndex};
+use lightning_interfaces::types::{Digest as BroadcastDigest, Epoch, NodeIndex};
+use lightning_metrics::increment_counter;
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
 
 use super::parcel::{AuthenticStampedParcel, Digest};
 use crate::consensus::PubSubMsg;
@@ -14,7 +22,7 @@ pub struct ParcelWrapper<T: BroadcastEventInterface<PubSubMsg>> {
     pub(crate) attestation_events: Option<HashMap<NodeIndex, T>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Parcel {
     pub inner: AuthenticStampedParcel,
     // The originator of this parcel.
@@ -25,12 +33,167 @@ pub struct Parcel {
     pub message_digest: Option<BroadcastDigest>,
 }
 
+// Two distinct parcels that both claim to extend the same parcel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParcelFork {
+    pub last_executed: Digest,
+    pub first: Digest,
+    pub second: Digest,
+}
+
+/// Returns the number of attestations needed to execute a parcel (2f + 1 of the committee).
+pub fn quorum_threshold(committee_size: usize) -> usize {
+    (committee_size * 2) / 3 + 1
+}
+
 pub struct TransactionStore<T: BroadcastEventInterface<PubSubMsg>> {
     ring: Vec<HashMap<Digest, ParcelWrapper<T>>>,
+    // The quorum threshold of the committee of each epoch in the ring. Attestations are always
+    // checked against the threshold of the epoch they were stored for, even if the committee
+    // changed since.
+    quorum_thresholds: Vec<Option<usize>>,
+    // Maps the `last_executed` digest of a parcel to the digest of the first parcel we have seen
+    // extending it. This is used to detect forks in the parcel chain.
+    parents: Vec<HashMap<Digest, Digest>>,
+    // The forks detected in the current epoch.
+    forks: Vec<ParcelFork>,
     pointer: usize,
+    persistence: Option<Persistence>,
+}
+
+// The file in the persistence directory that records which epoch and ring position the persisted
+// messages belong to.
+const PERSISTED_EPOCH_FILE_NAME: &str = "epoch";
+
+// The extension of the temporary files that are renamed over the persisted files, so that a
+// crash never leaves a partially written file behind.
+const TEMP_FILE_EXTENSION: &str = "tmp";
+
+// Where the store persists its parcels and attestations. Every slot of the ring is persisted in a
+// directory named after its index, with one file per digest.
+// The files are written by a dedicated thread, so that the worker never blocks on disk IO.
+struct Persistence {
+    // The epoch of the current slot of the ring.
+    epoch: Epoch,
+    writes: Option<mpsc::Sender<PersistOp>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    digest: Digest,
+    parcel: Option<Parcel>,
+    attestations: Option<HashSet<NodeIndex>>,
+}
+
+enum PersistOp {
+    // Writes a single entry of a slot.
+    Entry {
+        slot: usize,
+        entry: PersistedEntry,
+    },
+    // Replaces all the entries of a slot.
+    Slot {
+        slot: usize,
+        entries: Vec<PersistedEntry>,
+    },
+    // Records which epoch and ring position the persisted slots belong to.
+    Epoch {
+        epoch: Epoch,
+        pointer: usize,
+    },
+}
+
+impl Persistence {
+    fn new(path: PathBuf, epoch: Epoch) -> Self {
+        let (writes, rx) = mpsc::channel::<PersistOp>();
+        let writer = thread::Builder::new()
+            .name("consensus-txn-store".into())
+            .spawn(move || {
+                for op in rx {
+                    if let Err(e) = op.apply(&path) {
+                        error!("Failed to persist the transaction store: {e:?}");
+                    }
+                }
+            })
+            .expect("Failed to spawn the transaction store writer");
+        Self {
+            epoch,
+            writes: Some(writes),
+            writer: Some(writer),
+        }
+    }
+
+    fn send(&self, op: PersistOp) {
+        if let Some(writes) = &self.writes {
+            if writes.send(op).is_err() {
+                error!("The transaction store writer stopped");
+            }
+        }
+    }
+}
+
+impl Drop for Persistence {
+    // Waits for the pending writes, so that a store loaded afterwards sees all of them.
+    fn drop(&mut self) {
+        self.writes.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl PersistOp {
+    fn apply(self, path: &Path) -> anyhow::Result<()> {
+        match self {
+            PersistOp::Entry { slot, entry } => {
+                let dir = path.join(slot.to_string());
+                fs::create_dir_all(&dir)?;
+                write_entry(&dir, &entry)?;
+            },
+            PersistOp::Slot { slot, entries } => {
+                let dir = path.join(slot.to_string());
+                fs::create_dir_all(&dir)?;
+                let mut file_names = HashSet::with_capacity(entries.len());
+                for entry in &entries {
+                    file_names.insert(write_entry(&dir, entry)?);
+                }
+                // Remove the entries that are no longer in the slot.
+                for file in fs::read_dir(&dir)? {
+                    let file = file?;
+                    if !file_names.contains(&file.file_name().to_string_lossy().into_owned()) {
+                        fs::remove_file(file.path())?;
+                    }
+                }
+            },
+            PersistOp::Epoch { epoch, pointer } => {
+                fs::create_dir_all(path)?;
+                write_atomic(
+                    &path.join(PERSISTED_EPOCH_FILE_NAME),
+                    &bincode::serialize(&(epoch, pointer))?,
+                )?;
+            },
+        }
+        Ok(())
+    }
 }
 
 impl<T: BroadcastEventInterface<PubSubMsg>> TransactionStore<T> {
+    // Creates a store that persists its parcels and attestations under `path`, and loads the ones
+    // that were persisted before a restart. Persisted messages from another epoch are discarded.
+    // The broadcast events of messages from the next epoch are not persisted, so these messages
+    // are no longer propagated after a restart.
+    pub fn load(path: PathBuf, epoch: Epoch) -> Self {
+        let mut store = Self::default();
+        if let Err(e) = store.load_persisted(&path, epoch) {
+            warn!("Failed to load the persisted transaction store: {e:?}");
+            store = Self::default();
+        }
+        store.persistence = Some(Persistence::new(path, epoch));
+        store.persist_all();
+        store
+    }
+
     // Returns the parcel for the given digest, if it exists.
     // If the parcel does not exist for the current epoch, we will check for parcels from the
     // previous epoch.
@@ -55,6 +218,50 @@ pub fn get_attestations(&self, digest: &Digest) -> Option<&HashSet<NodeIndex>> {
                 .and_then(|wrapper| wrapper.attestations.as_ref()))
     }
 
+    // Returns the parcels that lead from `from` (exclusive) to `to` (inclusive), walking the
+    // parcel chain backwards from `to`. At most `max_len` parcels are returned, and the walk stops
+    // early at the first parcel we don't have.
+    pub fn get_chain_segment(&self, from: &Digest, to: &Digest, max_len: usize) -> Vec<&Parcel> {
+        let mut segment = Vec::new();
+        let mut current = *to;
+        while current != *from && segment.len() < max_len {
+            let Some(parcel) = self.get_parcel(&current) else {
+                break;
+            };
+            current = parcel.inner.last_executed;
+            segment.push(parcel);
+        }
+        segment
+    }
+
+    // Returns true if the parcel with the given digest was attested by a quorum of the committee
+    // of the epoch the attestations belong to.
+    pub fn has_quorum(&self, digest: &Digest) -> bool {
+        [self.pointer, self.prev_pointer()]
+            .into_iter()
+            .find_map(|pointer| {
+                let attestations = self.ring[pointer].get(digest)?.attestations.as_ref()?;
+                Some((attestations.len(), self.quorum_thresholds[pointer]?))
+            })
+            .is_some_and(|(attestations, threshold)| attestations >= threshold)
+    }
+
+    // Returns true if we store the parcel with the given digest.
+    pub fn contains_parcel(&self, digest: &Digest) -> bool {
+        self.get_parcel(digest).is_some()
+    }
+
+    // Sets the committee of the current epoch. This is used on startup and when the committee
+    // changes mid-epoch, the committee of the next epoch is set by `change_epoch`.
+    pub fn set_committee(&mut self, committee: &[NodeIndex]) {
+        self.quorum_thresholds[self.pointer] = Some(quorum_threshold(committee.len()));
+    }
+
+    // Returns the forks in the parcel chain that were detected in the current epoch.
+    pub fn forks(&self) -> &[ParcelFork] {
+        &self.forks
+    }
+
     // Store a parcel from the current epoch.
     pub fn store_parcel(
         &mut self,
@@ -62,7 +269,10 @@ pub fn store_parcel(
         originator: NodeIndex,
         message_digest: Option<BroadcastDigest>,
     ) {
-        self.store_parcel_internal(self.pointer, parcel, originator, message_digest, None);
+        let last_executed = parcel.last_executed;
+        let digest =
+            self.store_parcel_internal(self.pointer, parcel, originator, message_digest, None);
+        self.track_parent(self.pointer, last_executed, digest);
     }
 
     // Store a parcel from the next epoch. These parcels will be verified once the epoch changes.
@@ -147,7 +357,34 @@ pub fn change_epoch(&mut self, committee: &[NodeIndex]) {
         let prev_pointer = self.prev_pointer();
         // Clear previous epoch map, because this will become the next epoch map
         self.ring[prev_pointer].clear();
+        self.parents[prev_pointer].clear();
+        self.quorum_thresholds[prev_pointer] = None;
+        self.forks.clear();
+
+        // Now that the parcels from the next epoch are validated, we can check them for forks.
+        let valid_parcels: Vec<_> = self.ring[next_pointer]
+            .iter()
+            .filter_map(|(digest, wrapper)| {
+                wrapper
+                    .parcel
+                    .as_ref()
+                    .map(|parcel| (parcel.inner.last_executed, *digest))
+            })
+            .collect();
+        for (last_executed, digest) in valid_parcels {
+            self.track_parent(next_pointer, last_executed, digest);
+        }
+
         self.pointer = self.next_pointer();
+        self.set_committee(committee);
+
+        if let Some(persistence) = &mut self.persistence {
+            persistence.epoch += 1;
+        }
+        // Only the slots of the new current and next epoch changed.
+        self.persist_slot(self.pointer);
+        self.persist_slot(self.next_pointer());
+        self.persist_epoch();
     }
 
     // Store a parcel and optionally provide the digest of the broadcast message that delivered
@@ -161,7 +398,7 @@ fn store_parcel_internal(
         originator: NodeIndex,
         message_digest: Option<BroadcastDigest>,
         event: Option<T>,
-    ) {
+    ) -> Digest {
         let digest = parcel.to_digest();
         // We are explicitly matching the entry here instead of using `and_modify` together with
         // `or_insert` in order to avoid cloning the parcel.
@@ -193,6 +430,38 @@ fn store_parcel_internal(
                 },
             },
         }
+        self.persist(pointer, &digest);
+        digest
+    }
+
+    // Remember which parcel extends `last_executed`. If we already know of a different parcel
+    // that extends `last_executed`, the parcel chain has forked. We don't try to resolve the
+    // fork, we only record it.
+    fn track_parent(&mut self, pointer: usize, last_executed: Digest, digest: Digest) {
+        match self.parents[pointer].entry(last_executed) {
+            Entry::Vacant(entry) => {
+                entry.insert(digest);
+            },
+            Entry::Occupied(entry) if *entry.get() != digest => {
+                let fork = ParcelFork {
+                    last_executed,
+                    first: *entry.get(),
+                    second: digest,
+                };
+                warn!(
+                    last_executed = ?fork.last_executed,
+                    first = ?fork.first,
+                    second = ?fork.second,
+                    "Detected a fork in the parcel chain"
+                );
+                increment_counter!(
+                    "consensus_fork_detected",
+                    Some("Number of parcels that extend a parcel that was already extended")
+                );
+                self.forks.push(fork);
+            },
+            Entry::Occupied(_) => {},
+        }
     }
 
     fn store_attestation_internal(
@@ -218,6 +487,111 @@ fn store_attestation_internal(
                 parcel_event: None,
                 attestation_events: event.map(|t| std::iter::once((node_index, t)).collect()),
             });
+        self.persist(pointer, &digest);
+    }
+
+    fn load_persisted(&mut self, path: &Path, epoch: Epoch) -> anyhow::Result<()> {
+        let (persisted_epoch, pointer): (Epoch, usize) =
+            match fs::read(path.join(PERSISTED_EPOCH_FILE_NAME)) {
+                Ok(bytes) => bincode::deserialize(&bytes)?,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+        if persisted_epoch != epoch || pointer >= self.ring.len() {
+            return Ok(());
+        }
+
+        self.pointer = pointer;
+        for slot in 0..self.ring.len() {
+            let entries = match fs::read_dir(path.join(slot.to_string())) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for entry in entries {
+                let path = entry?.path();
+                // Skip the files that were not completely written before a crash.
+                if path
+                    .extension()
+                    .is_some_and(|ext| ext == TEMP_FILE_EXTENSION)
+                {
+                    continue;
+                }
+                let entry: PersistedEntry = bincode::deserialize(&fs::read(path)?)?;
+                self.ring[slot].insert(
+                    entry.digest,
+                    ParcelWrapper {
+                        parcel: entry.parcel,
+                        attestations: entry.attestations,
+                        parcel_event: None,
+                        attestation_events: None,
+                    },
+                );
+            }
+        }
+
+        let parents: Vec<_> = self.ring[self.pointer]
+            .iter()
+            .filter_map(|(digest, wrapper)| {
+                wrapper
+                    .parcel
+                    .as_ref()
+                    .map(|parcel| (parcel.inner.last_executed, *digest))
+            })
+            .collect();
+        for (last_executed, digest) in parents {
+            self.track_parent(self.pointer, last_executed, digest);
+        }
+        Ok(())
+    }
+
+    // Persists the entry for the given digest, if persistence is enabled.
+    fn persist(&self, pointer: usize, digest: &Digest) {
+        let (Some(persistence), Some(wrapper)) =
+            (&self.persistence, self.ring[pointer].get(digest))
+        else {
+            return;
+        };
+        persistence.send(PersistOp::Entry {
+            slot: pointer,
+            entry: persisted_entry(digest, wrapper),
+        });
+    }
+
+    // Replaces the persisted entries of the given slot with its current content, if persistence
+    // is enabled.
+    fn persist_slot(&self, pointer: usize) {
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        persistence.send(PersistOp::Slot {
+            slot: pointer,
+            entries: self.ring[pointer]
+                .iter()
+                .map(|(digest, wrapper)| persisted_entry(digest, wrapper))
+                .collect(),
+        });
+    }
+
+    // Persists the epoch and ring position of the persisted slots, if persistence is enabled.
+    // This is sent after the slots, so that a restart never pairs the slots with a newer epoch.
+    fn persist_epoch(&self) {
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        persistence.send(PersistOp::Epoch {
+            epoch: persistence.epoch,
+            pointer: self.pointer,
+        });
+    }
+
+    // Replaces everything that was persisted with the current content of the store, if
+    // persistence is enabled.
+    fn persist_all(&self) {
+        for pointer in 0..self.ring.len() {
+            self.persist_slot(pointer);
+        }
+        self.persist_epoch();
     }
 
     fn next_pointer(&self) -> usize {
@@ -241,7 +615,352 @@ fn default() -> Self {
                 HashMap::with_capacity(100),
                 HashMap::with_capacity(100),
             ],
+            parents: vec![HashMap::new(), HashMap::new(), HashMap::new()],
+            quorum_thresholds: vec![None; 3],
+            forks: Vec::new(),
             pointer: 1,
+            persistence: None,
+        }
+    }
+}
+
+fn persisted_entry<T: BroadcastEventInterface<PubSubMsg>>(
+    digest: &Digest,
+    wrapper: &ParcelWrapper<T>,
+) -> PersistedEntry {
+    PersistedEntry {
+        digest: *digest,
+        parcel: wrapper.parcel.clone(),
+        attestations: wrapper.attestations.clone(),
+    }
+}
+
+// Writes the entry to the given slot directory and returns the name of its file.
+fn write_entry(dir: &Path, entry: &PersistedEntry) -> anyhow::Result<String> {
+    let file_name: String = entry
+        .digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    write_atomic(&dir.join(&file_name), &bincode::serialize(entry)?)?;
+    Ok(file_name)
+}
+
+// Writes the file to a temporary path first and renames it over the destination, so that the
+// destination always holds either the old or the new content.
+fn write_atomic(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let temp_path = path.with_extension(TEMP_FILE_EXTENSION);
+    let mut file = File::create(&temp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    const PENDING: u8 = 0;
+    const PROPAGATED: u8 = 1;
+    const INVALID: u8 = 2;
+
+    struct TestEvent {
+        originator: NodeIndex,
+        outcome: Arc<AtomicU8>,
+    }
+
+    impl BroadcastEventInterface<PubSubMsg> for TestEvent {
+        fn originator(&self) -> NodeIndex {
+            self.originator
+        }
+
+        fn take(&mut self) -> Option<PubSubMsg> {
+            None
+        }
+
+        fn propagate(self) {
+            self.outcome.store(PROPAGATED, Ordering::Relaxed);
         }
+
+        fn mark_invalid_sender(self) {
+            self.outcome.store(INVALID, Ordering::Relaxed);
+        }
+
+        fn get_digest(&self) -> BroadcastDigest {
+            [0; 32]
+        }
+    }
+
+    fn event(originator: NodeIndex) -> (TestEvent, Arc<AtomicU8>) {
+        let outcome = Arc::new(AtomicU8::new(PENDING));
+        let event = TestEvent {
+            originator,
+            outcome: outcome.clone(),
+        };
+        (event, outcome)
+    }
+
+    fn parcel(sub_dag_index: u64) -> AuthenticStampedParcel {
+        AuthenticStampedParcel {
+            transactions: Vec::new(),
+            last_executed: [0; 32],
+            epoch: 1,
+            sub_dag_index,
+            sub_dag_round: 0,
+        }
+    }
+
+    #[test]
+    fn test_store_parcel_detects_fork() {
+        let mut store = TransactionStore::<TestEvent>::default();
+
+        // Given: a parcel that extends the head.
+        let first = parcel(0);
+        let first_digest = first.to_digest();
+        store.store_parcel(first.clone(), 1, None);
+        assert!(store.forks().is_empty());
+
+        // When: the same parcel is stored again.
+        store.store_parcel(first, 2, None);
+
+        // Then: no fork is detected.
+        assert!(store.forks().is_empty());
+
+        // When: a different parcel that extends the same head is stored.
+        let second = parcel(1);
+        let second_digest = second.to_digest();
+        store.store_parcel(second, 2, None);
+
+        // Then: the fork is detected.
+        assert_eq!(
+            store.forks(),
+            &[ParcelFork {
+                last_executed: [0; 32],
+                first: first_digest,
+                second: second_digest,
+            }]
+        );
+
+        // When: the epoch changes.
+        store.change_epoch(&[1, 2]);
+
+        // Then: the forks from the previous epoch are forgotten.
+        assert!(store.forks().is_empty());
+    }
+
+    #[test]
+    fn test_change_epoch_validates_pending_messages() {
+        let mut store = TransactionStore::<TestEvent>::default();
+        let committee = [1, 2, 3];
+
+        // Given: parcels from the next epoch sent by a committee member and an outsider.
+        let valid_parcel = parcel(0);
+        let valid_digest = valid_parcel.to_digest();
+        let (valid_event, valid_outcome) = event(1);
+        store.store_pending_parcel(valid_parcel, 1, None, valid_event);
+
+        let invalid_parcel = parcel(1);
+        let invalid_digest = invalid_parcel.to_digest();
+        let (invalid_event, invalid_outcome) = event(9);
+        store.store_pending_parcel(invalid_parcel, 9, None, invalid_event);
+
+        // Given: attestations from the next epoch sent by a committee member and an outsider.
+        let (valid_att_event, valid_att_outcome) = event(2);
+        store.store_pending_attestation(valid_digest, 2, valid_att_event);
+        let (invalid_att_event, invalid_att_outcome) = event(8);
+        store.store_pending_attestation(invalid_digest, 8, invalid_att_event);
+
+        // Then: none of them are visible before the epoch changes.
+        assert!(store.get_parcel(&valid_digest).is_none());
+        assert!(store.get_attestations(&valid_digest).is_none());
+
+        // When: the epoch changes.
+        store.change_epoch(&committee);
+
+        // Then: only the messages from the committee are kept and propagated.
+        assert!(store.get_parcel(&valid_digest).is_some());
+        assert!(store.get_parcel(&invalid_digest).is_none());
+        assert_eq!(
+            store.get_attestations(&valid_digest),
+            Some(&HashSet::from([2]))
+        );
+        assert!(store.get_attestations(&invalid_digest).is_none());
+
+        assert_eq!(valid_outcome.load(Ordering::Relaxed), PROPAGATED);
+        assert_eq!(invalid_outcome.load(Ordering::Relaxed), INVALID);
+        assert_eq!(valid_att_outcome.load(Ordering::Relaxed), PROPAGATED);
+        assert_eq!(invalid_att_outcome.load(Ordering::Relaxed), INVALID);
+    }
+
+    #[test]
+    fn test_quorum_uses_threshold_of_attested_epoch() {
+        let mut store = TransactionStore::<TestEvent>::default();
+        store.set_committee(&[1, 2, 3, 4]);
+
+        // Given: a parcel attested by 3 of the 4 members of the current committee.
+        let digest = parcel(0).to_digest();
+        for node_index in [1, 2, 3] {
+            store.store_attestation(digest, node_index);
+        }
+        assert!(store.has_quorum(&digest));
+
+        // Given: a parcel from the next epoch attested by 3 members of the next committee.
+        let next_digest = parcel(1).to_digest();
+        for node_index in [1, 2, 3] {
+            let (event, _) = event(node_index);
+            store.store_pending_attestation(next_digest, node_index, event);
+        }
+        assert!(!store.has_quorum(&next_digest));
+
+        // When: the committee grows to 7 members with the epoch change.
+        store.change_epoch(&[1, 2, 3, 4, 5, 6, 7]);
+
+        // Then: the attestations from the previous epoch still reach the quorum of their
+        // committee, but those of the new epoch need 5 attestations.
+        assert!(store.has_quorum(&digest));
+        assert!(!store.has_quorum(&next_digest));
+        store.store_attestation(next_digest, 4);
+        assert!(!store.has_quorum(&next_digest));
+        store.store_attestation(next_digest, 5);
+        assert!(store.has_quorum(&next_digest));
+
+        // When: the committee shrinks mid-epoch.
+        let shrunk_digest = parcel(2).to_digest();
+        store.store_attestation(shrunk_digest, 1);
+        store.store_attestation(shrunk_digest, 2);
+        assert!(!store.has_quorum(&shrunk_digest));
+        store.set_committee(&[1, 2]);
+
+        // Then: the attestations are checked against the threshold of the new committee.
+        assert!(store.has_quorum(&shrunk_digest));
+    }
+
+    #[test]
+    fn test_chain_segment_recovers_missing_parcels() {
+        // Given: a peer that stores a chain of 12 parcels.
+        let mut peer = TransactionStore::<TestEvent>::default();
+        let mut chain = Vec::new();
+        let mut last_executed = [0; 32];
+        for sub_dag_index in 0..12 {
+            let parcel = AuthenticStampedParcel {
+                last_executed,
+                ..parcel(sub_dag_index)
+            };
+            last_executed = parcel.to_digest();
+            peer.store_parcel(parcel.clone(), 1, None);
+            chain.push(parcel);
+        }
+        let digests: Vec<_> = chain.iter().map(|parcel| parcel.to_digest()).collect();
+
+        // Given: a node that executed the first parcel and received the last one, but is missing
+        // the 10 parcels in between.
+        let mut store = TransactionStore::<TestEvent>::default();
+        store.store_parcel(chain[11].clone(), 1, None);
+        let head = digests[0];
+        let missing = chain[11].last_executed;
+
+        // When: the node requests the segment between its head and the missing parcel.
+        let segment = peer.get_chain_segment(&head, &missing, 64);
+
+        // Then: the whole gap is served in one response.
+        assert_eq!(segment.len(), 10);
+        for parcel in segment {
+            store.store_parcel(parcel.inner.clone(), parcel.originator, None);
+        }
+        let mut current = digests[11];
+        while current != head {
+            current = store.get_parcel(&current).unwrap().inner.last_executed;
+        }
+
+        // Then: the segment is bounded by the max length and stops at unknown parcels.
+        assert_eq!(peer.get_chain_segment(&head, &missing, 4).len(), 4);
+        assert_eq!(peer.get_chain_segment(&[1; 32], &digests[11], 64).len(), 12);
+    }
+
+    #[test]
+    fn test_persisted_store_survives_restart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("transaction_store");
+
+        // Given: a persisted store with parcels and attestations from the current epoch, and a
+        // parcel from the next epoch.
+        let mut store = TransactionStore::<TestEvent>::load(path.clone(), 3);
+        let first = parcel(0);
+        let first_digest = first.to_digest();
+        store.store_parcel(first, 1, Some([7; 32]));
+        store.store_attestation(first_digest, 1);
+        store.store_attestation(first_digest, 2);
+        let attested_digest = parcel(1).to_digest();
+        store.store_attestation(attested_digest, 3);
+        let next = parcel(2);
+        let next_digest = next.to_digest();
+        let (next_event, _) = event(1);
+        store.store_pending_parcel(next, 1, None, next_event);
+        drop(store);
+
+        // When: the store is reconstructed from disk.
+        let mut store = TransactionStore::<TestEvent>::load(path.clone(), 3);
+
+        // Then: the messages of the current epoch survived.
+        assert!(store.contains_parcel(&first_digest));
+        assert_eq!(
+            store.get_parcel(&first_digest).unwrap().message_digest,
+            Some([7; 32])
+        );
+        assert_eq!(store.get_attestations(&first_digest).unwrap().len(), 2);
+        assert_eq!(store.get_attestations(&attested_digest).unwrap().len(), 1);
+        assert!(!store.contains_parcel(&attested_digest));
+
+        // Then: the parcel from the next epoch is validated once the epoch changes.
+        assert!(!store.contains_parcel(&next_digest));
+        store.change_epoch(&[1, 2, 3]);
+        assert!(store.contains_parcel(&next_digest));
+        drop(store);
+
+        // When: the store is reconstructed after the epoch change.
+        let store = TransactionStore::<TestEvent>::load(path.clone(), 4);
+
+        // Then: the messages from both epochs survived.
+        assert!(store.contains_parcel(&next_digest));
+        assert!(store.contains_parcel(&first_digest));
+
+        // When: the node restarts in a later epoch.
+        drop(store);
+        let store = TransactionStore::<TestEvent>::load(path, 6);
+
+        // Then: the stale messages are discarded.
+        assert!(!store.contains_parcel(&next_digest));
+        assert!(!store.contains_parcel(&first_digest));
+    }
+
+    #[test]
+    fn test_persisted_store_ignores_partial_writes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("transaction_store");
+
+        // Given: a persisted store, and a temporary file left behind by a crash during a write.
+        let mut store = TransactionStore::<TestEvent>::load(path.clone(), 3);
+        let first = parcel(0);
+        let first_digest = first.to_digest();
+        store.store_parcel(first, 1, None);
+        let slot = store.pointer;
+        drop(store);
+        let partial = path
+            .join(slot.to_string())
+            .join("partial")
+            .with_extension(TEMP_FILE_EXTENSION);
+        fs::write(&partial, [0; 3]).unwrap();
+
+        // When: the store is reconstructed from disk.
+        let store = TransactionStore::<TestEvent>::load(path, 3);
+
+        // Then: the partial write is skipped, and removed when the slot is persisted again.
+        assert!(store.contains_parcel(&first_digest));
+        drop(store);
+        assert!(!partial.exists());
     }
 }
@@ -1,20 +1,31 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use fleek_crypto::NodePublicKey;
+use fleek_crypto::{NodePublicKey, NodeSecretKey, SecretKey};
 use lightning_interfaces::prelude::*;
-use lightning_interfaces::types::{Block, Epoch, Metadata, NodeIndex, TransactionRequest};
-use lightning_interfaces::Events;
-use lightning_metrics::increment_counter;
+use lightning_interfaces::types::{
+    Block,
+    Digest as BroadcastDigest,
+    Epoch,
+    Metadata,
+    NodeIndex,
+    TransactionRequest,
+    TxHash,
+};
+use lightning_interfaces::{Events, ExecutionProgress};
+use lightning_metrics::{histogram, increment_counter};
 use lightning_utils::application::QueryRunnerExt;
 use narwhal_types::Transaction;
 use quick_cache::unsync::Cache;
+use serde::{Deserialize, Serialize};
 use tokio::pin;
 use tokio::sync::mpsc::Receiver;
-use tokio::sync::{mpsc, oneshot, Notify};
+use tokio::sync::{mpsc, oneshot, watch, Notify};
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use types::BlockExecutionResponse;
 
 use super::parcel::{AuthenticStampedParcel, CommitteeAttestation, Digest};
@@ -23,6 +34,13 @@
 use crate::consensus::PubSubMsg;
 
 const MAX_PENDING_TIMEOUTS: usize = 100;
+
+// If more parcels than this are missing between the executed chain and a parcel we received, we
+// request the whole chain segment at once instead of requesting the parcels one by one.
+const MIN_CHAIN_SEGMENT_GAP: u64 = 2;
+
+// The maximum number of parcels we serve in response to a single chain segment request.
+const MAX_CHAIN_SEGMENT_LEN: usize = 64;
 // Exponentially moving average parameter for estimating the time between executions of parcels.
 // This parameter must be in range [0, 1].
 const TBE_EMA: f64 = 0.125;
@@ -31,6 +49,9 @@
 const MIN_TBE: Duration = Duration::from_secs(10);
 const MAX_TBE: Duration = Duration::from_secs(40);
 
+// The estimates for the time between parcel executions are persisted every this many executions.
+const TBE_SAVE_INTERVAL: usize = 10;
+
 pub struct ExecutionWorker {
     handle: JoinHandle<()>,
     tx_shutdown: Arc<Notify>,
@@ -44,9 +65,7 @@ struct Context<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter> {
     /// Pending parcel digests.
     pending_digests: HashSet<Digest>,
     /// Executed parcel digests.
-    executed_digests: HashSet<Digest>,
-    /// quorom threshold to reach consensus.
-    quorom_threshold: usize,
+    executed_digests: ExecutedDigests,
     /// The current validator committee.
     committee: Vec<NodeIndex>,
     /// The node index of this node.
@@ -55,16 +74,26 @@ struct Context<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter> {
     on_committee: bool,
     /// The public key of this node.
     node_public_key: NodePublicKey,
+    /// The secret key of this node, used to sign attestations.
+    node_secret_key: NodeSecretKey,
     /// Pending timeouts for parcels.
     pending_timeouts: HashSet<Digest>,
     /// Pending requests for missing parcels.
     pending_requests: Cache<Digest, ()>,
+    /// Missing parcels that end a longer gap in the parcel chain, mapped to the head of the
+    /// executed chain. These are requested together with the rest of the gap.
+    missing_segments: HashMap<Digest, Digest>,
     /// Query runner.
     query_runner: Q,
     /// Pubsub handle to send and receive broadcast messages.
     pub_sub: P,
     /// Send the event to the RPC
     event_tx: Events,
+    /// Publish the execution progress.
+    execution_progress_tx: watch::Sender<ExecutionProgress>,
+    /// The highest sub dag index of the parcels we received from the committee. Sub dag indices
+    /// keep increasing across epochs, so this is never reset.
+    highest_known_sub_dag_index: u64,
     /// Notifications emitter
     notifier: NE,
     /// Used for sending timeouts to the main tokio select loop.
@@ -77,6 +106,75 @@ struct Context<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter> {
     estimated_tbe: Duration,
     /// The deviation for the time between parcel executions.
     deviation_tbe: Duration,
+    /// Where the time between parcel executions estimates are persisted.
+    tbe_path: PathBuf,
+    /// The number of estimate updates since they were last persisted.
+    tbe_updates_since_save: usize,
+}
+
+/// The digests of the executed parcels of the current and the previous epoch. Parcels from older
+/// epochs are rejected when trying to execute them, so there is no need to remember them.
+#[derive(Default)]
+struct ExecutedDigests {
+    current: HashSet<Digest>,
+    previous: HashSet<Digest>,
+}
+
+impl ExecutedDigests {
+    fn contains(&self, digest: &Digest) -> bool {
+        self.current.contains(digest) || self.previous.contains(digest)
+    }
+
+    fn insert(&mut self, digest: Digest) {
+        self.current.insert(digest);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.current.len() + self.previous.len()
+    }
+
+    /// Drops the digests from before the previous epoch.
+    fn change_epoch(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+}
+
+/// The estimates for the time between parcel executions that are persisted across restarts, so
+/// that a restarted node does not request missing parcels on a stale schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct TbeEstimate {
+    estimated_tbe: Duration,
+    deviation_tbe: Duration,
+}
+
+impl Default for TbeEstimate {
+    fn default() -> Self {
+        Self {
+            estimated_tbe: Duration::from_secs(20),
+            deviation_tbe: Duration::from_secs(5),
+        }
+    }
+}
+
+impl TbeEstimate {
+    /// Load the estimates from the given path, falling back to the defaults if they are missing
+    /// or can't be read.
+    fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| bincode::deserialize(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        let result = bincode::serialize(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| fs::write(path, bytes).map_err(Into::into));
+        if let Err(e) = result {
+            error!("Failed to persist the time between executions estimate: {e:?}");
+        }
+    }
 }
 
 impl ExecutionWorker {
@@ -86,10 +184,13 @@ pub fn spawn<P: PubSub<PubSubMsg> + 'static, Q: SyncQueryRunnerInterface, NE: Em
         consensus_output_rx: Receiver<FilteredConsensusOutput>,
         pub_sub: P,
         query_runner: Q,
-        node_public_key: NodePublicKey,
+        node_secret_key: NodeSecretKey,
         reconfigure_notify: Arc<Notify>,
         notifier: NE,
         event_tx_rx: oneshot::Receiver<Events>,
+        execution_progress_tx: watch::Sender<ExecutionProgress>,
+        tbe_path: PathBuf,
+        txn_store_path: Option<PathBuf>,
     ) -> Self {
         let shutdown_notify = Arc::new(Notify::new());
 
@@ -100,10 +201,13 @@ pub fn spawn<P: PubSub<PubSubMsg> + 'static, Q: SyncQueryRunnerInterface, NE: Em
                 pub_sub,
                 shutdown_notify.clone(),
                 query_runner,
-                node_public_key,
+                node_secret_key,
                 reconfigure_notify,
                 notifier,
                 event_tx_rx,
+                execution_progress_tx,
+                tbe_path,
+                txn_store_path,
             ),
             "CONSENSUS: message receiver worker"
         );
@@ -140,10 +244,13 @@ async fn spawn_worker<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emi
     pub_sub: P,
     shutdown_notify: Arc<Notify>,
     query_runner: Q,
-    node_public_key: NodePublicKey,
+    node_secret_key: NodeSecretKey,
     reconfigure_notify: Arc<Notify>,
     notifier: NE,
     event_tx_rx: oneshot::Receiver<Events>,
+    execution_progress_tx: watch::Sender<ExecutionProgress>,
+    tbe_path: PathBuf,
+    txn_store_path: Option<PathBuf>,
 ) {
     info!("Waiting for event sender in execution worker.");
     let event_tx = event_tx_rx.await.expect("Failed to receive event sender");
@@ -151,7 +258,7 @@ async fn spawn_worker<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emi
 
     info!("Execution node messageworker is running");
     let committee = query_runner.get_committee_members_by_index();
-    let quorom_threshold = (committee.len() * 2) / 3 + 1;
+    let node_public_key = node_secret_key.to_pk();
     let our_index = query_runner
         .pubkey_to_index(&node_public_key)
         .unwrap_or(u32::MAX);
@@ -161,28 +268,40 @@ async fn spawn_worker<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emi
     // with `MAX_PENDING_TIMEOUTS`, so `pending_timeouts` is bounded from above by that constant
     let pending_timeouts = HashSet::new();
     let pending_requests = Cache::new(100);
+    let tbe = TbeEstimate::load(&tbe_path);
+
+    let mut txn_store = match txn_store_path {
+        Some(path) => TransactionStore::load(path, query_runner.get_current_epoch()),
+        None => TransactionStore::default(),
+    };
+    txn_store.set_committee(&committee);
 
     let mut ctx = Context {
         executor,
-        txn_store: TransactionStore::default(),
+        txn_store,
         pending_digests: HashSet::with_capacity(100),
-        executed_digests: HashSet::with_capacity(100),
-        quorom_threshold,
+        executed_digests: ExecutedDigests::default(),
         committee,
         our_index,
         on_committee,
         node_public_key,
+        node_secret_key,
         pending_timeouts,
         pending_requests,
+        missing_segments: HashMap::new(),
         query_runner,
         pub_sub,
         event_tx,
+        execution_progress_tx,
+        highest_known_sub_dag_index: 0,
         notifier,
         timeout_tx,
         reconfigure_notify,
         last_executed_timestamp: None,
-        estimated_tbe: Duration::from_secs(20),
-        deviation_tbe: Duration::from_secs(5),
+        estimated_tbe: tbe.estimated_tbe,
+        deviation_tbe: tbe.deviation_tbe,
+        tbe_path,
+        tbe_updates_since_save: 0,
     };
 
     let shutdown_future = shutdown_notify.notified();
@@ -194,6 +313,7 @@ async fn spawn_worker<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emi
             biased;
             _ = &mut shutdown_future => {
                 ctx.executor.downgrade();
+                save_estimated_tbe(&mut ctx);
                 break;
             },
             output = consensus_output_rx.recv() => {
@@ -208,22 +328,44 @@ async fn spawn_worker<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emi
                 // Timeout for a missing parcel. If we still haven't received the parcel, we send a
                 // request.
                 if let Some(digest) = digest {
-                    ctx.pending_timeouts.remove(&digest);
-
-                    if ctx.txn_store.get_parcel(&digest).is_none() {
-                        let request = PubSubMsg::RequestTransactions(digest);
+                    let request = missing_parcel_request(
+                        digest,
+                        &ctx.txn_store,
+                        &mut ctx.pending_timeouts,
+                        &mut ctx.missing_segments,
+                    );
+                    if let Some(request) = request {
                         let _ = ctx.pub_sub.send(&request, None).await;
+                        if matches!(request, PubSubMsg::RequestChainSegment { .. }) {
+                            info!("Send request for missing chain segment ending with digest: {digest:?}");
+
+                            increment_counter!(
+                                "consensus_missing_segment_request",
+                                Some("Counter for the number of times the node sent a request for a missing segment of the parcel chain")
+                            );
+                        } else {
+                            info!("Send request for missing parcel with digest: {digest:?}");
+
+                            increment_counter!(
+                                "consensus_missing_parcel_request",
+                                Some("Counter for the number of times the node sent a request for a missing consensus parcel")
+                            );
+                        }
                         ctx.pending_requests.insert(digest, ());
-                        info!("Send request for missing parcel with digest: {digest:?}");
-
-                        increment_counter!(
-                            "consensus_missing_parcel_request",
-                            Some("Counter for the number of times the node sent a request for a missing consensus parcel")
-                        );
                     }
                 }
             }
         }
+
+        let execution_progress = ExecutionProgress {
+            pending_parcels: ctx.pending_digests.len(),
+            highest_known_sub_dag_index: ctx.highest_known_sub_dag_index,
+        };
+        ctx.execution_progress_tx.send_if_modified(|progress| {
+            let modified = *progress != execution_progress;
+            *progress = execution_progress;
+            modified
+        });
     }
 }
 
@@ -263,11 +405,12 @@ async fn handle_consensus_output<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterfa
     .await;
 
     let parcel_digest = parcel.to_digest();
-    let attestation = CommitteeAttestation {
-        digest: parcel_digest,
-        node_index: ctx.our_index,
-        epoch: parcel.epoch,
-    };
+    let attestation = CommitteeAttestation::new(
+        parcel_digest,
+        ctx.our_index,
+        parcel.epoch,
+        &ctx.node_secret_key,
+    );
 
     info!("Send transaction parcel to broadcast as a validator");
     let _ = ctx.pub_sub.send(&attestation.into(), None).await;
@@ -289,22 +432,37 @@ async fn handle_consensus_output<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterfa
         }
         ctx.reconfigure_notify.notify_waiters();
 
-        ctx.committee = ctx.query_runner.get_committee_members_by_index();
-        ctx.quorom_threshold = (ctx.committee.len() * 2) / 3 + 1;
-        // We recheck our index incase it was non existant before
-        // and we staked during this epoch and finally got the certificate
-        ctx.our_index = ctx
-            .query_runner
-            .pubkey_to_index(&ctx.node_public_key)
-            .unwrap_or(u32::MAX);
-        ctx.on_committee = ctx.committee.contains(&ctx.our_index);
+        update_committee(ctx);
 
         if response.change_epoch {
-            ctx.txn_store.change_epoch(&ctx.committee);
+            change_epoch(ctx);
+        } else {
+            ctx.txn_store.set_committee(&ctx.committee);
         }
     }
 }
 
+// Refreshes the committee after an epoch or epoch era change. The notifier only notifies the
+// subscribers if the members of the committee actually changed.
+fn update_committee<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter>(
+    ctx: &mut Context<P, Q, NE>,
+) {
+    let committee = ctx.query_runner.get_committee_members_by_index();
+    // We recheck our index incase it was non existant before
+    // and we staked during this epoch and finally got the certificate
+    ctx.our_index = ctx
+        .query_runner
+        .pubkey_to_index(&ctx.node_public_key)
+        .unwrap_or(u32::MAX);
+    ctx.on_committee = committee.contains(&ctx.our_index);
+    let previous_committee = std::mem::replace(&mut ctx.committee, committee);
+    ctx.notifier.committee_changed(
+        ctx.query_runner.get_current_epoch(),
+        previous_committee,
+        ctx.committee.clone(),
+    );
+}
+
 // This function sends transactions to the application to be executed. Returns true if the epoch
 // changed.
 async fn submit_batch<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter>(
@@ -314,21 +472,8 @@ async fn submit_batch<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emi
     sub_dag_index: u64,
     sub_dag_round: u64,
 ) -> BlockExecutionResponse {
-    let transactions = payload
-        .into_iter()
-        .filter_map(|txn| {
-            // Filter out transactions that wont serialize or have already been executed
-            if let Ok(txn) = TransactionRequest::try_from(txn.as_ref()) {
-                if !ctx.query_runner.has_executed_digest(txn.hash()) {
-                    Some(txn)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>();
+    let transactions =
+        filter_transactions(payload, |hash| ctx.query_runner.has_executed_digest(hash));
 
     let block = Block {
         digest,
@@ -409,6 +554,33 @@ async fn handle_pubsub_event<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface,
                 );
             }
         },
+        PubSubMsg::RequestChainSegment { from, to } => {
+            let msg_digests = chain_segment_message_digests(&ctx.txn_store, &from, &to);
+            if msg_digests.is_empty() {
+                increment_counter!(
+                    "consensus_missing_segment_ignored",
+                    Some(
+                        "Number of chain segment requests that were ignored due to not finding any of the parcels in the transaction store"
+                    ),
+                );
+                return;
+            }
+
+            let filter = HashSet::from([msg.originator()]);
+            for msg_digest in &msg_digests {
+                ctx.pub_sub
+                    .repropagate(*msg_digest, Some(filter.clone()))
+                    .await;
+            }
+            info!(
+                "Responded to request for missing chain segment ending with digest {to:?} with {} parcels",
+                msg_digests.len()
+            );
+            increment_counter!(
+                "consensus_missing_segment_sent",
+                Some("Number of missing chain segments served to other nodes"),
+            );
+        },
     }
 }
 
@@ -430,6 +602,9 @@ async fn handle_parcel<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Em
     let parcel_digest = parcel.to_digest();
     let from_next_epoch = parcel.epoch == epoch + 1;
     let last_executed = parcel.last_executed;
+    if is_committee && !from_next_epoch {
+        ctx.highest_known_sub_dag_index = ctx.highest_known_sub_dag_index.max(parcel.sub_dag_index);
+    }
 
     let mut event = None;
     let parcel_request = ctx.pending_requests.remove(&parcel_digest);
@@ -501,6 +676,19 @@ async fn handle_attestation<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, N
         return;
     }
 
+    let is_signed_by_originator = ctx
+        .query_runner
+        .index_to_pubkey(&att.node_index)
+        .is_some_and(|pk| att.verify(&pk));
+    if !is_signed_by_originator {
+        increment_counter!(
+            "consensus_invalid_attestation_signature",
+            Some("Number of attestations rejected because of an invalid signature")
+        );
+        msg.mark_invalid_sender();
+        return;
+    }
+
     let from_next_epoch = att.epoch == epoch + 1;
     let mut event = None;
     if !from_next_epoch {
@@ -540,18 +728,12 @@ async fn execute_digest<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: E
         Ok(epoch_changed) => {
             let epoch_era_changed = epoch_era_before_execution != ctx.query_runner.get_epoch_era();
             if epoch_changed || epoch_era_changed {
-                ctx.committee = ctx.query_runner.get_committee_members_by_index();
-                ctx.quorom_threshold = (ctx.committee.len() * 2) / 3 + 1;
-                // We recheck our index incase it was non existant before and
-                // we staked during this epoch and finally got the certificate
-                ctx.our_index = ctx
-                    .query_runner
-                    .pubkey_to_index(&ctx.node_public_key)
-                    .unwrap_or(u32::MAX);
-                ctx.on_committee = ctx.committee.contains(&ctx.our_index);
+                update_committee(ctx);
                 ctx.reconfigure_notify.notify_waiters();
                 if epoch_changed {
-                    ctx.txn_store.change_epoch(&ctx.committee);
+                    change_epoch(ctx);
+                } else {
+                    ctx.txn_store.set_committee(&ctx.committee);
                 }
             }
         },
@@ -561,6 +743,19 @@ async fn execute_digest<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: E
     }
 }
 
+// Moves the transaction store and the digest bookkeeping to the new epoch. Pending digests whose
+// parcels were garbage collected by the transaction store can never be executed, so we drop them.
+fn change_epoch<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter>(
+    ctx: &mut Context<P, Q, NE>,
+) {
+    ctx.txn_store.change_epoch(&ctx.committee);
+    ctx.executed_digests.change_epoch();
+
+    let txn_store = &ctx.txn_store;
+    ctx.pending_digests
+        .retain(|digest| txn_store.get_parcel(digest).is_some());
+}
+
 // This function will try to execute the parcel with the given digest. It's a helper function for
 // `execute_digest`. This function will only be called by nodes that are not currently validators.
 // Returns Some(true) if the epoch has changed.
@@ -600,13 +795,11 @@ async fn try_execute_internal<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface,
     if ctx.executed_digests.contains(&digest) {
         // we already executed this parcel
         return Ok(false);
-    } else if let Some(x) = ctx.txn_store.get_attestations(&digest) {
+    } else if ctx.txn_store.has_quorum(&digest) {
         // we need a quorum of attestations in order to execute the parcel
-        if x.len() >= ctx.quorom_threshold {
-            // if we should execute we need to make sure we can connect this to our transaction
-            // chain
-            return try_execute_chain(digest, head, ctx).await;
-        }
+        // if we should execute we need to make sure we can connect this to our transaction
+        // chain
+        return try_execute_chain(digest, head, ctx).await;
     }
     Err(NotExecuted::MissingAttestations(digest))
 }
@@ -650,6 +843,22 @@ async fn try_execute_chain<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE
             for digest in parcel_chain {
                 ctx.pending_digests.insert(digest);
             }
+            increment_counter!(
+                "consensus_parcel_chain_missing_parcel",
+                Some("Number of parcel chain walks that stopped at a missing parcel")
+            );
+
+            // The sub dag indices of consecutive parcels are consecutive, so we can tell how many
+            // parcels are missing between the executed chain and the oldest parcel we have.
+            let gap = txn_chain.front().map_or(0, |(_, sub_dag_index, _, _)| {
+                sub_dag_index.saturating_sub(ctx.query_runner.get_sub_dag_index() + 1)
+            });
+            if gap > MIN_CHAIN_SEGMENT_GAP {
+                return Err(NotExecuted::MissingChainSegment {
+                    from: head,
+                    to: current_digest,
+                });
+            }
             return Err(NotExecuted::MissingParcel(current_digest));
         } else {
             // This case cannot happen. `current_digest` cannot be [0; 32]. If `current_digest`
@@ -670,6 +879,12 @@ async fn try_execute_chain<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE
         if last_executed == head {
             let mut epoch_changed = false;
 
+            histogram!(
+                "consensus_parcel_chain_depth",
+                Some("Number of parcels walked to connect a parcel to the executed chain"),
+                txn_chain.len() as f64
+            );
+
             // We connected the chain now execute all the transactions
             for (batch, sub_dag_index, sub_dag_round, digest) in txn_chain {
                 let response = submit_batch(ctx, batch, digest, sub_dag_index, sub_dag_round).await;
@@ -709,35 +924,110 @@ fn handle_not_executed<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Em
     not_executed: NotExecuted,
     ctx: &mut Context<P, Q, NE>,
 ) {
-    if let NotExecuted::MissingParcel(digest) = not_executed {
-        set_parcel_timer(
-            digest,
-            get_timeout(ctx),
-            ctx.timeout_tx.clone(),
-            &mut ctx.pending_timeouts,
-        );
+    match not_executed {
+        NotExecuted::MissingParcel(digest) => {
+            set_parcel_timer(
+                digest,
+                get_timeout(ctx),
+                ctx.timeout_tx.clone(),
+                &mut ctx.pending_timeouts,
+            );
+        },
+        NotExecuted::MissingChainSegment { from, to } => {
+            set_segment_timer(
+                from,
+                to,
+                get_timeout(ctx),
+                ctx.timeout_tx.clone(),
+                &mut ctx.pending_timeouts,
+                &mut ctx.missing_segments,
+            );
+        },
+        _ => {},
     }
 }
 
 // If the execution of the parcel chain fails because of a missing parcel, we first set a timer
 // before sending a missing parcel request. This is to avoid sending premature missing parcel
 // requests, because parcels may arrive out of order.
+// Returns false if the timer could not be registered because there are already
+// `MAX_PENDING_TIMEOUTS` pending timeouts.
 fn set_parcel_timer(
     digest: Digest,
     timeout: Duration,
     timeout_tx: mpsc::Sender<Digest>,
     pending_timeouts: &mut HashSet<Digest>,
-) {
-    if !pending_timeouts.contains(&digest) && pending_timeouts.len() < MAX_PENDING_TIMEOUTS {
-        spawn!(
-            async move {
-                tokio::time::sleep(timeout).await;
-                let _ = timeout_tx.send(digest).await;
-            },
-            "CONSENSUS: parcel timer"
+) -> bool {
+    if pending_timeouts.contains(&digest) {
+        return true;
+    }
+
+    if pending_timeouts.len() >= MAX_PENDING_TIMEOUTS {
+        warn!("Too many pending parcel timeouts, not tracking missing parcel {digest:?}");
+        increment_counter!(
+            "consensus_parcel_timer_dropped",
+            Some("Number of missing parcel timers that were dropped because too many were pending")
         );
-        pending_timeouts.insert(digest);
+        return false;
     }
+
+    spawn!(
+        async move {
+            tokio::time::sleep(timeout).await;
+            let _ = timeout_tx.send(digest).await;
+        },
+        "CONSENSUS: parcel timer"
+    );
+    pending_timeouts.insert(digest);
+    true
+}
+
+// Sets a timer for the parcel at the end of a missing segment of the parcel chain. Once the timer
+// fires, the whole segment is requested instead of the single parcel.
+fn set_segment_timer(
+    from: Digest,
+    to: Digest,
+    timeout: Duration,
+    timeout_tx: mpsc::Sender<Digest>,
+    pending_timeouts: &mut HashSet<Digest>,
+    missing_segments: &mut HashMap<Digest, Digest>,
+) {
+    if set_parcel_timer(to, timeout, timeout_tx, pending_timeouts) {
+        missing_segments.insert(to, from);
+    }
+}
+
+// Returns the request to send once the timer for a missing parcel fires, or None if we received
+// the parcel in the meantime.
+fn missing_parcel_request<T: BroadcastEventInterface<PubSubMsg>>(
+    digest: Digest,
+    txn_store: &TransactionStore<T>,
+    pending_timeouts: &mut HashSet<Digest>,
+    missing_segments: &mut HashMap<Digest, Digest>,
+) -> Option<PubSubMsg> {
+    pending_timeouts.remove(&digest);
+    let segment_from = missing_segments.remove(&digest);
+    if txn_store.get_parcel(&digest).is_some() {
+        return None;
+    }
+    Some(match segment_from {
+        Some(from) => PubSubMsg::RequestChainSegment { from, to: digest },
+        None => PubSubMsg::RequestTransactions(digest),
+    })
+}
+
+// Returns the digests of the broadcast messages that delivered the parcels of the requested
+// segment of the parcel chain, so that they can be repropagated to the requesting node.
+fn chain_segment_message_digests<T: BroadcastEventInterface<PubSubMsg>>(
+    txn_store: &TransactionStore<T>,
+    from: &Digest,
+    to: &Digest,
+) -> Vec<BroadcastDigest> {
+    txn_store
+        .get_chain_segment(from, to, MAX_CHAIN_SEGMENT_LEN)
+        .into_iter()
+        .filter_map(|parcel| parcel.message_digest)
+        .collect()
 }
 
 // This function calculates the exponentially weigted average of the time between parcel executions
@@ -756,11 +1046,52 @@ fn update_estimated_tbe<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: E
             let new_deviation_tbe =
                 (1.0 - TBE_EMA) * deviation_tbe + TBE_EMA * (new_estimated_tbe - sample_tbe).abs();
             ctx.deviation_tbe = Duration::from_millis(new_deviation_tbe as u64);
+
+            ctx.tbe_updates_since_save += 1;
+            if ctx.tbe_updates_since_save >= TBE_SAVE_INTERVAL {
+                save_estimated_tbe(ctx);
+            }
         }
     }
     ctx.last_executed_timestamp = Some(SystemTime::now());
 }
 
+fn save_estimated_tbe<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter>(
+    ctx: &mut Context<P, Q, NE>,
+) {
+    TbeEstimate {
+        estimated_tbe: ctx.estimated_tbe,
+        deviation_tbe: ctx.deviation_tbe,
+    }
+    .save(&ctx.tbe_path);
+    ctx.tbe_updates_since_save = 0;
+}
+
+// Filters out the transactions that won't deserialize, that have already been executed, or that
+// appear earlier in the same payload. This only depends on the payload and the application state,
+// so every node executes the same transactions for the same parcel.
+fn filter_transactions(
+    payload: Vec<Transaction>,
+    has_executed: impl Fn(TxHash) -> bool,
+) -> Vec<TransactionRequest> {
+    let mut seen = HashSet::with_capacity(payload.len());
+    payload
+        .into_iter()
+        .filter_map(|txn| {
+            let txn = TransactionRequest::try_from(txn.as_ref()).ok()?;
+            let hash = txn.hash();
+            if !seen.insert(hash) || has_executed(hash) {
+                increment_counter!(
+                    "consensus_duplicate_transaction",
+                    Some("Number of transactions filtered out before execution because they were already executed or appeared earlier in the payload")
+                );
+                return None;
+            }
+            Some(txn)
+        })
+        .collect()
+}
+
 // calculate the timeout for sending missing parcel requests based on the estimates.
 fn get_timeout<P: PubSub<PubSubMsg>, Q: SyncQueryRunnerInterface, NE: Emitter>(
     ctx: &Context<P, Q, NE>,
@@ -782,13 +1113,99 @@ fn is_valid_message(in_committee: bool, msg_epoch: Epoch, current_epoch: Epoch)
 #[derive(Debug)]
 pub enum NotExecuted {
     MissingParcel(Digest),
+    MissingChainSegment { from: Digest, to: Digest },
     RejectedParcel(Digest),
     MissingAttestations(Digest),
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::execution::worker::is_valid_message;
+    use std::collections::{HashMap, HashSet};
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use fleek_crypto::{NodeSecretKey, SecretKey};
+    use lightning_application::config::ApplicationConfig;
+    use lightning_application::env::ApplicationEnv;
+    use lightning_interfaces::_hacks::{blackhole_socket, Blanket};
+    use lightning_interfaces::prelude::*;
+    use lightning_interfaces::types::{
+        Digest as BroadcastDigest,
+        Genesis,
+        NodeIndex,
+        TransactionRequest,
+    };
+    use lightning_interfaces::{ExecutionProgress, ToDigest};
+    use lightning_test_utils::transaction::get_update_transactions;
+    use tempfile::tempdir;
+    use tokio::sync::{broadcast, mpsc, oneshot, watch, Notify};
+
+    use crate::consensus::PubSubMsg;
+    use crate::execution::parcel::{AuthenticStampedParcel, CommitteeAttestation};
+    use crate::execution::transaction_store::TransactionStore;
+    use crate::execution::worker::{
+        chain_segment_message_digests,
+        filter_transactions,
+        is_valid_message,
+        missing_parcel_request,
+        set_parcel_timer,
+        set_segment_timer,
+        ExecutedDigests,
+        ExecutionWorker,
+        TbeEstimate,
+        MAX_PENDING_TIMEOUTS,
+    };
+    use crate::tests::Event;
+
+    // A pubsub that never receives any messages.
+    #[derive(Clone)]
+    struct IdlePubSub;
+
+    impl PubSub<PubSubMsg> for IdlePubSub {
+        type Event = Event;
+
+        async fn send(
+            &self,
+            _msg: &PubSubMsg,
+            _filter: Option<HashSet<NodeIndex>>,
+        ) -> anyhow::Result<BroadcastDigest> {
+            Ok([0; 32])
+        }
+
+        async fn repropagate(&self, _digest: BroadcastDigest, _filter: Option<HashSet<NodeIndex>>) {
+        }
+
+        async fn recv(&mut self) -> Option<PubSubMsg> {
+            std::future::pending().await
+        }
+
+        async fn recv_event(&mut self) -> Option<Self::Event> {
+            std::future::pending().await
+        }
+    }
+
+    // Starts an execution worker that persists its estimates to `tbe_path`, and shuts it down
+    // once it is running.
+    async fn run_execution_worker(query_runner: &impl SyncQueryRunnerInterface, tbe_path: &Path) {
+        let (_consensus_output_tx, consensus_output_rx) = mpsc::channel(1);
+        let (event_tx_tx, event_tx_rx) = oneshot::channel();
+        let worker = ExecutionWorker::spawn::<IdlePubSub, _, Blanket>(
+            blackhole_socket(),
+            consensus_output_rx,
+            IdlePubSub,
+            query_runner.clone(),
+            NodeSecretKey::generate(),
+            Arc::new(Notify::new()),
+            Blanket,
+            event_tx_rx,
+            watch::channel(ExecutionProgress::default()).0,
+            tbe_path.to_path_buf(),
+            None,
+        );
+        event_tx_tx.send(broadcast::channel(1).0.into()).unwrap();
+        worker.shutdown().await;
+    }
 
     #[test]
     fn test_is_valid_message() {
@@ -807,4 +1224,246 @@ fn test_is_valid_message() {
         // msg is not from a committee member, msg epoch is the last epoch => invalid
         assert!(!is_valid_message(false, 1, 2));
     }
+
+    #[test]
+    fn test_tbe_estimate_persistence() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("execution_tbe");
+
+        // Nothing was persisted yet, so we fall back to the defaults.
+        assert_eq!(TbeEstimate::load(&path), TbeEstimate::default());
+
+        let estimate = TbeEstimate {
+            estimated_tbe: Duration::from_millis(12_345),
+            deviation_tbe: Duration::from_millis(678),
+        };
+        estimate.save(&path);
+        assert_eq!(TbeEstimate::load(&path), estimate);
+
+        // Corrupted files are ignored.
+        std::fs::write(&path, b"garbage").unwrap();
+        assert_eq!(TbeEstimate::load(&path), TbeEstimate::default());
+    }
+
+    #[tokio::test]
+    async fn test_tbe_estimate_is_restored_on_restart() {
+        let temp_dir = tempdir().unwrap();
+        let genesis_path = Genesis::default()
+            .write_to_dir(temp_dir.path().to_path_buf().try_into().unwrap())
+            .unwrap();
+        let config = ApplicationConfig::test(genesis_path);
+        let mut env = ApplicationEnv::new(&config, None).unwrap();
+        env.apply_genesis_block(config.genesis().unwrap().unwrap())
+            .unwrap();
+        let query_runner = env.query_runner();
+        let tbe_path = temp_dir.path().join("execution_tbe");
+
+        // The first run starts from the defaults and persists them on shutdown.
+        run_execution_worker(&query_runner, &tbe_path).await;
+        assert_eq!(TbeEstimate::load(&tbe_path), TbeEstimate::default());
+
+        // As if the worker had learned the estimate from the executed parcels.
+        let learned = TbeEstimate {
+            estimated_tbe: Duration::from_millis(12_345),
+            deviation_tbe: Duration::from_millis(678),
+        };
+        learned.save(&tbe_path);
+
+        // The restarted worker picks up the learned estimate instead of the defaults, so it is
+        // what gets persisted again on shutdown.
+        run_execution_worker(&query_runner, &tbe_path).await;
+        assert_eq!(TbeEstimate::load(&tbe_path), learned);
+    }
+
+    #[test]
+    fn test_filter_transactions_drops_duplicates() {
+        let txns: Vec<_> = get_update_transactions(3)
+            .into_iter()
+            .map(TransactionRequest::UpdateRequest)
+            .collect();
+        let hashes: Vec<_> = txns.iter().map(|txn| txn.hash()).collect();
+        let bytes: Vec<Vec<u8>> = txns.iter().map(|txn| Vec::try_from(txn).unwrap()).collect();
+
+        // A payload with a duplicate transaction only executes it once.
+        let payload = vec![bytes[0].clone(), bytes[1].clone(), bytes[0].clone()];
+        let filtered = filter_transactions(payload, |_| false);
+        assert_eq!(
+            filtered.iter().map(|txn| txn.hash()).collect::<Vec<_>>(),
+            vec![hashes[0], hashes[1]]
+        );
+
+        // Payloads are filtered independently. A transaction from an earlier payload that the
+        // application did not record as executed, e.g. because it reverted, is kept.
+        let payload = vec![bytes[1].clone(), bytes[2].clone()];
+        let filtered = filter_transactions(payload, |_| false);
+        assert_eq!(
+            filtered.iter().map(|txn| txn.hash()).collect::<Vec<_>>(),
+            vec![hashes[1], hashes[2]]
+        );
+
+        // Executed transactions are filtered out.
+        let filtered = filter_transactions(vec![bytes[0].clone()], |hash| hash == hashes[0]);
+        assert!(filtered.is_empty());
+    }
+
+    #[test]
+    fn test_executed_digests_stay_bounded() {
+        let mut executed = ExecutedDigests::default();
+        let parcels_per_epoch = 100u32;
+
+        for epoch in 0..50u32 {
+            for i in 0..parcels_per_epoch {
+                let mut digest = [0; 32];
+                digest[..4].copy_from_slice(&epoch.to_le_bytes());
+                digest[4..8].copy_from_slice(&i.to_le_bytes());
+                executed.insert(digest);
+                assert!(executed.contains(&digest));
+            }
+            assert!(executed.len() <= 2 * parcels_per_epoch as usize);
+            executed.change_epoch();
+        }
+
+        // Digests from the previous epoch are still known, older ones are dropped.
+        let mut digest = [0; 32];
+        digest[..4].copy_from_slice(&49u32.to_le_bytes());
+        assert!(executed.contains(&digest));
+        digest[..4].copy_from_slice(&48u32.to_le_bytes());
+        assert!(!executed.contains(&digest));
+    }
+
+    #[tokio::test]
+    async fn test_set_parcel_timer_is_bounded() {
+        let (timeout_tx, _timeout_rx) = mpsc::channel(1);
+        let mut pending_timeouts = HashSet::new();
+        let timeout = Duration::from_secs(60);
+
+        for i in 0..MAX_PENDING_TIMEOUTS as u32 {
+            let mut digest = [0; 32];
+            digest[..4].copy_from_slice(&i.to_le_bytes());
+            assert!(set_parcel_timer(
+                digest,
+                timeout,
+                timeout_tx.clone(),
+                &mut pending_timeouts
+            ));
+        }
+
+        // A timer that is already pending is still tracked.
+        assert!(set_parcel_timer(
+            [0; 32],
+            timeout,
+            timeout_tx.clone(),
+            &mut pending_timeouts
+        ));
+
+        // New timers are refused once the limit is reached.
+        assert!(!set_parcel_timer(
+            [1; 32],
+            timeout,
+            timeout_tx,
+            &mut pending_timeouts
+        ));
+        assert_eq!(pending_timeouts.len(), MAX_PENDING_TIMEOUTS);
+    }
+
+    #[tokio::test]
+    async fn test_missing_chain_segment_is_requested_and_served() {
+        // Given: a peer that stores a chain of parcels, and a node that only has the last one.
+        let mut peer = TransactionStore::<Event>::default();
+        let mut store = TransactionStore::<Event>::default();
+        let mut chain = Vec::new();
+        let mut last_executed = [0; 32];
+        for i in 0..10u8 {
+            let parcel = AuthenticStampedParcel {
+                transactions: Vec::new(),
+                last_executed,
+                epoch: 0,
+                sub_dag_index: i as u64,
+                sub_dag_round: 0,
+            };
+            last_executed = parcel.to_digest();
+            peer.store_parcel(parcel.clone(), 1, Some([i; 32]));
+            chain.push(parcel);
+        }
+        let head = chain[0].to_digest();
+        let tip = chain[9].clone();
+        let to = tip.last_executed;
+        store.store_parcel(tip, 1, None);
+
+        // When: the execution stops at the missing segment and the timer for it fires.
+        let (timeout_tx, mut timeout_rx) = mpsc::channel(1);
+        let mut pending_timeouts = HashSet::new();
+        let mut missing_segments = HashMap::new();
+        set_segment_timer(
+            head,
+            to,
+            Duration::from_millis(10),
+            timeout_tx,
+            &mut pending_timeouts,
+            &mut missing_segments,
+        );
+        assert_eq!(timeout_rx.recv().await, Some(to));
+        let request =
+            missing_parcel_request(to, &store, &mut pending_timeouts, &mut missing_segments);
+
+        // Then: the whole segment is requested.
+        let Some(PubSubMsg::RequestChainSegment {
+            from,
+            to: requested,
+        }) = request
+        else {
+            panic!("expected a chain segment request");
+        };
+        assert_eq!((from, requested), (head, to));
+        assert!(pending_timeouts.is_empty());
+        assert!(missing_segments.is_empty());
+
+        // Then: the peer repropagates the messages of the missing parcels, newest first.
+        let msg_digests = chain_segment_message_digests(&peer, &from, &requested);
+        let expected: Vec<_> = (1..9u8).rev().map(|i| [i; 32]).collect();
+        assert_eq!(msg_digests, expected);
+
+        // When: the parcels arrived before the timer fired.
+        for (i, parcel) in chain.iter().enumerate().take(9).skip(1) {
+            store.store_parcel(parcel.clone(), 1, Some([i as u8; 32]));
+        }
+
+        // Then: nothing is requested.
+        assert!(
+            missing_parcel_request(to, &store, &mut pending_timeouts, &mut missing_segments)
+                .is_none()
+        );
+
+        // Then: a missing parcel that does not end a segment is requested on its own.
+        let request = missing_parcel_request(
+            [42; 32],
+            &store,
+            &mut pending_timeouts,
+            &mut missing_segments,
+        );
+        assert!(matches!(
+            request,
+            Some(PubSubMsg::RequestTransactions(digest)) if digest == [42; 32]
+        ));
+    }
+
+    #[test]
+    fn test_committee_attestation_signature() {
+        let sk = NodeSecretKey::generate();
+        let other_sk = NodeSecretKey::generate();
+        let att = CommitteeAttestation::new([1; 32], 3, 7, &sk);
+
+        assert!(att.verify(&sk.to_pk()));
+        assert!(!att.verify(&other_sk.to_pk()));
+
+        // A forged attestation that reuses the signature for another node index is rejected.
+        let mut forged = att.clone();
+        forged.node_index = 4;
+        assert!(!forged.verify(&sk.to_pk()));
+
+        // The signature does not cover another parcel digest.
+        let mut forged = att;
+        forged.digest = [2; 32];
+        assert!(!forged.verify(&sk.to_pk()));
+    }
 }
@@ -71,6 +71,28 @@ fn test_to_digest_reorder_batches() {
     assert_ne!(parcel1.to_digest(), parcel2.to_digest());
 }
 
+#[test]
+fn test_to_digest_matches_transcript() {
+    let parcel = generate_random_parcel(5, 4, 10, None);
+    assert_eq!(parcel.to_digest(), parcel.transcript().hash());
+}
+
+#[test]
+fn test_to_digest_covers_epoch() {
+    let parcel1 = generate_random_parcel(5, 4, 10, None);
+    let mut parcel2 = parcel1.clone();
+    parcel2.epoch += 1;
+    assert_ne!(parcel1.to_digest(), parcel2.to_digest());
+}
+
+#[test]
+fn test_to_digest_covers_sub_dag_index() {
+    let parcel1 = generate_random_parcel(5, 4, 10, None);
+    let mut parcel2 = parcel1.clone();
+    parcel2.sub_dag_index += 1;
+    assert_ne!(parcel1.to_digest(), parcel2.to_digest());
+}
+
 #[test]
 fn test_ring_buffer_store_get_parcel() {
     let mut ring_buffer = TransactionStore::<Event>::default();
@@ -160,7 +182,7 @@ fn test_ring_buffer_invalid_parcel() {
     assert!(ring_buffer.get_parcel(&digest).is_none());
 }
 
-struct Event {
+pub(crate) struct Event {
     originator: NodeIndex,
     message: Option<PubSubMsg>,
     digest: BroadcastDigest,
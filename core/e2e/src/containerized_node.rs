@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use anyhow::Context;
 use fleek_crypto::AccountOwnerSecretKey;
 use futures::Future;
 use lightning_interfaces::fdi::MultiThreadedProvider;
@@ -52,7 +53,9 @@ pub fn new(
     pub async fn start(&mut self) -> anyhow::Result<()> {
         // This function has to return a result in order to use try_join_all in swarm.rs
         let handle = self.node.spawn();
-        handle.await.unwrap()?;
+        handle
+            .await
+            .with_context(|| format!("Failed to start node {}", self.index))??;
 
         self.started = true;
 
@@ -697,6 +697,7 @@ fn build_config<C: NodeComponents>(
             .join("data/narwhal_store")
             .try_into()
             .expect("Failed to resolve path"),
+        ..Default::default()
     });
 
     config.inject::<Keystore<C>>(KeystoreConfig {
@@ -0,0 +1,42 @@
+use fleek_crypto::{AccountOwnerSecretKey, SecretKey};
+use lightning_application::app::Application;
+use lightning_application::config::ApplicationConfig;
+use lightning_e2e::containerized_node::ContainerizedNode;
+use lightning_interfaces::partial_node_components;
+use lightning_interfaces::types::{NodePorts, Staking};
+use lightning_utils::config::TomlConfigProvider;
+use tempfile::tempdir;
+
+partial_node_components!(TestBinding {
+    ConfigProviderInterface = TomlConfigProvider<Self>;
+    ApplicationInterface = Application<Self>;
+});
+
+#[tokio::test]
+async fn test_start_returns_init_error() {
+    let temp_dir = tempdir().unwrap();
+    let config = TomlConfigProvider::<TestBinding>::default();
+    // The application fails to initialize, since the genesis file does not exist.
+    config.inject::<Application<TestBinding>>(ApplicationConfig::test(
+        temp_dir
+            .path()
+            .join("missing-genesis.toml")
+            .try_into()
+            .unwrap(),
+    ));
+
+    let mut node = ContainerizedNode::new(
+        config,
+        AccountOwnerSecretKey::generate(),
+        NodePorts::default(),
+        0,
+        false,
+        Staking::default(),
+    );
+
+    // The error is returned to the caller instead of panicking.
+    assert!(node.start().await.is_err());
+    assert!(!node.is_started());
+
+    node.shutdown().await;
+}
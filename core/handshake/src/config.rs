@@ -19,6 +19,8 @@ pub struct HandshakeConfig {
     /// Timeout for disconnected sessions
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// Maximum length of a single service payload in either direction
+    pub max_payload_len: usize,
 }
 
 impl Default for HandshakeConfig {
@@ -33,6 +35,7 @@ fn default() -> Self {
             http_address: ([0, 0, 0, 0], 4220).into(),
             https: None,
             timeout: Duration::from_secs(1),
+            max_payload_len: 32 << 20,
         }
     }
 }
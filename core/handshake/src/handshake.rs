@@ -45,12 +45,19 @@ pub fn new(
         config: &C::ConfigProviderInterface,
         keystore: &C::KeystoreInterface,
         service_executor: &C::ServiceExecutorInterface,
+        dack_aggregator: &C::DeliveryAcknowledgmentAggregatorInterface,
         fdi::Cloned(waiter): fdi::Cloned<ShutdownWaiter>,
     ) -> Self {
         let config = config.get::<Self>();
         let provider = service_executor.get_provider();
         let pk = keystore.get_ed25519_pk();
-        let ctx = Context::new(provider, waiter, config.timeout);
+        let ctx = Context::new(
+            provider,
+            waiter,
+            config.timeout,
+            config.max_payload_len,
+            dack_aggregator.socket(),
+        );
         let handle = Handle::new();
 
         Self {
@@ -138,9 +145,12 @@ pub struct Context<P: ExecutorProviderInterface> {
     /// Service unix socket provider
     provider: P,
     pub(crate) shutdown: ShutdownWaiter,
+    /// Socket used to submit the delivery acknowledgments received from clients.
+    pub(crate) dack_socket: DeliveryAcknowledgmentSocket,
     connection_counter: Arc<AtomicU64>,
     connections: Arc<DashMap<u64, ConnectionEntry>>,
     timeout: Duration,
+    max_payload_len: usize,
 }
 
 struct ConnectionEntry {
@@ -151,16 +161,26 @@ struct ConnectionEntry {
     access_token: [u8; 48],
     /// The timeout for the access token.
     timeout: u128,
+    /// Whether the access token was revoked. A revoked token can't be extended.
+    revoked: bool,
 }
 
 impl<P: ExecutorProviderInterface> Context<P> {
-    pub fn new(provider: P, waiter: ShutdownWaiter, timeout: Duration) -> Self {
+    pub fn new(
+        provider: P,
+        waiter: ShutdownWaiter,
+        timeout: Duration,
+        max_payload_len: usize,
+        dack_socket: DeliveryAcknowledgmentSocket,
+    ) -> Self {
         Self {
             provider,
             shutdown: waiter,
+            dack_socket,
             connection_counter: AtomicU64::new(0).into(),
             connections: DashMap::new().into(),
             timeout,
+            max_payload_len,
         }
     }
 
@@ -221,16 +241,19 @@ pub async fn handle_new_connection<S: TransportSender, R: TransportReceiver>(
                         connection_sender: tx,
                         access_token,
                         timeout: 0,
+                        revoked: false,
                     },
                 );
 
                 Proxy::new(
                     connection_id,
                     service,
+                    pk,
                     socket,
                     rx,
                     self.clone(),
                     self.timeout,
+                    self.max_payload_len,
                 )
                 .spawn(Some(State::OnlyPrimaryConnection(
                     (sender, receiver).into(),
@@ -250,11 +273,12 @@ pub async fn handle_new_connection<S: TransportSender, R: TransportReceiver>(
                     return;
                 }
 
-                if connection.timeout
-                    < SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .expect("Failed to get current time")
-                        .as_millis()
+                if connection.revoked
+                    || connection.timeout
+                        < SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .expect("Failed to get current time")
+                            .as_millis()
                 {
                     sender.terminate(TerminationReason::InvalidToken).await;
                     return;
@@ -289,6 +313,10 @@ pub async fn handle_new_connection<S: TransportSender, R: TransportReceiver>(
             return ([0; 48], 0);
         };
 
+        if connection.revoked {
+            return (connection.access_token, 0);
+        }
+
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Failed to get current time.")
@@ -300,6 +328,15 @@ pub async fn handle_new_connection<S: TransportSender, R: TransportReceiver>(
         (connection.access_token, ttl)
     }
 
+    /// Expire the access token of the given connection so that any further join requests using it
+    /// are rejected.
+    pub fn revoke_access_token(&self, connection_id: u64) {
+        if let Some(mut connection) = self.connections.get_mut(&connection_id) {
+            connection.timeout = 0;
+            connection.revoked = true;
+        }
+    }
+
     pub fn cleanup_connection(&self, connection_id: u64) {
         self.connections.remove(&connection_id);
     }
@@ -4,11 +4,18 @@
 use arrayref::array_ref;
 use async_channel::Receiver;
 use bytes::BytesMut;
-use lightning_interfaces::schema::handshake::{ResponseFrame, TerminationReason};
+use fleek_crypto::{ClientPublicKey, ClientSignature, PublicKey};
+use lightning_interfaces::schema::handshake::{
+    delivery_acknowledgment_digest,
+    ResponseFrame,
+    TerminationReason,
+};
+use lightning_interfaces::types::{DeliveryAcknowledgment, DeliveryAcknowledgmentProof};
 use lightning_interfaces::{spawn, ExecutorProviderInterface};
 use lightning_metrics::increment_counter;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
+use tracing::warn;
 
 use crate::handshake::Context;
 use crate::schema::RequestFrame;
@@ -20,6 +27,8 @@ pub struct Proxy<P: ExecutorProviderInterface> {
     connection_id: u64,
     /// The id for the service this connection is connected to.
     service_id: u32,
+    /// The public key of the client that initiated this connection.
+    client: ClientPublicKey,
     /// The unix socket connection to the service made specifically for this ongoing connection.
     socket: UnixStream,
     /// The buffer using which we read bytes from the unix socket.
@@ -42,7 +51,13 @@ pub struct Proxy<P: ExecutorProviderInterface> {
     /// payload through the transport. And randomly inserting in some other frame in the middle of
     /// an active length delimited message before reaching the promised length breaks many things.
     queued_primary_response: VecDeque<ResponseFrame>,
+    /// The total number of payload bytes that were written to the client's transport so far.
+    delivered: u128,
+    /// The number of payload bytes the client has already acknowledged.
+    acknowledged: u128,
     timeout: Duration,
+    /// The maximum length of a single payload in either direction.
+    max_payload_len: usize,
 }
 
 pub type IsPrimary = bool;
@@ -57,8 +72,14 @@ pub enum State {
 
 enum HandleRequestResult {
     Ok,
-    DropTransport,
-    TerminateConnection,
+    /// Terminate only the transport that sent the request, the connection to the service stays
+    /// alive.
+    TerminateTransport(TerminationReason),
+    /// Terminate the entire connection including every transport attached to it.
+    TerminateConnection(TerminationReason),
+    /// The access token was revoked, any secondary connection that joined using it should be
+    /// terminated.
+    RevokeSecondary,
 }
 
 impl<P: ExecutorProviderInterface> Proxy<P> {
@@ -67,15 +88,18 @@ impl<P: ExecutorProviderInterface> Proxy<P> {
     pub fn new(
         connection_id: u64,
         service_id: u32,
+        client: ClientPublicKey,
         socket: UnixStream,
         connection_rx: Receiver<(IsPrimary, TransportPair)>,
         context: Context<P>,
         timeout: Duration,
+        max_payload_len: usize,
     ) -> Self {
         Self {
             context,
             connection_id,
             service_id,
+            client,
             socket,
             buffer: Default::default(),
             connection_rx,
@@ -83,7 +107,10 @@ pub fn new(
             discard_bytes: false,
             is_primary_the_current_sender: false,
             queued_primary_response: VecDeque::new(),
+            delivered: 0,
+            acknowledged: 0,
             timeout,
+            max_payload_len,
         }
     }
 
@@ -185,14 +212,23 @@ async fn run_with_one_connection<S: TransportSender, R: TransportReceiver>(
             tokio::select! {
                 res = receiver.recv() => {
                     match async_map(res, |r| self.handle_incoming(is_primary, r)).await {
-                        Some(HandleRequestResult::Ok) if is_primary => {
+                        // There is no secondary connection to drop after a revocation.
+                        Some(HandleRequestResult::Ok | HandleRequestResult::RevokeSecondary)
+                            if is_primary =>
+                        {
                             self.maybe_flush_primary_queue(true, &mut sender).await;
                         },
-                        Some(HandleRequestResult::Ok) => {},
-                        Some(HandleRequestResult::TerminateConnection) => {
-                            break 'outer TerminationReason::InternalError;
+                        Some(HandleRequestResult::Ok | HandleRequestResult::RevokeSecondary) => {},
+                        Some(HandleRequestResult::TerminateConnection(reason)) => {
+                            break 'outer reason;
                         },
-                        Some(HandleRequestResult::DropTransport) | None => {
+                        Some(HandleRequestResult::TerminateTransport(reason)) => {
+                            sender.terminate(reason).await;
+                            self.discard_bytes = true;
+                            self.queued_primary_response.clear();
+                            return State::NoConnection;
+                        },
+                        None => {
                             // We're possibly switching connection. If there are any pending bytes from
                             // a current service payload we need to discard them before moving on to
                             // send the next payload to the new connection.
@@ -232,19 +268,23 @@ async fn run_with_one_connection<S: TransportSender, R: TransportReceiver>(
                         },
                     },
                     Err(_) => {
-                        break 'outer TerminationReason::InternalError;
+                        // The sender is owned by the context which only goes away when the node is
+                        // shutting down.
+                        break 'outer TerminationReason::Shutdown;
                     }
                 },
                 res = self.socket.read_buf(&mut self.buffer) => match res {
                     Ok(0) => {
                         debug_assert_ne!(self.buffer.capacity(), 0);
+                        // If the service closed the socket in the middle of a payload the client
+                        // will never receive the promised bytes.
                         break if self.current_write == 0 {
                             TerminationReason::ServiceTerminated
                         } else {
-                            TerminationReason::InternalError
+                            TerminationReason::ServiceSocketClosed
                         }
                     },
-                    Err(_) => { break TerminationReason::InternalError },
+                    Err(_) => { break TerminationReason::ServiceSocketClosed },
                     _ => {
                         'inner: while !self.buffer.is_empty() {
                             // current write is set to zero. we're looking for the next header.
@@ -267,6 +307,9 @@ async fn run_with_one_connection<S: TransportSender, R: TransportReceiver>(
 
                                 let bytes = self.buffer.split_to(4);
                                 let len = u32::from_be_bytes(*array_ref![bytes, 0, 4]) as usize;
+                                if len > self.max_payload_len {
+                                    break 'outer TerminationReason::PayloadTooLarge;
+                                }
                                 sender.start_write(len).await;
                                 self.current_write = len;
                                 continue 'inner; // to handle `len` == 0.
@@ -281,10 +324,13 @@ async fn run_with_one_connection<S: TransportSender, R: TransportReceiver>(
                                 continue 'inner;
                             }
 
-                            if sender.write(bytes.freeze()).await.is_err() {
-                                self.discard_bytes = true;
-                                self.queued_primary_response.clear();
-                                return State::NoConnection;
+                            match sender.write(bytes.freeze()).await {
+                                Ok(written) => self.delivered += written as u128,
+                                Err(_) => {
+                                    self.discard_bytes = true;
+                                    self.queued_primary_response.clear();
+                                    return State::NoConnection;
+                                },
                             }
                         }
                     }
@@ -321,10 +367,25 @@ pub async fn run_with_primary_and_secondary<
                         Some(HandleRequestResult::Ok) => {
                             self.maybe_flush_primary_queue(false, &mut p_sender).await;
                         },
-                        Some(HandleRequestResult::TerminateConnection) => {
-                            break 'outer TerminationReason::InternalError;
+                        Some(HandleRequestResult::RevokeSecondary) => {
+                            s_sender.terminate(TerminationReason::InvalidToken).await;
+                            if !self.is_primary_the_current_sender {
+                                self.discard_bytes = true;
+                            }
+                            return State::OnlyPrimaryConnection((p_sender, p_receiver).into());
+                        },
+                        Some(HandleRequestResult::TerminateConnection(reason)) => {
+                            break 'outer reason;
+                        },
+                        Some(HandleRequestResult::TerminateTransport(reason)) => {
+                            p_sender.terminate(reason).await;
+                            if self.is_primary_the_current_sender {
+                                self.discard_bytes = true;
+                            }
+                            self.queued_primary_response.clear();
+                            return State::OnlySecondaryConnection((s_sender, s_receiver).into());
                         },
-                        Some(HandleRequestResult::DropTransport) | None => {
+                        None => {
                             // We lost connection with primary. So if we're currently writing to it
                             // discard the current payload going its way.
                             if self.is_primary_the_current_sender {
@@ -337,12 +398,19 @@ pub async fn run_with_primary_and_secondary<
                 },
                 res = s_receiver.recv() => {
                     match async_map(res, |r| self.handle_incoming(false, r)).await {
-                        Some(HandleRequestResult::Ok) => {},
-                        Some(HandleRequestResult::TerminateConnection) => {
-                            break 'outer TerminationReason::InternalError;
+                        Some(HandleRequestResult::Ok | HandleRequestResult::RevokeSecondary) => {},
+                        Some(HandleRequestResult::TerminateConnection(reason)) => {
+                            break 'outer reason;
+                        },
+                        Some(HandleRequestResult::TerminateTransport(reason)) => {
+                            s_sender.terminate(reason).await;
+                            if !self.is_primary_the_current_sender {
+                                self.discard_bytes = true;
+                            }
+                            return State::OnlyPrimaryConnection((p_sender, p_receiver).into());
                         },
-                        Some(HandleRequestResult::DropTransport) | None => {
-                         if !self.is_primary_the_current_sender {
+                        None => {
+                            if !self.is_primary_the_current_sender {
                                 self.discard_bytes = true;
                             }
                             return State::OnlyPrimaryConnection((p_sender, p_receiver).into());
@@ -371,19 +439,23 @@ pub async fn run_with_primary_and_secondary<
                         }
                     },
                     Err(_) => {
-                        break TerminationReason::InternalError;
+                        // The sender is owned by the context which only goes away when the node is
+                        // shutting down.
+                        break TerminationReason::Shutdown;
                     }
                 },
                 res = self.socket.read_buf(&mut self.buffer) => match res {
                     Ok(0) => {
                         debug_assert_ne!(self.buffer.capacity(), 0);
+                        // If the service closed the socket in the middle of a payload the client
+                        // will never receive the promised bytes.
                         break if self.current_write == 0 {
                             TerminationReason::ServiceTerminated
                         } else {
-                            TerminationReason::InternalError
+                            TerminationReason::ServiceSocketClosed
                         }
                     },
-                    Err(_) => { break TerminationReason::InternalError },
+                    Err(_) => { break TerminationReason::ServiceSocketClosed },
                     _ => {
                         'inner: while !self.buffer.is_empty() {
                             // current write is set to zero. we're looking for the next header.
@@ -404,6 +476,9 @@ pub async fn run_with_primary_and_secondary<
 
                                 let bytes = self.buffer.split_to(4);
                                 let len = u32::from_be_bytes(*array_ref![bytes, 0, 4]) as usize;
+                                if len > self.max_payload_len {
+                                    break 'outer TerminationReason::PayloadTooLarge;
+                                }
                                 s_sender.start_write(len).await;
                                 self.current_write = len;
                                 continue 'inner; // to handle `len` == 0.
@@ -419,14 +494,16 @@ pub async fn run_with_primary_and_secondary<
                             }
 
                             return if self.is_primary_the_current_sender {
-                                if p_sender.write(bytes.freeze()).await.is_ok() {
+                                if let Ok(written) = p_sender.write(bytes.freeze()).await {
+                                    self.delivered += written as u128;
                                     continue 'inner;
                                 }
                                 self.discard_bytes = true;
                                 self.queued_primary_response.clear();
                                 State::OnlySecondaryConnection((s_sender, s_receiver).into())
                             } else {
-                                if s_sender.write(bytes.freeze()).await.is_ok() {
+                                if let Ok(written) = s_sender.write(bytes.freeze()).await {
+                                    self.delivered += written as u128;
                                     continue 'inner;
                                 }
                                 self.discard_bytes = true;
@@ -450,6 +527,11 @@ async fn handle_incoming(
     ) -> HandleRequestResult {
         match request {
             RequestFrame::ServicePayload { bytes } => {
+                if bytes.len() > self.max_payload_len {
+                    return HandleRequestResult::TerminateConnection(
+                        TerminationReason::PayloadTooLarge,
+                    );
+                }
                 let service_id = self.service_id.to_string();
                 increment_counter!(
                     "handshake_service_payloads",
@@ -457,16 +539,24 @@ async fn handle_incoming(
                     "service_id" => service_id.as_str()
                 );
                 if self.socket.write_u32(bytes.len() as u32).await.is_err() {
-                    return HandleRequestResult::TerminateConnection;
+                    return HandleRequestResult::TerminateConnection(
+                        TerminationReason::ServiceSocketClosed,
+                    );
                 }
                 if self.socket.write_all(&bytes).await.is_err() {
-                    return HandleRequestResult::TerminateConnection;
+                    return HandleRequestResult::TerminateConnection(
+                        TerminationReason::ServiceSocketClosed,
+                    );
                 }
                 HandleRequestResult::Ok
             },
-            RequestFrame::AccessToken { .. } if !is_primary => HandleRequestResult::DropTransport,
-            RequestFrame::ExtendAccessToken { .. } if !is_primary => {
-                HandleRequestResult::DropTransport
+            // Only the primary connection is allowed to manage the access token.
+            RequestFrame::AccessToken { .. }
+            | RequestFrame::ExtendAccessToken { .. }
+            | RequestFrame::RevokeAccessToken
+                if !is_primary =>
+            {
+                HandleRequestResult::TerminateTransport(TerminationReason::WrongPermssion)
             },
             RequestFrame::AccessToken { ttl } => {
                 let (access_token, ttl) = self.context.extend_access_token(self.connection_id, ttl);
@@ -481,14 +571,58 @@ async fn handle_incoming(
                 self.context.extend_access_token(self.connection_id, ttl);
                 HandleRequestResult::Ok
             },
-            RequestFrame::DeliveryAcknowledgment { .. } => {
-                // todo: not supported/expected at the moment.
-                HandleRequestResult::DropTransport
+            RequestFrame::RevokeAccessToken => {
+                self.context.revoke_access_token(self.connection_id);
+                HandleRequestResult::RevokeSecondary
+            },
+            RequestFrame::DeliveryAcknowledgment {
+                commodity,
+                signature,
+            } => {
+                self.handle_delivery_acknowledgment(commodity, signature)
+                    .await
             },
             _ => unreachable!(),
         }
     }
 
+    /// Verify a delivery acknowledgment from the client and submit the newly acknowledged
+    /// commodity to the aggregator.
+    async fn handle_delivery_acknowledgment(
+        &mut self,
+        commodity: u128,
+        signature: ClientSignature,
+    ) -> HandleRequestResult {
+        // The client can only acknowledge bytes that were actually sent to it and have not been
+        // acknowledged already.
+        if commodity <= self.acknowledged || commodity > self.delivered {
+            return HandleRequestResult::TerminateConnection(
+                TerminationReason::InvalidDeliveryAcknowledgment,
+            );
+        }
+
+        let digest = delivery_acknowledgment_digest(self.service_id, commodity);
+        if !matches!(self.client.verify(&signature, &digest), Ok(true)) {
+            return HandleRequestResult::TerminateConnection(
+                TerminationReason::InvalidDeliveryAcknowledgment,
+            );
+        }
+
+        let dack = DeliveryAcknowledgment {
+            service_id: self.service_id,
+            commodity: commodity - self.acknowledged,
+            proof: DeliveryAcknowledgmentProof,
+            metadata: None,
+        };
+        self.acknowledged = commodity;
+
+        if self.context.dack_socket.enqueue(dack).await.is_err() {
+            warn!("failed to submit delivery acknowledgment to the aggregator");
+        }
+
+        HandleRequestResult::Ok
+    }
+
     /// Makes sure the buffer has a proper allocated capacity based on the expected number of bytes.
     #[inline(always)]
     fn grow_buffer(&mut self) {
@@ -547,12 +681,14 @@ async fn async_map<T, F, O, R>(option: Option<T>, f: F) -> Option<R>
 mod tests {
     use std::time::Duration;
 
+    use affair::Socket;
     use anyhow::Result;
-    use fleek_crypto::{ClientPublicKey, ClientSignature};
+    use fleek_crypto::{ClientPublicKey, ClientSecretKey, ClientSignature, SecretKey};
     use fn_sdk::header::read_header;
     use futures::{SinkExt, StreamExt};
     use lightning_interfaces::prelude::*;
     use lightning_interfaces::schema::handshake::{
+        delivery_acknowledgment_digest,
         HandshakeRequestFrame,
         RequestFrame,
         ResponseFrame,
@@ -560,6 +696,7 @@ mod tests {
     };
     use lightning_interfaces::types::ServiceId;
     use lightning_interfaces::ShutdownController;
+    use tokio::io::AsyncWriteExt;
     use tokio::net::UnixStream;
     use tokio::time::timeout;
     use tokio_util::codec::Framed;
@@ -569,7 +706,11 @@ mod tests {
     use crate::transports::Transport;
 
     const ECHO_SERVICE: u32 = 1001;
+    const CLOSING_SERVICE: u32 = 1002;
+    const TRUNCATING_SERVICE: u32 = 1003;
+    const OVERSIZED_SERVICE: u32 = 1004;
     const TEST_PAYLOAD: &[u8] = &[69; 420];
+    const MAX_PAYLOAD_LEN: usize = 1024;
 
     #[derive(Clone)]
     struct MockServiceProvider;
@@ -594,6 +735,67 @@ fn echo_service(mut stream: UnixStream) {
                 "HANDSHAKE: echo service"
             );
         }
+
+        /// Closes the socket cleanly after receiving the first payload.
+        fn closing_service(mut stream: UnixStream) {
+            spawn!(
+                async move {
+                    read_header(&mut stream)
+                        .await
+                        .expect("Could not read hello frame.");
+
+                    let mut framed =
+                        Framed::new(stream, tokio_util::codec::LengthDelimitedCodec::new());
+                    framed.next().await;
+                },
+                "HANDSHAKE: closing service"
+            );
+        }
+
+        /// Closes the socket in the middle of writing a response to the first payload.
+        fn truncating_service(mut stream: UnixStream) {
+            spawn!(
+                async move {
+                    read_header(&mut stream)
+                        .await
+                        .expect("Could not read hello frame.");
+
+                    let mut framed =
+                        Framed::new(stream, tokio_util::codec::LengthDelimitedCodec::new());
+                    if framed.next().await.is_none() {
+                        return;
+                    }
+
+                    let mut stream = framed.into_inner();
+                    stream.write_u32(10).await.ok();
+                    stream.write_all(&[0; 2]).await.ok();
+                },
+                "HANDSHAKE: truncating service"
+            );
+        }
+
+        /// Announces a response larger than the allowed payload length.
+        fn oversized_service(mut stream: UnixStream) {
+            spawn!(
+                async move {
+                    read_header(&mut stream)
+                        .await
+                        .expect("Could not read hello frame.");
+
+                    let mut framed =
+                        Framed::new(stream, tokio_util::codec::LengthDelimitedCodec::new());
+                    if framed.next().await.is_none() {
+                        return;
+                    }
+
+                    let mut stream = framed.into_inner();
+                    stream.write_u32(MAX_PAYLOAD_LEN as u32 + 1).await.ok();
+                    // keep the socket open so the termination is caused by the length alone
+                    futures::future::pending::<()>().await;
+                },
+                "HANDSHAKE: oversized service"
+            );
+        }
     }
 
     impl ExecutorProviderInterface for MockServiceProvider {
@@ -604,17 +806,42 @@ async fn connect(&self, service_id: ServiceId) -> Option<UnixStream> {
                     Self::echo_service(left);
                     Some(right)
                 },
+                CLOSING_SERVICE => {
+                    let (left, right) = UnixStream::pair().ok()?;
+                    Self::closing_service(left);
+                    Some(right)
+                },
+                TRUNCATING_SERVICE => {
+                    let (left, right) = UnixStream::pair().ok()?;
+                    Self::truncating_service(left);
+                    Some(right)
+                },
+                OVERSIZED_SERVICE => {
+                    let (left, right) = UnixStream::pair().ok()?;
+                    Self::oversized_service(left);
+                    Some(right)
+                },
                 _ => None,
             }
         }
     }
 
     async fn start_mock_node<P: ExecutorProviderInterface>(id: u16) -> Result<ShutdownController> {
+        start_mock_node_with_dack_socket::<P>(id, lightning_interfaces::_hacks::blackhole_socket())
+            .await
+    }
+
+    async fn start_mock_node_with_dack_socket<P: ExecutorProviderInterface>(
+        id: u16,
+        dack_socket: DeliveryAcknowledgmentSocket,
+    ) -> Result<ShutdownController> {
         let shutdown = ShutdownController::default();
         let context = Context::new(
             MockServiceProvider,
             shutdown.waiter(),
             Duration::from_secs(1),
+            MAX_PAYLOAD_LEN,
+            dack_socket,
         );
         let (transport, _) =
             MockTransport::bind::<P>(shutdown.waiter(), MockTransportConfig { port: id }).await?;
@@ -623,6 +850,20 @@ async fn start_mock_node<P: ExecutorProviderInterface>(id: u16) -> Result<Shutdo
         Ok(shutdown)
     }
 
+    async fn expect_termination(
+        rx: &async_channel::Receiver<bytes::Bytes>,
+        reason: TerminationReason,
+    ) -> Result<()> {
+        let bytes = timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("termination frame should be sent within 1 second")?;
+        assert_eq!(
+            ResponseFrame::decode(&bytes)?,
+            ResponseFrame::Termination { reason }
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn primary_connection() -> Result<()> {
         // start and connect to the mock node
@@ -848,4 +1089,462 @@ async fn extend_token() -> Result<()> {
         shutdown.shutdown().await;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn terminate_on_service_close() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(4).await?;
+        let (tx, rx) = dial_mock(4).await.expect("failed to dial");
+
+        tx.send(
+            HandshakeRequestFrame::Handshake {
+                retry: None,
+                service: CLOSING_SERVICE,
+                pk: ClientPublicKey([0; 96]),
+                pop: ClientSignature([0; 48]),
+            }
+            .encode(),
+        )
+        .await?;
+
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: TEST_PAYLOAD.into(),
+            }
+            .encode(),
+        )
+        .await?;
+
+        expect_termination(&rx, TerminationReason::ServiceTerminated).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn terminate_on_incomplete_service_payload() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(5).await?;
+        let (tx, rx) = dial_mock(5).await.expect("failed to dial");
+
+        tx.send(
+            HandshakeRequestFrame::Handshake {
+                retry: None,
+                service: TRUNCATING_SERVICE,
+                pk: ClientPublicKey([0; 96]),
+                pop: ClientSignature([0; 48]),
+            }
+            .encode(),
+        )
+        .await?;
+
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: TEST_PAYLOAD.into(),
+            }
+            .encode(),
+        )
+        .await?;
+
+        expect_termination(&rx, TerminationReason::ServiceSocketClosed).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn terminate_secondary_on_token_request() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(6).await?;
+        let (primary_tx, primary_rx) = dial_mock(6)
+            .await
+            .expect("failed to dial primary connection");
+
+        primary_tx
+            .send(
+                HandshakeRequestFrame::Handshake {
+                    retry: None,
+                    service: ECHO_SERVICE,
+                    pk: ClientPublicKey([0; 96]),
+                    pop: ClientSignature([0; 48]),
+                }
+                .encode(),
+            )
+            .await?;
+
+        primary_tx
+            .send(RequestFrame::AccessToken { ttl: 1 }.encode())
+            .await?;
+        let access_token = match ResponseFrame::decode(&primary_rx.recv().await?)? {
+            ResponseFrame::AccessToken { access_token, .. } => *access_token,
+            f => panic!("expected access token, got {f:?}"),
+        };
+
+        let (secondary_tx, secondary_rx) = dial_mock(6)
+            .await
+            .expect("failed to dial secondary connection");
+        secondary_tx
+            .send(HandshakeRequestFrame::JoinRequest { access_token }.encode())
+            .await?;
+
+        // only the primary is allowed to request access tokens
+        secondary_tx
+            .send(RequestFrame::AccessToken { ttl: 1 }.encode())
+            .await?;
+        expect_termination(&secondary_rx, TerminationReason::WrongPermssion).await?;
+
+        // the primary connection should not be affected
+        primary_tx
+            .send(
+                RequestFrame::ServicePayload {
+                    bytes: TEST_PAYLOAD.into(),
+                }
+                .encode(),
+            )
+            .await?;
+        match ResponseFrame::decode(&primary_rx.recv().await?)? {
+            ResponseFrame::ServicePayload { bytes } => assert_eq!(&bytes, TEST_PAYLOAD),
+            f => panic!("expected payload, got {f:?}"),
+        }
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn terminate_replaced_primary() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(7).await?;
+        let (primary_tx, primary_rx) = dial_mock(7)
+            .await
+            .expect("failed to dial primary connection");
+
+        primary_tx
+            .send(
+                HandshakeRequestFrame::Handshake {
+                    retry: None,
+                    service: ECHO_SERVICE,
+                    pk: ClientPublicKey([0; 96]),
+                    pop: ClientSignature([0; 48]),
+                }
+                .encode(),
+            )
+            .await?;
+
+        primary_tx
+            .send(RequestFrame::AccessToken { ttl: 1 }.encode())
+            .await?;
+        let access_token = match ResponseFrame::decode(&primary_rx.recv().await?)? {
+            ResponseFrame::AccessToken { access_token, .. } => *access_token,
+            f => panic!("expected access token, got {f:?}"),
+        };
+        let connection_id = u64::from_be_bytes(*arrayref::array_ref![access_token, 0, 8]);
+
+        // reconnect as the primary, the old primary should be terminated
+        let (new_tx, _new_rx) = dial_mock(7)
+            .await
+            .expect("failed to dial new primary connection");
+        new_tx
+            .send(
+                HandshakeRequestFrame::Handshake {
+                    retry: Some(connection_id),
+                    service: ECHO_SERVICE,
+                    pk: ClientPublicKey([0; 96]),
+                    pop: ClientSignature([0; 48]),
+                }
+                .encode(),
+            )
+            .await?;
+
+        expect_termination(&primary_rx, TerminationReason::ConnectionInUse).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn submit_delivery_acknowledgment() -> Result<()> {
+        let (dack_socket, mut dack_rx) = Socket::raw_bounded(16);
+        let mut shutdown =
+            start_mock_node_with_dack_socket::<MockServiceProvider>(8, dack_socket).await?;
+        let (tx, rx) = dial_mock(8).await.expect("failed to dial");
+
+        let client_sk = ClientSecretKey::generate();
+        tx.send(
+            HandshakeRequestFrame::Handshake {
+                retry: None,
+                service: ECHO_SERVICE,
+                pk: client_sk.to_pk(),
+                pop: ClientSignature([0; 48]),
+            }
+            .encode(),
+        )
+        .await?;
+
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: TEST_PAYLOAD.into(),
+            }
+            .encode(),
+        )
+        .await?;
+        match ResponseFrame::decode(&rx.recv().await?)? {
+            ResponseFrame::ServicePayload { bytes } => assert_eq!(&bytes, TEST_PAYLOAD),
+            f => panic!("expected payload, got {f:?}"),
+        }
+
+        // acknowledge the delivered bytes
+        let commodity = TEST_PAYLOAD.len() as u128;
+        let signature = client_sk.sign(&delivery_acknowledgment_digest(ECHO_SERVICE, commodity));
+        tx.send(
+            RequestFrame::DeliveryAcknowledgment {
+                commodity,
+                signature,
+            }
+            .encode(),
+        )
+        .await?;
+
+        let task = timeout(Duration::from_secs(1), dack_rx.recv())
+            .await
+            .expect("dack should be submitted within 1 second")
+            .expect("dack socket closed");
+        assert_eq!(task.request.service_id, ECHO_SERVICE);
+        assert_eq!(task.request.commodity, commodity);
+
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: TEST_PAYLOAD.into(),
+            }
+            .encode(),
+        )
+        .await?;
+        match ResponseFrame::decode(&rx.recv().await?)? {
+            ResponseFrame::ServicePayload { bytes } => assert_eq!(&bytes, TEST_PAYLOAD),
+            f => panic!("expected payload, got {f:?}"),
+        }
+
+        // an acknowledgment with an invalid signature should terminate the connection
+        tx.send(
+            RequestFrame::DeliveryAcknowledgment {
+                commodity: commodity * 2,
+                signature: ClientSignature([0; 48]),
+            }
+            .encode(),
+        )
+        .await?;
+        expect_termination(&rx, TerminationReason::InvalidDeliveryAcknowledgment).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn primary_resumes_after_secondary_disconnect() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(9).await?;
+        let (primary_tx, primary_rx) = dial_mock(9)
+            .await
+            .expect("failed to dial primary connection");
+
+        primary_tx
+            .send(
+                HandshakeRequestFrame::Handshake {
+                    retry: None,
+                    service: ECHO_SERVICE,
+                    pk: ClientPublicKey([0; 96]),
+                    pop: ClientSignature([0; 48]),
+                }
+                .encode(),
+            )
+            .await?;
+
+        primary_tx
+            .send(RequestFrame::AccessToken { ttl: 1 }.encode())
+            .await?;
+        let access_token = match ResponseFrame::decode(&primary_rx.recv().await?)? {
+            ResponseFrame::AccessToken { access_token, .. } => *access_token,
+            f => panic!("expected access token, got {f:?}"),
+        };
+
+        // join and interact with the service over the secondary connection
+        let (secondary_tx, secondary_rx) = dial_mock(9)
+            .await
+            .expect("failed to dial secondary connection");
+        secondary_tx
+            .send(HandshakeRequestFrame::JoinRequest { access_token }.encode())
+            .await?;
+
+        for _ in 0..10 {
+            secondary_tx
+                .send(
+                    RequestFrame::ServicePayload {
+                        bytes: TEST_PAYLOAD.into(),
+                    }
+                    .encode(),
+                )
+                .await?;
+
+            match ResponseFrame::decode(&secondary_rx.recv().await?)? {
+                ResponseFrame::ServicePayload { bytes } => assert_eq!(&bytes, TEST_PAYLOAD),
+                f => panic!("expected payload, got {f:?}"),
+            }
+        }
+
+        // disconnect the secondary connection and give the proxy a moment to notice
+        drop(secondary_tx);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // the primary connection should keep working
+        for _ in 0..10 {
+            primary_tx
+                .send(
+                    RequestFrame::ServicePayload {
+                        bytes: TEST_PAYLOAD.into(),
+                    }
+                    .encode(),
+                )
+                .await?;
+
+            let bytes = timeout(Duration::from_secs(1), primary_rx.recv())
+                .await
+                .expect("primary should receive a response within 1 second")?;
+            match ResponseFrame::decode(&bytes)? {
+                ResponseFrame::ServicePayload { bytes } => assert_eq!(&bytes, TEST_PAYLOAD),
+                f => panic!("expected payload, got {f:?}"),
+            }
+        }
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn payload_size_limit() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(10).await?;
+        let (tx, rx) = dial_mock(10).await.expect("failed to dial");
+
+        tx.send(
+            HandshakeRequestFrame::Handshake {
+                retry: None,
+                service: ECHO_SERVICE,
+                pk: ClientPublicKey([0; 96]),
+                pop: ClientSignature([0; 48]),
+            }
+            .encode(),
+        )
+        .await?;
+
+        // a payload right at the limit should go through
+        let payload = vec![1; MAX_PAYLOAD_LEN];
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: payload.clone().into(),
+            }
+            .encode(),
+        )
+        .await?;
+        match ResponseFrame::decode(&rx.recv().await?)? {
+            ResponseFrame::ServicePayload { bytes } => assert_eq!(bytes, payload),
+            f => panic!("expected payload, got {f:?}"),
+        }
+
+        // a payload just over the limit should terminate the connection
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: vec![1; MAX_PAYLOAD_LEN + 1].into(),
+            }
+            .encode(),
+        )
+        .await?;
+        expect_termination(&rx, TerminationReason::PayloadTooLarge).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn service_payload_size_limit() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(11).await?;
+        let (tx, rx) = dial_mock(11).await.expect("failed to dial");
+
+        tx.send(
+            HandshakeRequestFrame::Handshake {
+                retry: None,
+                service: OVERSIZED_SERVICE,
+                pk: ClientPublicKey([0; 96]),
+                pop: ClientSignature([0; 48]),
+            }
+            .encode(),
+        )
+        .await?;
+
+        tx.send(
+            RequestFrame::ServicePayload {
+                bytes: TEST_PAYLOAD.into(),
+            }
+            .encode(),
+        )
+        .await?;
+        expect_termination(&rx, TerminationReason::PayloadTooLarge).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reject_revoked_token() -> Result<()> {
+        let mut shutdown = start_mock_node::<MockServiceProvider>(12).await?;
+        let (primary_tx, primary_rx) = dial_mock(12)
+            .await
+            .expect("failed to dial primary connection");
+
+        primary_tx
+            .send(
+                HandshakeRequestFrame::Handshake {
+                    retry: None,
+                    service: ECHO_SERVICE,
+                    pk: ClientPublicKey([0; 96]),
+                    pop: ClientSignature([0; 48]),
+                }
+                .encode(),
+            )
+            .await?;
+
+        // Use a long ttl, so that the token can only be rejected because it was revoked.
+        primary_tx
+            .send(RequestFrame::AccessToken { ttl: 60_000 }.encode())
+            .await?;
+        let access_token = match ResponseFrame::decode(&primary_rx.recv().await?)? {
+            ResponseFrame::AccessToken { access_token, .. } => *access_token,
+            f => panic!("expected access token, got {f:?}"),
+        };
+
+        // revoke the token, try to extend it again, and make sure the proxy handled both before
+        // joining
+        primary_tx
+            .send(RequestFrame::RevokeAccessToken.encode())
+            .await?;
+        primary_tx
+            .send(RequestFrame::ExtendAccessToken { ttl: 60_000 }.encode())
+            .await?;
+        primary_tx
+            .send(
+                RequestFrame::ServicePayload {
+                    bytes: TEST_PAYLOAD.into(),
+                }
+                .encode(),
+            )
+            .await?;
+        match ResponseFrame::decode(&primary_rx.recv().await?)? {
+            ResponseFrame::ServicePayload { bytes } => assert_eq!(&bytes, TEST_PAYLOAD),
+            f => panic!("expected payload, got {f:?}"),
+        }
+
+        let (secondary_tx, secondary_rx) = dial_mock(12)
+            .await
+            .expect("failed to dial secondary connection");
+        secondary_tx
+            .send(HandshakeRequestFrame::JoinRequest { access_token }.encode())
+            .await?;
+        expect_termination(&secondary_rx, TerminationReason::InvalidToken).await?;
+
+        shutdown.shutdown().await;
+        Ok(())
+    }
 }
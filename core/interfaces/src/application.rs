@@ -18,6 +18,7 @@
     Genesis,
     Job,
     NodeIndex,
+    NodeInfoWithIndex,
     Nonce,
     ProtocolParamKey,
     ProtocolParamValue,
@@ -143,6 +144,15 @@ fn get_node_info<V>(&self, node: &NodeIndex, selector: impl FnOnce(NodeInfo) ->
     /// Returns an Iterator to Node Table
     fn get_node_table_iter<V>(&self, closure: impl FnOnce(KeyIterator<NodeIndex>) -> V) -> V;
 
+    /// Returns the nodes that are currently participating in the network.
+    fn get_participating_nodes(&self) -> Vec<NodeInfoWithIndex>;
+
+    /// Returns the nodes whose participation is [`Participation::True`] if `active` is true, or
+    /// all of the other nodes otherwise.
+    ///
+    /// [`Participation::True`]: lightning_types::Participation::True
+    fn get_nodes_by_participation(&self, active: bool) -> Vec<NodeInfoWithIndex>;
+
     /// Query Pub Key to Node Index Table
     fn pubkey_to_index(&self, pub_key: &NodePublicKey) -> Option<NodeIndex>;
 
@@ -293,3 +303,11 @@ pub struct WithdrawPagingParams {
     pub start: u64,
     pub limit: usize,
 }
+
+#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ReputationPagingParams {
+    /// The position of the first measurement to return, after the reporter filter is applied.
+    pub start: usize,
+    /// The maximum number of measurements to return.
+    pub limit: usize,
+}
@@ -2,6 +2,7 @@
 use lightning_schema::LightningMessage;
 use ready::empty::EmptyReadyState;
 use ready::ReadyWaiterState;
+use tokio::sync::watch;
 
 use crate::components::NodeComponents;
 
@@ -16,4 +17,20 @@ pub trait ConsensusInterface<C: NodeComponents>: BuildGraph + Sized + Send + Syn
 
     /// Wait for the consensus component to be ready after starting.
     async fn wait_for_ready(&self) -> Self::ReadyState;
+
+    /// Returns a receiver for the progress of the execution of the parcels received from the
+    /// committee.
+    #[blank = watch::channel(ExecutionProgress::default()).1]
+    fn execution_progress(&self) -> watch::Receiver<ExecutionProgress>;
+}
+
+/// How far the parcels received from the committee have been executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionProgress {
+    /// The number of parcels that have a quorum of attestations, but could not be executed yet
+    /// because the parcels leading up to them are missing.
+    pub pending_parcels: usize,
+    /// The highest sub dag index of the parcels received from the committee. The node is behind
+    /// the committee while the sub dag index of its last executed block is lower than this.
+    pub highest_known_sub_dag_index: u64,
 }
@@ -4,6 +4,14 @@
 use crate::components::NodeComponents;
 use crate::ConfigConsumer;
 
+/// The public keys of a node before and after its keys were rotated.
+pub struct RotatedKeys {
+    pub old_node_pk: Option<NodePublicKey>,
+    pub old_consensus_pk: Option<ConsensusPublicKey>,
+    pub new_node_pk: NodePublicKey,
+    pub new_consensus_pk: ConsensusPublicKey,
+}
+
 #[interfaces_proc::blank]
 pub trait KeystoreInterface<C: NodeComponents>:
     BuildGraph + ConfigConsumer + Clone + Sized + Send + Sync
@@ -25,4 +33,9 @@ pub trait KeystoreInterface<C: NodeComponents>:
     /// Otherwise if true, partial_node_components keys will be preserved and only missing ones will
     /// be generated.
     fn generate_keys(provider: Self::Config, accept_partial: bool) -> anyhow::Result<()>;
+
+    /// Standalone utility to rotate the keys from a given config.
+    /// The existing keys are backed up next to the originals with a timestamp suffix, and new
+    /// keys are generated in their place.
+    fn rotate_keys(config: Self::Config) -> anyhow::Result<RotatedKeys>;
 }
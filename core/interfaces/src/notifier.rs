@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use fdi::BuildGraph;
-use lightning_types::{Block, BlockExecutionResponse};
+use lightning_types::{Block, BlockExecutionResponse, NodeIndex};
 use merklize::StateRootHash;
 
 use crate::components::NodeComponents;
@@ -20,6 +20,13 @@ pub struct EpochChangedNotification {
     pub new_state_root: StateRootHash,
 }
 
+#[derive(Clone, Debug)]
+pub struct CommitteeChangedNotification {
+    pub epoch: u64,
+    pub previous_committee: Vec<NodeIndex>,
+    pub new_committee: Vec<NodeIndex>,
+}
+
 /// # Notifier
 #[interfaces_proc::blank]
 pub trait NotifierInterface<C: NodeComponents>: BuildGraph + Sync + Send + Clone {
@@ -36,6 +43,11 @@ pub trait NotifierInterface<C: NodeComponents>: BuildGraph + Sync + Send + Clone
     #[blank = crate::_hacks::Blanket]
     fn subscribe_epoch_changed(&self) -> impl Subscriber<EpochChangedNotification>;
 
+    /// Subscribe to changes of the committee membership. Unlike epoch changes, this only fires
+    /// when the set of committee members actually differs, which may also happen mid-epoch.
+    #[blank = crate::_hacks::Blanket]
+    fn subscribe_committee_changed(&self) -> impl Subscriber<CommitteeChangedNotification>;
+
     #[blank = crate::_hacks::Blanket]
     fn subscribe_before_epoch_change(&self, duration: Duration) -> impl Subscriber<()>;
 }
@@ -51,6 +63,15 @@ fn epoch_changed(
         new_state_root: StateRootHash,
     );
 
+    /// Notify the waiters about a change of the committee. Nothing is sent if both committees
+    /// have the same members.
+    fn committee_changed(
+        &self,
+        epoch: u64,
+        previous_committee: Vec<NodeIndex>,
+        new_committee: Vec<NodeIndex>,
+    );
+
     /// Notify the waiters about new block.
     fn new_block(&self, block: Block, response: BlockExecutionResponse);
 }
@@ -75,6 +75,14 @@ fn send_to_all<F: Fn(NodeIndex) -> bool + Send + Sync + 'static>(
         filter: F,
     );
     fn send_to_one(&self, node: NodeIndex, payload: Bytes);
+    /// Send the payload to one peer and resolve once the message was written to the peer or the
+    /// attempt failed.
+    #[blank(async { Ok(()) })]
+    fn send_to_one_with_ack(
+        &self,
+        node: NodeIndex,
+        payload: Bytes,
+    ) -> impl futures::Future<Output = io::Result<()>> + Send;
     async fn receive(&mut self) -> Option<(NodeIndex, Bytes)>;
 }
 
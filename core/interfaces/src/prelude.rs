@@ -22,6 +22,10 @@
 pub use crate::{
     ExecutionEngineSocket,
     SignerSubmitTxSocket,
+    SignerConfirmTxSocket,
+    SignerBatchSubmitTxSocket,
+    SignerPendingTxSocket,
+    SignerAccountTxSocket,
     FetcherSocket,
     DeliveryAcknowledgmentSocket,
     MempoolSocket,
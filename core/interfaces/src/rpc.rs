@@ -26,6 +26,9 @@ fn from(sender: broadcast::Sender<Vec<Event>>) -> Self {
 }
 
 impl Events {
+    /// Sends the events to the current subscribers. This never blocks, so that a stalled
+    /// subscriber can't stall block execution. A subscriber that falls behind misses the oldest
+    /// events instead.
     pub fn send(&self, event: Vec<Event>) {
         // Will error if there are no exisiting receivers, however we dont care about that
         let _ = self.0.send(event);
@@ -1,6 +1,12 @@
 use affair::Socket;
 use fdi::BuildGraph;
-use lightning_types::ExecuteTransaction;
+use lightning_types::{
+    ExecuteTransaction,
+    TransactionConfirmationError,
+    TransactionReceipt,
+    UpdateMethod,
+};
+use tokio::sync::oneshot;
 
 use crate::components::NodeComponents;
 
@@ -9,6 +15,31 @@
 /// nonce (which we also refer to as the counter).
 pub type SignerSubmitTxSocket = Socket<ExecuteTransaction, ()>;
 
+/// A socket that submits a batch of transactions to the consensus from our node. All of the
+/// transactions are assigned contiguous nonces, and the response contains the assigned nonces in
+/// the same order as the request. If the signer simulates transactions before submitting them and
+/// any of them reverts, the whole batch is rejected.
+pub type SignerBatchSubmitTxSocket =
+    Socket<Vec<UpdateMethod>, Result<Vec<u64>, TransactionConfirmationError>>;
+
+/// A socket that responds with the nonce and method of every transaction that was submitted by
+/// our node, but has not been ordered yet.
+pub type SignerPendingTxSocket = Socket<(), Vec<(u64, UpdateMethod)>>;
+
+/// A socket that submits a transaction signed with the account owner key configured for the
+/// signer, instead of the node key. The account has its own nonce sequence, independent from the
+/// one of the node. Responds with the assigned nonce, or `None` if no account key is configured,
+/// and with an error if the signer simulated the transaction and it reverted.
+pub type SignerAccountTxSocket =
+    Socket<UpdateMethod, Result<Option<u64>, TransactionConfirmationError>>;
+
+/// A socket that submits a transaction just like [`SignerSubmitTxSocket`], but responds with a
+/// receiver that resolves once the transaction has been ordered and executed.
+pub type SignerConfirmTxSocket = Socket<
+    UpdateMethod,
+    oneshot::Receiver<Result<TransactionReceipt, TransactionConfirmationError>>,
+>;
+
 /// The signature provider is responsible for signing messages using the private key of
 /// the node.
 #[interfaces_proc::blank]
@@ -18,4 +49,26 @@ pub trait SignerInterface<C: NodeComponents>: BuildGraph + Sized + Send + Sync {
     /// implementation.
     #[socket]
     fn get_socket(&self) -> SignerSubmitTxSocket;
+
+    /// Returns a socket that can be used to submit transactions to the mempool and await
+    /// their confirmation. The response of the socket is a receiver that resolves to the
+    /// transaction receipt once the transaction was executed, or to an error if the signer
+    /// gave up on the transaction.
+    #[socket]
+    fn get_confirming_socket(&self) -> SignerConfirmTxSocket;
+
+    /// Returns a socket that can be used to submit a batch of transactions to the mempool.
+    /// The transactions are signed by the node and assigned contiguous nonces atomically.
+    #[socket]
+    fn get_batch_socket(&self) -> SignerBatchSubmitTxSocket;
+
+    /// Returns a socket that can be used to inspect the transactions that were submitted to the
+    /// mempool but have not been ordered yet.
+    #[socket]
+    fn get_pending_socket(&self) -> SignerPendingTxSocket;
+
+    /// Returns a socket that can be used to submit transactions to the mempool that are signed
+    /// by the account owner key of the node operator, if one is configured.
+    #[socket]
+    fn get_account_socket(&self) -> SignerAccountTxSocket;
 }
@@ -2,7 +2,8 @@
 use std::io::Write;
 use std::marker::PhantomData;
 use std::os::unix::fs::PermissionsExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{bail, Context};
 use fleek_crypto::{
@@ -13,6 +14,7 @@
     SecretKey,
 };
 use lightning_interfaces::prelude::*;
+use lightning_interfaces::RotatedKeys;
 use tracing::info;
 use triomphe::Arc;
 
@@ -125,6 +127,69 @@ fn generate_keys(config: Self::Config, accept_partial: bool) -> anyhow::Result<(
 
         Ok(())
     }
+
+    fn rotate_keys(config: Self::Config) -> anyhow::Result<RotatedKeys> {
+        // Decode the existing keys before touching any file, so we don't end up with a partial
+        // rotation if one of them is corrupted.
+        let old_node_pk = read_key(&config.node_key_path, NodeSecretKey::decode_pem)
+            .context("Failed to read node pem file")?
+            .map(|sk| sk.to_pk());
+        let old_consensus_pk = read_key(&config.consensus_key_path, ConsensusSecretKey::decode_pem)
+            .context("Failed to read consensus pem file")?
+            .map(|sk| sk.to_pk());
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        if old_node_pk.is_some() {
+            backup(&config.node_key_path, timestamp)?;
+        }
+        if old_consensus_pk.is_some() {
+            backup(&config.consensus_key_path, timestamp)?;
+        }
+
+        let node_secret_key = NodeSecretKey::generate();
+        save(&config.node_key_path, node_secret_key.encode_pem())?;
+        let consensus_secret_key = ConsensusSecretKey::generate();
+        save(
+            &config.consensus_key_path,
+            consensus_secret_key.encode_pem(),
+        )?;
+
+        let keys = RotatedKeys {
+            old_node_pk,
+            old_consensus_pk,
+            new_node_pk: node_secret_key.to_pk(),
+            new_consensus_pk: consensus_secret_key.to_pk(),
+        };
+        info!("Rotated node key: {}", keys.new_node_pk);
+        info!("Rotated consensus key: {}", keys.new_consensus_pk);
+        Ok(keys)
+    }
+}
+
+fn read_key<T>(path: &Path, decode: fn(&str) -> Option<T>) -> anyhow::Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let encoded = read_to_string(path)?;
+    decode(&encoded)
+        .context("Failed to decode pem file")
+        .map(Some)
+}
+
+fn backup_path(path: &Path, timestamp: u64) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".{timestamp}.bak"));
+    backup.into()
+}
+
+fn backup(path: &Path, timestamp: u64) -> anyhow::Result<()> {
+    let backup = backup_path(path, timestamp);
+    if backup.exists() {
+        bail!("Cannot overwrite existing backup {backup:?}");
+    }
+    fs::rename(path, &backup)?;
+    info!("Backed up {path:?} to {backup:?}");
+    Ok(())
 }
 
 fn save<T: AsRef<[u8]>>(path: &Path, data: T) -> anyhow::Result<()> {
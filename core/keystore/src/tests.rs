@@ -1 +1,94 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
 
+use fleek_crypto::{ConsensusSecretKey, NodeSecretKey, SecretKey};
+use lightning_interfaces::partial_node_components;
+use lightning_interfaces::prelude::*;
+use tempfile::tempdir;
+
+use crate::{Keystore, KeystoreConfig};
+
+partial_node_components!(TestBinding {
+    KeystoreInterface = Keystore<Self>;
+});
+
+fn config(dir: &Path) -> KeystoreConfig {
+    KeystoreConfig {
+        node_key_path: dir.join("node.pem").try_into().unwrap(),
+        consensus_key_path: dir.join("consensus.pem").try_into().unwrap(),
+    }
+}
+
+fn backups(dir: &Path, name: &str) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+            file_name.starts_with(name) && file_name.ends_with(".bak")
+        })
+        .collect()
+}
+
+#[test]
+fn test_rotate_keys() {
+    let dir = tempdir().unwrap();
+    <Keystore<TestBinding> as KeystoreInterface<TestBinding>>::generate_keys(
+        config(dir.path()),
+        false,
+    )
+    .unwrap();
+    let old_node = read_to_string(dir.path().join("node.pem")).unwrap();
+    let old_consensus = read_to_string(dir.path().join("consensus.pem")).unwrap();
+
+    let keys =
+        <Keystore<TestBinding> as KeystoreInterface<TestBinding>>::rotate_keys(config(dir.path()))
+            .unwrap();
+
+    // The old keys are reported and backed up.
+    let old_node_pk = NodeSecretKey::decode_pem(&old_node).unwrap().to_pk();
+    let old_consensus_pk = ConsensusSecretKey::decode_pem(&old_consensus)
+        .unwrap()
+        .to_pk();
+    assert_eq!(keys.old_node_pk, Some(old_node_pk));
+    assert_eq!(keys.old_consensus_pk, Some(old_consensus_pk));
+    let node_backups = backups(dir.path(), "node.pem.");
+    let consensus_backups = backups(dir.path(), "consensus.pem.");
+    assert_eq!(node_backups.len(), 1);
+    assert_eq!(consensus_backups.len(), 1);
+    assert_eq!(read_to_string(&node_backups[0]).unwrap(), old_node);
+    assert_eq!(
+        read_to_string(&consensus_backups[0]).unwrap(),
+        old_consensus
+    );
+
+    // The new keys replace the old ones.
+    let new_node = read_to_string(dir.path().join("node.pem")).unwrap();
+    let new_consensus = read_to_string(dir.path().join("consensus.pem")).unwrap();
+    assert_ne!(keys.new_node_pk, old_node_pk);
+    assert_eq!(
+        NodeSecretKey::decode_pem(&new_node).unwrap().to_pk(),
+        keys.new_node_pk
+    );
+    assert_eq!(
+        ConsensusSecretKey::decode_pem(&new_consensus)
+            .unwrap()
+            .to_pk(),
+        keys.new_consensus_pk
+    );
+}
+
+#[test]
+fn test_rotate_keys_without_existing_keys() {
+    let dir = tempdir().unwrap();
+
+    let keys =
+        <Keystore<TestBinding> as KeystoreInterface<TestBinding>>::rotate_keys(config(dir.path()))
+            .unwrap();
+
+    assert!(keys.old_node_pk.is_none());
+    assert!(keys.old_consensus_pk.is_none());
+    assert!(dir.path().join("node.pem").exists());
+    assert!(dir.path().join("consensus.pem").exists());
+    assert!(backups(dir.path(), "").is_empty());
+}
@@ -1,10 +1,12 @@
+use std::collections::HashSet;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use futures::future::{select, Either};
 use lightning_interfaces::prelude::*;
-use lightning_interfaces::types::{Block, BlockExecutionResponse};
+use lightning_interfaces::types::{Block, BlockExecutionResponse, NodeIndex};
 use lightning_interfaces::{
     BlockExecutedNotification,
+    CommitteeChangedNotification,
     EpochChangedNotification,
     OwnedShutdownSignal,
 };
@@ -73,6 +75,13 @@ fn subscribe_epoch_changed(&self) -> impl Subscriber<EpochChangedNotification> {
         )
     }
 
+    fn subscribe_committee_changed(&self) -> impl Subscriber<CommitteeChangedNotification> {
+        BroadcastSub(
+            self.notify.committee_changed.subscribe(),
+            self.waiter.wait_for_shutdown_owned(),
+        )
+    }
+
     fn subscribe_before_epoch_change(&self, duration: Duration) -> impl Subscriber<()> {
         let (sender, rx) = broadcast::channel(8);
         let epoch_changed = BroadcastSub(
@@ -91,6 +100,7 @@ fn subscribe_before_epoch_change(&self, duration: Duration) -> impl Subscriber<(
 pub struct NotificationsEmitter {
     block_executed: broadcast::Sender<BlockExecutedNotification>,
     epoch_changed: broadcast::Sender<EpochChangedNotification>,
+    committee_changed: broadcast::Sender<CommitteeChangedNotification>,
 }
 
 impl Default for NotificationsEmitter {
@@ -98,6 +108,7 @@ fn default() -> Self {
         Self {
             block_executed: broadcast::channel(64).0,
             epoch_changed: broadcast::channel(16).0,
+            committee_changed: broadcast::channel(16).0,
         }
     }
 }
@@ -125,6 +136,26 @@ fn epoch_changed(
             new_state_root,
         });
     }
+
+    fn committee_changed(
+        &self,
+        epoch: u64,
+        previous_committee: Vec<NodeIndex>,
+        new_committee: Vec<NodeIndex>,
+    ) {
+        // The order of the members does not matter.
+        if previous_committee.iter().collect::<HashSet<_>>()
+            == new_committee.iter().collect::<HashSet<_>>()
+        {
+            return;
+        }
+
+        let _ = self.committee_changed.send(CommitteeChangedNotification {
+            epoch,
+            previous_committee,
+            new_committee,
+        });
+    }
 }
 
 /// Provides an implementation for [`Subscriber`] backed by a tokio broadcast.
@@ -1,9 +1,9 @@
-use lightning_interfaces::{ShutdownController, Subscriber};
+use lightning_interfaces::{Emitter, ShutdownController, Subscriber};
 use tokio::sync::broadcast;
 use tokio::test;
 use tokio::time::{sleep, timeout, Duration};
 
-use crate::BroadcastSub;
+use crate::{BroadcastSub, NotificationsEmitter};
 
 #[test]
 async fn sub_is_cancel_safe() {
@@ -150,3 +150,24 @@ async fn sub_shutdown_3() {
     let ret = timeout(Duration::from_millis(100), sub1.last()).await;
     assert_eq!(ret, Ok(None));
 }
+
+#[test]
+async fn committee_changed_only_fires_on_change() {
+    let ctrl = ShutdownController::new(false);
+    let emitter = NotificationsEmitter::default();
+    let mut sub = BroadcastSub(emitter.committee_changed.subscribe(), ctrl.waiter().into());
+
+    // Same members in a different order is not a change.
+    emitter.committee_changed(1, vec![0, 1, 2], vec![2, 0, 1]);
+    let ret = timeout(Duration::from_millis(10), sub.recv()).await;
+    assert!(ret.is_err());
+
+    emitter.committee_changed(1, vec![0, 1, 2], vec![0, 1, 3]);
+    let notification = timeout(Duration::from_millis(10), sub.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notification.epoch, 1);
+    assert_eq!(notification.previous_committee, vec![0, 1, 2]);
+    assert_eq!(notification.new_committee, vec![0, 1, 3]);
+}
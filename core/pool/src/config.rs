@@ -7,6 +7,19 @@
 pub struct Config {
     #[serde(with = "humantime_serde")]
     pub max_idle_timeout: Duration,
+    /// The maximum number of connections, incoming and outgoing, that the node will keep open
+    /// at the same time. Unlimited if not set.
+    pub max_concurrent_connections: Option<usize>,
+    /// The interval at which keep-alive packets are sent on outgoing connections. Defaults to
+    /// half of `max_idle_timeout` if not set.
+    #[serde(with = "humantime_serde", default)]
+    pub keep_alive_interval: Option<Duration>,
+    /// The maximum number of bi-directional streams a peer may open on a connection. Uses the
+    /// transport's default if not set.
+    pub max_concurrent_bidi_streams: Option<u32>,
+    /// Limits the rate of broadcast messages accepted from each peer. Messages that exceed the
+    /// limit are dropped. Unlimited if not set.
+    pub peer_message_rate_limit: Option<RateLimitConfig>,
     pub address: SocketAddr,
     pub http: Option<SocketAddr>,
 }
@@ -15,8 +28,20 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             max_idle_timeout: Duration::from_millis(30000),
+            max_concurrent_connections: None,
+            keep_alive_interval: None,
+            max_concurrent_bidi_streams: None,
+            peer_message_rate_limit: None,
             address: "0.0.0.0:4300".parse().expect("Hardcoded socket address"),
             http: None,
         }
     }
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// The sustained number of messages per second accepted from a peer.
+    pub messages_per_sec: u32,
+    /// The number of messages a peer can send in a burst above the sustained rate.
+    pub burst: u32,
+}
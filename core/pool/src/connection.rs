@@ -1,21 +1,27 @@
 use std::io;
+use std::time::Duration;
 
 use anyhow::Result;
 use bytes::{Buf, Bytes};
 use futures::{SinkExt, StreamExt};
 use lightning_interfaces::types::NodeIndex;
 use lightning_interfaces::{spawn, RequestHeader, ServiceScope};
+use lightning_metrics::increment_counter;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::oneshot;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 
 use crate::event::{Event, Message};
-use crate::muxer::{ConnectionInterface, NetChannel};
+use crate::muxer::{CloseReason, ConnectionInterface, NetChannel};
 use crate::provider;
 use crate::provider::{Response, Status};
 use crate::state::Stats;
 
+// How long we wait for the in-flight streams of a connection to finish before closing it. This
+// has to be shorter than the time the endpoint waits for the connections to drop on shutdown.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(3);
+
 /// Context for driving the connection.
 pub struct Context<C> {
     /// The multiplexed connection.
@@ -52,6 +58,7 @@ pub async fn connection_loop<C: ConnectionInterface>(mut ctx: Context<C>) -> Res
                 let (stream_tx, stream_rx) = match accept_result {
                     Ok(streams) => streams,
                     Err(e) => {
+                        record_peer_close_reason(&ctx.connection, ctx.peer);
                         return Err(e.into());
                     }
                 };
@@ -75,6 +82,7 @@ pub async fn connection_loop<C: ConnectionInterface>(mut ctx: Context<C>) -> Res
                 let stream_rx = match accept_result {
                     Ok(stream) => stream,
                     Err(e) => {
+                        record_peer_close_reason(&ctx.connection, ctx.peer);
                         return Err(e.into());
                     }
                 };
@@ -109,6 +117,22 @@ pub async fn connection_loop<C: ConnectionInterface>(mut ctx: Context<C>) -> Res
                             }
                         }, "POOL: send message");
                     },
+                    Some(Request::SendMessageWithAck { message, respond }) => {
+                        tracing::trace!("handling a message request with acknowledgment");
+                        let connection = ctx.connection.clone();
+                        let peer = ctx.peer;
+                        spawn!(async move {
+                            let result = send_message(connection, message).await;
+                            if let Err(e) = &result {
+                                tracing::error!(
+                                    "failed to send message to peer with index {peer}: {e:?}"
+                                );
+                            }
+                            let _ = respond.send(
+                                result.map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+                            );
+                        }, "POOL: send message with ack");
+                    },
                     Some(Request::SendReqResp { service, request, respond }) => {
                         tracing::trace!("handling new outgoing request");
                         // We need to create a new stream on the connection for the channel.
@@ -131,12 +155,20 @@ pub async fn connection_loop<C: ConnectionInterface>(mut ctx: Context<C>) -> Res
                         tracing::trace!("handling new stats request");
                         let _ = respond.send(ctx.connection.stats());
                     }
-                    Some(Request::Close) | None => {
+                    Some(Request::Close) => {
+                        tracing::trace!(
+                            "draining the connection with peer {}",
+                            ctx.peer
+                        );
+                        ctx.connection.drain(DRAIN_TIMEOUT).await;
+                        break
+                    },
+                    None => {
                         tracing::trace!(
                             "closing the connection with peer {}",
                             ctx.peer
                         );
-                        ctx.connection.close(0u8, b"close from disconnect");
+                        ctx.connection.close_with(CloseReason::Disconnect);
                         break
                     },
                 }
@@ -146,6 +178,18 @@ pub async fn connection_loop<C: ConnectionInterface>(mut ctx: Context<C>) -> Res
 
     Ok(())
 }
+
+fn record_peer_close_reason<C: ConnectionInterface>(connection: &C, peer: NodeIndex) {
+    if let Some(reason) = connection.close_reason() {
+        tracing::debug!("peer with index {peer} closed the connection: {reason}");
+        increment_counter!(
+            "pool_connection_closed_by_peer",
+            Some("Counter for connections closed by peers, by the reason they gave"),
+            "reason" => reason.as_str()
+        );
+    }
+}
+
 async fn handle_incoming_uni_stream<C: ConnectionInterface>(
     peer: NodeIndex,
     stream_rx: C::RecvStream,
@@ -261,6 +305,10 @@ async fn send_request<C: ConnectionInterface>(
 /// Requests that will be performed on a connection.
 pub enum Request {
     SendMessage(Message),
+    SendMessageWithAck {
+        message: Message,
+        respond: oneshot::Sender<io::Result<()>>,
+    },
     SendReqResp {
         service: ServiceScope,
         request: Bytes,
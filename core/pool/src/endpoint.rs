@@ -24,7 +24,7 @@
 use crate::connection::Context;
 use crate::event::{Event, Message};
 use crate::logical_pool::ConnectionInfo;
-use crate::muxer::{ConnectionInterface, MuxerInterface};
+use crate::muxer::{CloseReason, ConnectionInterface, MuxerInterface};
 use crate::provider::Response;
 use crate::ready::{PoolReadyState, PoolReadyWaiter};
 use crate::state::{DialInfo, EndpointInfo, NodeInfo, TransportConnectionInfo};
@@ -241,6 +241,33 @@ fn handle_outgoing_request(
         Ok(())
     }
 
+    fn handle_outgoing_message_with_ack(
+        &mut self,
+        dst: ConnectionInfo,
+        message: Message,
+        respond: oneshot::Sender<io::Result<()>>,
+    ) -> anyhow::Result<()> {
+        let request = connection::Request::SendMessageWithAck { message, respond };
+        match self.pool.get(&dst.node_info.index) {
+            None => {
+                let peer_index = dst.node_info.index;
+                self.enqueue_dial_task(
+                    dst.node_info,
+                    self.muxer
+                        .clone()
+                        .expect("Endpoint is always initialized on start"),
+                    None,
+                )?;
+                self.enqueue_pending_request(peer_index, request);
+            },
+            Some(handle) => {
+                let ongoing_conn_tx = handle.service_request_tx.clone();
+                self.enqueue_request_for_connection(ongoing_conn_tx, request);
+            },
+        }
+        Ok(())
+    }
+
     fn handle_outgoing_message(
         &mut self,
         dst: Vec<ConnectionInfo>,
@@ -436,7 +463,7 @@ fn handle_new_connection(&mut self, connection: M::Connection) {
             if self.pool.contains_key(&peer_index) && self.redundant_pool.contains_key(&peer_index)
             {
                 tracing::warn!("too many redundant connections with peer {peer_index:?}");
-                connection.close(0u8, b"close from disconnect");
+                connection.close_with(CloseReason::Redundant);
                 return;
             }
 
@@ -624,6 +651,13 @@ fn handle_task(&mut self, task: EndpointTask) -> anyhow::Result<()> {
             EndpointTask::SendMessage { peers, message } => {
                 let _ = self.handle_outgoing_message(peers, message);
             },
+            EndpointTask::SendMessageWithAck {
+                peer,
+                message,
+                respond,
+            } => {
+                let _ = self.handle_outgoing_message_with_ack(peer, message, respond);
+            },
             EndpointTask::SendRequest {
                 dst,
                 service,
@@ -680,17 +714,26 @@ fn garbage_collect_closed_connections(&mut self, peer: NodeIndex, connection_id:
     fn send_connection_failed_notification(&mut self, dst: NodeIndex) {
         if let Some(requests) = self.pending_task.remove(&dst) {
             for request in requests {
-                if let connection::Request::SendReqResp { respond, .. } = request {
-                    self.ongoing_async_tasks.push(spawn!(
-                        async move {
-                            let _ = respond.send(Err(io::Error::new(
-                                io::ErrorKind::ConnectionRefused,
-                                "failed to connect to peer",
-                            )));
-                            AsyncTaskResult::GenericTaskEnded
-                        },
-                        "POOL: notify connection attempt failed"
-                    ));
+                match request {
+                    connection::Request::SendReqResp { respond, .. } => {
+                        self.ongoing_async_tasks.push(spawn!(
+                            async move {
+                                let _ = respond.send(Err(io::Error::new(
+                                    io::ErrorKind::ConnectionRefused,
+                                    "failed to connect to peer",
+                                )));
+                                AsyncTaskResult::GenericTaskEnded
+                            },
+                            "POOL: notify connection attempt failed"
+                        ));
+                    },
+                    connection::Request::SendMessageWithAck { respond, .. } => {
+                        let _ = respond.send(Err(io::Error::new(
+                            io::ErrorKind::ConnectionRefused,
+                            "failed to connect to peer",
+                        )));
+                    },
+                    _ => {},
                 }
             }
         }
@@ -880,6 +923,11 @@ pub enum EndpointTask {
         peers: Vec<ConnectionInfo>,
         message: Message,
     },
+    SendMessageWithAck {
+        peer: ConnectionInfo,
+        message: Message,
+        respond: oneshot::Sender<io::Result<()>>,
+    },
     SendRequest {
         dst: NodeInfo,
         service: ServiceScope,
@@ -2,7 +2,7 @@
 use std::future::Future;
 use std::io;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use fleek_crypto::NodePublicKey;
@@ -10,6 +10,7 @@
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::NodeIndex;
 use lightning_interfaces::{RequestHeader, ServiceScope};
+use lightning_metrics::increment_counter;
 use lightning_types::PeerFilter;
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{Receiver, Sender};
@@ -18,9 +19,11 @@
 use tracing::info;
 use x509_parser::nom::AsBytes;
 
+use crate::config::RateLimitConfig;
 use crate::endpoint::EndpointTask;
 use crate::logical_pool::LogicalPool;
 use crate::provider::{Request, Response};
+use crate::rate_limit::PeerRateLimiter;
 use crate::state::{ConnectionInfo, DialInfo, EventReceiverInfo};
 
 /// If a connection ended and the duration was shorter than `CONN_DURATION_THRESHOLD`,
@@ -53,6 +56,12 @@ pub enum Event {
         request: Bytes,
         respond: oneshot::Sender<io::Result<Response>>,
     },
+    SendMessageWithAck {
+        dst: NodeIndex,
+        service_scope: ServiceScope,
+        message: Bytes,
+        respond: oneshot::Sender<io::Result<()>>,
+    },
     MessageReceived {
         remote: NodeIndex,
         message: Message,
@@ -85,6 +94,8 @@ pub struct EventReceiver<C: NodeComponents> {
     ongoing_async_tasks: FuturesUnordered<JoinHandle<anyhow::Result<()>>>,
     /// Information about attempted connection dials.
     dial_info: Arc<scc::HashMap<NodeIndex, DialInfo>>,
+    /// Limits the rate of incoming broadcast messages per peer.
+    pub(crate) rate_limiter: Option<PeerRateLimiter>,
 }
 
 impl<C> EventReceiver<C>
@@ -99,6 +110,7 @@ pub fn new(
         pool_queue: Sender<EndpointTask>,
         public_key: NodePublicKey,
         dial_info: Arc<scc::HashMap<NodeIndex, DialInfo>>,
+        rate_limit: Option<RateLimitConfig>,
     ) -> Self {
         let logical_pool = LogicalPool::<C>::new(sync_query.clone(), public_key);
 
@@ -111,6 +123,7 @@ pub fn new(
             send_request_service_handles: HashMap::new(),
             ongoing_async_tasks: FuturesUnordered::new(),
             dial_info,
+            rate_limiter: rate_limit.as_ref().map(PeerRateLimiter::new),
         }
     }
 
@@ -187,6 +200,13 @@ fn handle_new_topology(&mut self) {
         // the sender to block.
         let conns = self.topology_rx.borrow_and_update().clone();
         let endpoint_task = self.handler.update_connections(conns);
+        if let (Some(limiter), EndpointTask::Update { drop, .. }) =
+            (self.rate_limiter.as_mut(), &endpoint_task)
+        {
+            for peer in drop {
+                limiter.remove(peer);
+            }
+        }
         self.enqueue_endpoint_task(endpoint_task);
     }
 
@@ -208,6 +228,14 @@ pub(crate) fn handle_event(&mut self, event: Event) -> anyhow::Result<()> {
             } => {
                 let _ = self.handle_outgoing_request(dst, service_scope, request, respond);
             },
+            Event::SendMessageWithAck {
+                dst,
+                service_scope,
+                message,
+                respond,
+            } => {
+                self.handle_outgoing_message_with_ack(dst, service_scope, message, respond);
+            },
             Event::MessageReceived { remote, message } => {
                 self.handle_incoming_broadcast(remote, message);
             },
@@ -290,6 +318,12 @@ fn handle_new_connection(&mut self, peer: NodeIndex, service_request_sent: bool)
 
     #[inline]
     fn handle_closed_connection(&mut self, peer: NodeIndex) {
+        // The bucket of the peer is kept until it refills, otherwise the peer could reset its
+        // budget by reconnecting.
+        if let Some(limiter) = self.rate_limiter.as_mut() {
+            limiter.remove_refilled(Instant::now());
+        }
+
         if let Some(info) = self.handler.pool.get(&peer) {
             if info.from_topology {
                 // A connection to a peer in our topology was ended or failed.
@@ -378,8 +412,35 @@ fn handle_outgoing_request(
     }
 
     #[inline]
-    fn handle_incoming_broadcast(&self, remote: NodeIndex, message: Message) {
+    fn handle_outgoing_message_with_ack(
+        &self,
+        dst: NodeIndex,
+        service_scope: ServiceScope,
+        message: Bytes,
+        respond: oneshot::Sender<io::Result<()>>,
+    ) {
+        if let Some(task) =
+            self.handler
+                .process_outgoing_message_with_ack(dst, service_scope, message, respond)
+        {
+            self.enqueue_endpoint_task(task);
+        }
+    }
+
+    #[inline]
+    fn handle_incoming_broadcast(&mut self, remote: NodeIndex, message: Message) {
         if self.handler.process_received_message(&remote) {
+            if let Some(limiter) = self.rate_limiter.as_mut() {
+                if !limiter.check(remote) {
+                    increment_counter!(
+                        "pool_rate_limited_messages",
+                        Some("Counter for incoming messages dropped due to the peer rate limit")
+                    );
+                    tracing::trace!("dropping message from peer {remote}: rate limit exceeded");
+                    return;
+                }
+            }
+
             if let Some(sender) = self
                 .broadcast_service_handles
                 .get(&message.service)
@@ -454,6 +515,15 @@ pub async fn run(&mut self) {
     }
 }
 
+/// The version of the wire format of [`Message`].
+pub const MESSAGE_VERSION: u8 = 0;
+
+/// The size of the version, service scope and payload length that precede the payload.
+const MESSAGE_HEADER_LEN: usize = 6;
+
+/// A message sent to a peer over a uni-directional stream.
+///
+/// It is encoded as `[version: u8][service: u8][payload length: u32 be][payload]`.
 #[derive(Clone, Debug)]
 pub struct Message {
     pub service: ServiceScope,
@@ -465,19 +535,40 @@ impl TryFrom<BytesMut> for Message {
 
     fn try_from(value: BytesMut) -> anyhow::Result<Self> {
         let bytes = value.as_bytes();
-        if bytes.is_empty() {
-            return Err(anyhow::anyhow!("Cannot convert empty bytes into a message"));
+        if bytes.len() < MESSAGE_HEADER_LEN {
+            return Err(anyhow::anyhow!(
+                "message is truncated: expected at least {MESSAGE_HEADER_LEN} bytes but got {}",
+                bytes.len()
+            ));
+        }
+        if bytes[0] != MESSAGE_VERSION {
+            return Err(anyhow::anyhow!(
+                "unsupported message version {}, expected {MESSAGE_VERSION}",
+                bytes[0]
+            ));
+        }
+        let service = ServiceScope::try_from(bytes[1])?;
+        let len = u32::from_be_bytes(bytes[2..MESSAGE_HEADER_LEN].try_into().unwrap()) as usize;
+        let payload = &bytes[MESSAGE_HEADER_LEN..];
+        if payload.len() != len {
+            return Err(anyhow::anyhow!(
+                "message payload length mismatch: header says {len} bytes but got {}",
+                payload.len()
+            ));
         }
-        let service = ServiceScope::try_from(bytes[0])?;
-        let payload = bytes[1..bytes.len()].to_vec();
-        Ok(Self { service, payload })
+        Ok(Self {
+            service,
+            payload: payload.to_vec(),
+        })
     }
 }
 
 impl From<Message> for Bytes {
     fn from(value: Message) -> Self {
-        let mut buf = BytesMut::with_capacity(value.payload.len() + 1);
+        let mut buf = BytesMut::with_capacity(value.payload.len() + MESSAGE_HEADER_LEN);
+        buf.put_u8(MESSAGE_VERSION);
         buf.put_u8(value.service as u8);
+        buf.put_u32(value.payload.len() as u32);
         buf.put_slice(&value.payload);
         buf.into()
     }
@@ -6,11 +6,12 @@
 mod logical_pool;
 pub mod muxer;
 mod provider;
+mod rate_limit;
 mod ready;
 mod state;
 #[cfg(test)]
 mod tests;
 mod tls;
 
-pub use config::Config;
+pub use config::{Config, RateLimitConfig};
 pub use provider::PoolProvider;
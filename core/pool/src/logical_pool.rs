@@ -296,6 +296,34 @@ pub fn process_outgoing_broadcast(
         }
     }
 
+    pub fn process_outgoing_message_with_ack(
+        &self,
+        dst: NodeIndex,
+        service_scope: ServiceScope,
+        message: Bytes,
+        respond: oneshot::Sender<io::Result<()>>,
+    ) -> Option<EndpointTask> {
+        match self.pool.get(&dst) {
+            Some(info) => Some(EndpointTask::SendMessageWithAck {
+                peer: info.clone(),
+                message: Message {
+                    service: service_scope,
+                    payload: message.to_vec(),
+                },
+                respond,
+            }),
+            None => {
+                if respond
+                    .send(Err(io::ErrorKind::NotConnected.into()))
+                    .is_err()
+                {
+                    tracing::error!("sender dropped the channel")
+                }
+                None
+            },
+        }
+    }
+
     pub fn process_outgoing_request(
         &mut self,
         dst: NodeIndex,
@@ -0,0 +1,321 @@
+//! An in-memory muxer that connects peers in the same process over [`tokio::io::duplex`] pipes,
+//! so that the pool can be tested without binding sockets or doing TLS handshakes.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fleek_crypto::NodePublicKey;
+use tokio::io::{DuplexStream, ReadHalf, WriteHalf};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::muxer::tracker::{StreamTracker, Tracked};
+use crate::muxer::{CloseReason, ConnectionInterface, MuxerInterface};
+use crate::state::{NodeInfo, Stats};
+
+// The size of the in-memory buffer of every stream.
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+static NEXT_CONNECTION_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Connects the muxers that share it, keyed by their public keys.
+#[derive(Clone, Default)]
+pub struct MockRegistry(Arc<Mutex<RegistryState>>);
+
+#[derive(Default)]
+struct RegistryState {
+    listeners: HashMap<NodePublicKey, Listener>,
+    failing: HashSet<NodePublicKey>,
+    latency: Duration,
+}
+
+struct Listener {
+    address: SocketAddr,
+    incoming_tx: mpsc::UnboundedSender<Connection>,
+}
+
+impl MockRegistry {
+    /// Makes every following attempt to connect to the peer fail.
+    pub fn fail_connections_to(&self, pk: NodePublicKey) {
+        self.0.lock().unwrap().failing.insert(pk);
+    }
+
+    /// Undoes [`MockRegistry::fail_connections_to`].
+    pub fn allow_connections_to(&self, pk: &NodePublicKey) {
+        self.0.lock().unwrap().failing.remove(pk);
+    }
+
+    /// Delays every following connection attempt and every new stream by `latency`.
+    pub fn set_latency(&self, latency: Duration) {
+        self.0.lock().unwrap().latency = latency;
+    }
+
+    fn latency(&self) -> Duration {
+        self.0.lock().unwrap().latency
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub registry: MockRegistry,
+    pub pk: NodePublicKey,
+    /// The address reported to peers. Nothing is bound to it.
+    pub address: SocketAddr,
+}
+
+#[derive(Clone)]
+pub struct MockMuxer {
+    registry: MockRegistry,
+    pk: NodePublicKey,
+    address: SocketAddr,
+    incoming_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Connection>>>,
+}
+
+pub type Connecting = Pin<Box<dyn Future<Output = io::Result<Connection>> + Send>>;
+
+impl MuxerInterface for MockMuxer {
+    type Connecting = Connecting;
+    type Connection = Connection;
+    type Config = Config;
+
+    fn init(config: Self::Config) -> io::Result<Self> {
+        let (incoming_tx, incoming_rx) = mpsc::unbounded_channel();
+        let mut registry = config.registry.0.lock().unwrap();
+        if registry.listeners.contains_key(&config.pk) {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        registry.listeners.insert(
+            config.pk,
+            Listener {
+                address: config.address,
+                incoming_tx,
+            },
+        );
+        drop(registry);
+
+        Ok(Self {
+            registry: config.registry,
+            pk: config.pk,
+            address: config.address,
+            incoming_rx: Arc::new(tokio::sync::Mutex::new(incoming_rx)),
+        })
+    }
+
+    async fn connect(&self, peer: NodeInfo, _server_name: &str) -> io::Result<Self::Connecting> {
+        let registry = self.registry.0.lock().unwrap();
+        if registry.failing.contains(&peer.pk) {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        }
+        let listener = registry
+            .listeners
+            .get(&peer.pk)
+            .ok_or(io::Error::from(io::ErrorKind::ConnectionRefused))?;
+
+        let latency = registry.latency;
+        let (local, remote) = Connection::pair(
+            (self.pk, self.address),
+            (peer.pk, listener.address),
+            self.registry.clone(),
+        );
+        listener
+            .incoming_tx
+            .send(remote)
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionRefused))?;
+
+        Ok(Box::pin(async move {
+            tokio::time::sleep(latency).await;
+            Ok(local)
+        }))
+    }
+
+    fn listen_address(&self) -> io::Result<SocketAddr> {
+        Ok(self.address)
+    }
+
+    async fn accept(&self) -> Option<Self::Connecting> {
+        let connection = self.incoming_rx.lock().await.recv().await?;
+        Some(Box::pin(async move { Ok(connection) }))
+    }
+
+    async fn close(&self) {
+        self.registry.0.lock().unwrap().listeners.remove(&self.pk);
+        self.incoming_rx.lock().await.close();
+    }
+}
+
+type BiStream = (ReadHalf<DuplexStream>, WriteHalf<DuplexStream>);
+
+/// One end of an in-memory connection. Streams opened on one end are accepted on the other.
+#[derive(Clone)]
+pub struct Connection {
+    id: usize,
+    peer: NodePublicKey,
+    remote_address: SocketAddr,
+    registry: MockRegistry,
+    bi_tx: mpsc::UnboundedSender<BiStream>,
+    uni_tx: mpsc::UnboundedSender<ReadHalf<DuplexStream>>,
+    bi_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<BiStream>>>,
+    uni_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<ReadHalf<DuplexStream>>>>,
+    // Shared by both ends, cancelled once either end closes the connection.
+    closed: CancellationToken,
+    // Shared by both ends, the id of the end that closed the connection and the error code.
+    close_code: Arc<Mutex<Option<(usize, u8)>>>,
+    streams: Arc<StreamTracker>,
+}
+
+impl Connection {
+    fn pair(
+        local: (NodePublicKey, SocketAddr),
+        remote: (NodePublicKey, SocketAddr),
+        registry: MockRegistry,
+    ) -> (Self, Self) {
+        let (local_bi_tx, remote_bi_rx) = mpsc::unbounded_channel();
+        let (remote_bi_tx, local_bi_rx) = mpsc::unbounded_channel();
+        let (local_uni_tx, remote_uni_rx) = mpsc::unbounded_channel();
+        let (remote_uni_tx, local_uni_rx) = mpsc::unbounded_channel();
+        let closed = CancellationToken::new();
+        let close_code = Arc::new(Mutex::new(None));
+
+        let local_end = Self {
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            peer: remote.0,
+            remote_address: remote.1,
+            registry: registry.clone(),
+            bi_tx: local_bi_tx,
+            uni_tx: local_uni_tx,
+            bi_rx: Arc::new(tokio::sync::Mutex::new(local_bi_rx)),
+            uni_rx: Arc::new(tokio::sync::Mutex::new(local_uni_rx)),
+            closed: closed.clone(),
+            close_code: close_code.clone(),
+            streams: Arc::new(StreamTracker::default()),
+        };
+        let remote_end = Self {
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            peer: local.0,
+            remote_address: local.1,
+            registry,
+            bi_tx: remote_bi_tx,
+            uni_tx: remote_uni_tx,
+            bi_rx: Arc::new(tokio::sync::Mutex::new(remote_bi_rx)),
+            uni_rx: Arc::new(tokio::sync::Mutex::new(remote_uni_rx)),
+            closed,
+            close_code,
+            streams: Arc::new(StreamTracker::default()),
+        };
+        (local_end, remote_end)
+    }
+
+    async fn before_open(&self) -> io::Result<()> {
+        if self.closed.is_cancelled() {
+            return Err(io::ErrorKind::ConnectionAborted.into());
+        }
+        self.streams.ensure_not_draining()?;
+        tokio::time::sleep(self.registry.latency()).await;
+        Ok(())
+    }
+}
+
+impl ConnectionInterface for Connection {
+    type SendStream = Tracked<WriteHalf<DuplexStream>>;
+    type RecvStream = Tracked<ReadHalf<DuplexStream>>;
+
+    async fn open_bi_stream(&mut self) -> io::Result<(Self::SendStream, Self::RecvStream)> {
+        self.before_open().await?;
+        let (local, remote) = tokio::io::duplex(STREAM_BUFFER_SIZE);
+        self.bi_tx
+            .send(tokio::io::split(remote))
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionAborted))?;
+        let (recv, send) = tokio::io::split(local);
+        let guard = self.streams.track();
+        Ok((Tracked::new(send, guard.clone()), Tracked::new(recv, guard)))
+    }
+
+    async fn open_uni_stream(&mut self) -> io::Result<Self::SendStream> {
+        self.before_open().await?;
+        let (local, remote) = tokio::io::duplex(STREAM_BUFFER_SIZE);
+        self.uni_tx
+            .send(tokio::io::split(remote).0)
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionAborted))?;
+        let (_, send) = tokio::io::split(local);
+        Ok(Tracked::new(send, self.streams.track()))
+    }
+
+    async fn accept_bi_stream(&mut self) -> io::Result<(Self::SendStream, Self::RecvStream)> {
+        let mut bi_rx = self.bi_rx.lock().await;
+        tokio::select! {
+            _ = self.closed.cancelled() => Err(io::ErrorKind::ConnectionAborted.into()),
+            stream = bi_rx.recv() => {
+                let (recv, send) =
+                    stream.ok_or(io::Error::from(io::ErrorKind::ConnectionAborted))?;
+                let guard = self.streams.track();
+                Ok((Tracked::new(send, guard.clone()), Tracked::new(recv, guard)))
+            }
+        }
+    }
+
+    async fn accept_uni_stream(&mut self) -> io::Result<Self::RecvStream> {
+        let mut uni_rx = self.uni_rx.lock().await;
+        tokio::select! {
+            _ = self.closed.cancelled() => Err(io::ErrorKind::ConnectionAborted.into()),
+            stream = uni_rx.recv() => {
+                let recv = stream.ok_or(io::Error::from(io::ErrorKind::ConnectionAborted))?;
+                Ok(Tracked::new(recv, self.streams.track()))
+            }
+        }
+    }
+
+    fn peer_identity(&self) -> Option<NodePublicKey> {
+        Some(self.peer)
+    }
+
+    fn remote_address(&self) -> SocketAddr {
+        self.remote_address
+    }
+
+    fn connection_id(&self) -> usize {
+        self.id
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            rtt: self.registry.latency() * 2,
+            lost_packets: 0,
+            sent_packets: 0,
+            congestion_events: 0,
+            cwnd: 0,
+            black_holes_detected: 0,
+        }
+    }
+
+    fn close(&self, error_code: u8, _reason: &[u8]) {
+        self.close_code
+            .lock()
+            .unwrap()
+            .get_or_insert((self.id, error_code));
+        self.closed.cancel();
+    }
+
+    fn close_with(&self, reason: CloseReason) {
+        self.close(reason.error_code(), reason.as_str().as_bytes());
+    }
+
+    fn close_reason(&self) -> Option<CloseReason> {
+        match *self.close_code.lock().unwrap() {
+            Some((closed_by, error_code)) if closed_by != self.id => {
+                CloseReason::from_error_code(error_code)
+            },
+            _ => None,
+        }
+    }
+
+    async fn drain(&self, timeout: Duration) {
+        self.streams.drain(timeout).await;
+        self.close_with(CloseReason::Shutdown);
+    }
+}
@@ -1,10 +1,14 @@
+#[cfg(test)]
+pub mod mock;
 pub mod quinn;
+mod tracker;
 
 use std::future::Future;
-use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
+use std::{fmt, io};
 
 use bytes::Bytes;
 use fleek_crypto::NodePublicKey;
@@ -55,6 +59,72 @@ pub trait _ConnectionInterface: Clone + Send + 'static {
     fn connection_id(&self) -> usize;
     fn stats(&self) -> Stats;
     fn close(&self, error_code: u8, reason: &[u8]);
+    fn close_with(&self, reason: CloseReason);
+    /// Returns the reason the peer gave for closing the connection, if it closed it.
+    fn close_reason(&self) -> Option<CloseReason>;
+    /// Stops opening and accepting new streams, waits up to `timeout` for the existing streams
+    /// to finish and then closes the connection with [`CloseReason::Shutdown`].
+    async fn drain(&self, timeout: Duration);
+}
+
+/// Why a connection was closed. Sent to the peer as the application error code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The connection is no longer needed.
+    Disconnect,
+    /// The node is shutting down.
+    Shutdown,
+    /// The connection was idle for too long.
+    Idle,
+    /// The peer misbehaved.
+    Misbehavior,
+    /// The peer runs an incompatible version.
+    VersionMismatch,
+    /// There is already a redundant connection with the peer.
+    Redundant,
+}
+
+impl CloseReason {
+    /// Returns the error code sent to the peer. These values must stay stable.
+    pub fn error_code(&self) -> u8 {
+        match self {
+            CloseReason::Disconnect => 0,
+            CloseReason::Shutdown => 1,
+            CloseReason::Idle => 2,
+            CloseReason::Misbehavior => 3,
+            CloseReason::VersionMismatch => 4,
+            CloseReason::Redundant => 5,
+        }
+    }
+
+    pub fn from_error_code(error_code: u8) -> Option<Self> {
+        match error_code {
+            0 => Some(CloseReason::Disconnect),
+            1 => Some(CloseReason::Shutdown),
+            2 => Some(CloseReason::Idle),
+            3 => Some(CloseReason::Misbehavior),
+            4 => Some(CloseReason::VersionMismatch),
+            5 => Some(CloseReason::Redundant),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloseReason::Disconnect => "disconnect",
+            CloseReason::Shutdown => "shutdown",
+            CloseReason::Idle => "idle",
+            CloseReason::Misbehavior => "misbehavior",
+            CloseReason::VersionMismatch => "version mismatch",
+            CloseReason::Redundant => "redundant connection",
+        }
+    }
+}
+
+impl fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// A bi-directional channel intended for sending/receiving
@@ -2,15 +2,17 @@
 use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use fleek_crypto::{NodePublicKey, NodeSecretKey};
-use quinn::{ClientConfig, Endpoint, RecvStream, SendStream, ServerConfig, TransportConfig};
+use quinn::{ClientConfig, Endpoint, ServerConfig, TransportConfig, VarInt};
 use rustls::Certificate;
 
-use crate::muxer::{ConnectionInterface, MuxerInterface};
+use crate::muxer::tracker::{StreamTracker, Tracked};
+use crate::muxer::{CloseReason, ConnectionInterface, MuxerInterface};
 use crate::state::{NodeInfo, Stats};
 use crate::tls;
 
@@ -20,6 +22,9 @@ pub struct Config {
     pub address: SocketAddr,
     pub sk: NodeSecretKey,
     pub max_idle_timeout: Duration,
+    pub max_concurrent_connections: Option<usize>,
+    pub keep_alive_interval: Option<Duration>,
+    pub max_concurrent_bidi_streams: Option<u32>,
 }
 
 #[derive(Clone)]
@@ -27,6 +32,17 @@ pub struct QuinnMuxer {
     endpoint: Endpoint,
     sk: NodeSecretKey,
     max_idle_timeout: Duration,
+    max_concurrent_connections: Option<usize>,
+    keep_alive_interval: Option<Duration>,
+    max_concurrent_bidi_streams: Option<u32>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl QuinnMuxer {
+    /// Returns the number of established connections that are still alive.
+    pub fn connection_count(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
 }
 
 impl MuxerInterface for QuinnMuxer {
@@ -34,7 +50,14 @@ impl MuxerInterface for QuinnMuxer {
     type Connection = Connection;
     type Config = Config;
 
-    fn init(config: Self::Config) -> io::Result<Self> {
+    fn init(mut config: Self::Config) -> io::Result<Self> {
+        if let Some(max) = config.max_concurrent_connections {
+            // Quinn counts both incoming and outgoing connections against this limit.
+            config
+                .server_config
+                .concurrent_connections(max.try_into().unwrap_or(u32::MAX));
+        }
+
         let endpoint = Endpoint::server(config.server_config, config.address)
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
@@ -44,10 +67,24 @@ fn init(config: Self::Config) -> io::Result<Self> {
             endpoint,
             sk: config.sk,
             max_idle_timeout: config.max_idle_timeout,
+            max_concurrent_connections: config.max_concurrent_connections,
+            keep_alive_interval: config.keep_alive_interval,
+            max_concurrent_bidi_streams: config.max_concurrent_bidi_streams,
+            connections: Arc::new(AtomicUsize::new(0)),
         })
     }
 
     async fn connect(&self, peer: NodeInfo, server_name: &str) -> io::Result<Self::Connecting> {
+        if self
+            .max_concurrent_connections
+            .is_some_and(|max| self.connection_count() >= max)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "maximum number of concurrent connections reached",
+            ));
+        }
+
         let tls_config = tls::make_client_config(&self.sk, Some(peer.pk))
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
         let mut client_config = ClientConfig::new(Arc::new(tls_config));
@@ -56,13 +93,19 @@ async fn connect(&self, peer: NodeInfo, server_name: &str) -> io::Result<Self::C
             tracing::error!("failed to set max idle timeout: {e:?}");
             io::ErrorKind::Other
         })?));
-        transport_config.keep_alive_interval(Some(self.max_idle_timeout / 2));
+        transport_config.keep_alive_interval(Some(
+            self.keep_alive_interval
+                .unwrap_or(self.max_idle_timeout / 2),
+        ));
+        if let Some(max) = self.max_concurrent_bidi_streams {
+            transport_config.max_concurrent_bidi_streams(max.into());
+        }
         client_config.transport_config(Arc::new(transport_config));
         let connecting = self
             .endpoint
             .connect_with(client_config, peer.socket_address, server_name)
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-        Ok(Connecting(connecting))
+        Ok(Connecting(connecting, self.connections.clone()))
     }
 
     fn listen_address(&self) -> io::Result<SocketAddr> {
@@ -70,7 +113,10 @@ fn listen_address(&self) -> io::Result<SocketAddr> {
     }
 
     async fn accept(&self) -> Option<Self::Connecting> {
-        self.endpoint.accept().await.map(Connecting)
+        self.endpoint
+            .accept()
+            .await
+            .map(|connecting| Connecting(connecting, self.connections.clone()))
     }
 
     async fn close(&self) {
@@ -80,52 +126,94 @@ async fn close(&self) {
     }
 }
 
-pub struct Connecting(quinn::Connecting);
+pub struct Connecting(quinn::Connecting, Arc<AtomicUsize>);
 
 impl Future for Connecting {
     type Output = io::Result<Connection>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let counter = self.1.clone();
         Pin::new(&mut self.0)
             .poll(cx)
-            .map(|result| result.map(Connection))
+            .map(|result| {
+                result.map(|conn| {
+                    Connection(
+                        conn,
+                        Arc::new(ConnectionGuard::new(counter)),
+                        Arc::new(StreamTracker::default()),
+                    )
+                })
+            })
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
     }
 }
 
+/// Keeps the muxer's connection count up to date for as long as a connection, or any of its
+/// clones, is alive.
+struct ConnectionGuard(Arc<AtomicUsize>);
+
+impl ConnectionGuard {
+    fn new(counter: Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 #[derive(Clone)]
-pub struct Connection(quinn::Connection);
+pub struct Connection(quinn::Connection, Arc<ConnectionGuard>, Arc<StreamTracker>);
+
+pub type SendStream = Tracked<quinn::SendStream>;
+pub type RecvStream = Tracked<quinn::RecvStream>;
 
 impl ConnectionInterface for Connection {
     type SendStream = SendStream;
     type RecvStream = RecvStream;
 
     async fn open_bi_stream(&mut self) -> io::Result<(Self::SendStream, Self::RecvStream)> {
-        self.0
+        self.2.ensure_not_draining()?;
+        let guard = self.2.track();
+        let (send, recv) = self
+            .0
             .open_bi()
             .await
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        Ok((Tracked::new(send, guard.clone()), Tracked::new(recv, guard)))
     }
 
     async fn open_uni_stream(&mut self) -> io::Result<Self::SendStream> {
+        self.2.ensure_not_draining()?;
+        let guard = self.2.track();
         self.0
             .open_uni()
             .await
+            .map(|send| Tracked::new(send, guard))
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
     }
 
     async fn accept_bi_stream(&mut self) -> io::Result<(Self::SendStream, Self::RecvStream)> {
-        self.0
+        let (send, recv) = self
+            .0
             .accept_bi()
             .await
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let guard = self.2.track();
+        Ok((Tracked::new(send, guard.clone()), Tracked::new(recv, guard)))
     }
 
     async fn accept_uni_stream(&mut self) -> io::Result<Self::RecvStream> {
-        self.0
+        let recv = self
+            .0
             .accept_uni()
             .await
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let guard = self.2.track();
+        Ok(Tracked::new(recv, guard))
     }
 
     fn peer_identity(&self) -> Option<NodePublicKey> {
@@ -178,4 +266,26 @@ fn stats(&self) -> Stats {
     fn close(&self, error_code: u8, reason: &[u8]) {
         self.0.close(error_code.into(), reason);
     }
+
+    fn close_with(&self, reason: CloseReason) {
+        self.close(reason.error_code(), reason.as_str().as_bytes());
+    }
+
+    fn close_reason(&self) -> Option<CloseReason> {
+        match self.0.close_reason()? {
+            quinn::ConnectionError::ApplicationClosed(close) => {
+                let error_code = u8::try_from(close.error_code.into_inner()).ok()?;
+                CloseReason::from_error_code(error_code)
+            },
+            _ => None,
+        }
+    }
+
+    async fn drain(&self, timeout: Duration) {
+        // Stop the peer from opening new streams.
+        self.0.set_max_concurrent_bi_streams(VarInt::from_u32(0));
+        self.0.set_max_concurrent_uni_streams(VarInt::from_u32(0));
+        self.2.drain(timeout).await;
+        self.close_with(CloseReason::Shutdown);
+    }
 }
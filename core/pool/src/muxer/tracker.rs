@@ -0,0 +1,109 @@
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::sync::Notify;
+
+/// Keeps track of the open streams of a connection, so that it can be drained before closing.
+#[derive(Default)]
+pub(crate) struct StreamTracker {
+    open: AtomicUsize,
+    draining: AtomicBool,
+    idle: Notify,
+}
+
+impl StreamTracker {
+    /// Returns a guard that counts as an open stream until it, and all of its clones, are
+    /// dropped.
+    pub fn track(self: &Arc<Self>) -> Arc<StreamGuard> {
+        self.open.fetch_add(1, Ordering::Relaxed);
+        Arc::new(StreamGuard(self.clone()))
+    }
+
+    /// Returns an error if the connection is draining and should not open new streams.
+    pub fn ensure_not_draining(&self) -> io::Result<()> {
+        if self.draining.load(Ordering::Relaxed) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "connection is draining",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Marks the connection as draining and waits up to `timeout` for the open streams to finish.
+    pub async fn drain(&self, timeout: Duration) {
+        self.draining.store(true, Ordering::Relaxed);
+        if tokio::time::timeout(timeout, self.wait_idle())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "timed out draining connection with {} open streams",
+                self.open.load(Ordering::Relaxed)
+            );
+        }
+    }
+
+    async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.open.load(Ordering::Relaxed) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// Shared by both halves of a stream. The stream is finished once both halves are dropped.
+pub(crate) struct StreamGuard(Arc<StreamTracker>);
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        if self.0.open.fetch_sub(1, Ordering::Relaxed) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+/// A stream, or one half of it, that counts as open for as long as it is alive.
+pub struct Tracked<S>(S, Arc<StreamGuard>);
+
+impl<S> Tracked<S> {
+    pub(crate) fn new(stream: S, guard: Arc<StreamGuard>) -> Self {
+        Self(stream, guard)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Tracked<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Tracked<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
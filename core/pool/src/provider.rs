@@ -6,6 +6,7 @@
 
 use anyhow::{Context, Result};
 use bytes::Bytes;
+use fleek_crypto::NodePublicKey;
 use futures::{SinkExt, Stream};
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::{NodeIndex, RejectReason};
@@ -19,6 +20,8 @@
 use crate::config::Config;
 use crate::endpoint::{Endpoint, EndpointTask};
 use crate::event::{Event, EventReceiver};
+#[cfg(test)]
+use crate::muxer::mock::{MockMuxer, MockRegistry};
 use crate::muxer::quinn::QuinnMuxer;
 use crate::muxer::{BoxedChannel, MuxerInterface};
 use crate::ready::{PoolReadyState, PoolReadyWaiter};
@@ -42,14 +45,16 @@ fn init(
         config: &C::ConfigProviderInterface,
         keystore: &C::KeystoreInterface,
         topology: &C::TopologyInterface,
-        sync_query: fdi::Cloned<c!(C::ApplicationInterface::SyncExecutor)>,
+        fdi::Cloned(sync_query): fdi::Cloned<c!(C::ApplicationInterface::SyncExecutor)>,
     ) -> Result<Self> {
         let config: Config = config.get::<Self>();
         let sk = keystore.get_ed25519_sk();
-        let public_key = keystore.get_ed25519_pk();
 
         let mut transport_config = quinn::TransportConfig::default();
         transport_config.max_idle_timeout(Some(config.max_idle_timeout.try_into()?));
+        if let Some(max) = config.max_concurrent_bidi_streams {
+            transport_config.max_concurrent_bidi_streams(max.into());
+        }
         let tls_config = tls::make_server_config(&sk).expect("Secret key to be valid");
         let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(tls_config));
         server_config.transport_config(Arc::new(transport_config));
@@ -58,8 +63,59 @@ fn init(
             address: config.address,
             sk,
             max_idle_timeout: config.max_idle_timeout,
+            max_concurrent_connections: config.max_concurrent_connections,
+            keep_alive_interval: config.keep_alive_interval,
+            max_concurrent_bidi_streams: config.max_concurrent_bidi_streams,
+        };
+
+        Ok(Self::new(
+            config,
+            keystore.get_ed25519_pk(),
+            topology,
+            sync_query,
+            muxer_config,
+        ))
+    }
+}
+
+#[cfg(test)]
+impl<C: NodeComponents> PoolProvider<C, MockMuxer> {
+    fn init(
+        config: &C::ConfigProviderInterface,
+        keystore: &C::KeystoreInterface,
+        topology: &C::TopologyInterface,
+        fdi::Cloned(sync_query): fdi::Cloned<c!(C::ApplicationInterface::SyncExecutor)>,
+        fdi::Cloned(registry): fdi::Cloned<MockRegistry>,
+    ) -> Result<Self> {
+        let config: Config = config.get::<Self>();
+        let muxer_config = muxer::mock::Config {
+            registry,
+            pk: keystore.get_ed25519_pk(),
+            address: config.address,
         };
 
+        Ok(Self::new(
+            config,
+            keystore.get_ed25519_pk(),
+            topology,
+            sync_query,
+            muxer_config,
+        ))
+    }
+}
+
+impl<C, M> PoolProvider<C, M>
+where
+    C: NodeComponents,
+    M: MuxerInterface,
+{
+    fn new(
+        config: Config,
+        public_key: NodePublicKey,
+        topology: &C::TopologyInterface,
+        sync_query: c!(C::ApplicationInterface::SyncExecutor),
+        muxer_config: M::Config,
+    ) -> Self {
         let dial_info = Arc::new(scc::HashMap::default());
         let (endpoint_task_tx, endpoint_task_rx) = mpsc::channel(1024);
         let (event_tx, event_rx) = mpsc::channel(1024);
@@ -70,9 +126,10 @@ fn init(
             endpoint_task_tx.clone(),
             public_key,
             dial_info.clone(),
+            config.peer_message_rate_limit.clone(),
         );
         let ready = PoolReadyWaiter::new();
-        let endpoint = Endpoint::<C, QuinnMuxer>::new(
+        let endpoint = Endpoint::<C, M>::new(
             sync_query.clone(),
             endpoint_task_rx,
             event_tx.clone(),
@@ -80,13 +137,13 @@ fn init(
             muxer_config,
         );
 
-        Ok(Self {
+        Self {
             state: Some((endpoint, receiver)).into(),
             event_queue: event_tx,
             endpoint_task_queue: endpoint_task_tx,
             config,
             ready,
-        })
+        }
     }
 
     async fn start(this: fdi::Ref<Self>, fdi::Cloned(shutdown): fdi::Cloned<ShutdownWaiter>) {
@@ -151,9 +208,10 @@ fn register_requester_service(
     }
 }
 
-impl<C> ConfigConsumer for PoolProvider<C, QuinnMuxer>
+impl<C, M> ConfigConsumer for PoolProvider<C, M>
 where
     C: NodeComponents,
+    M: MuxerInterface,
 {
     const KEY: &'static str = "pool";
     type Config = Config;
@@ -166,9 +224,22 @@ fn build_graph() -> fdi::DependencyGraph {
     }
 }
 
+#[cfg(test)]
+impl<C: NodeComponents> BuildGraph for PoolProvider<C, MockMuxer> {
+    fn build_graph() -> fdi::DependencyGraph {
+        fdi::DependencyGraph::new()
+            .with(Self::init.with_event_handler("start", Self::start.wrap_with_spawn_named("POOL")))
+    }
+}
+
 // Todo: An improvement would be to pass a `Muxer` in `init`.
 // See comments in `MuxerInterface`.
-impl<C: NodeComponents> PoolInterface<C> for PoolProvider<C, QuinnMuxer> {
+impl<C, M> PoolInterface<C> for PoolProvider<C, M>
+where
+    C: NodeComponents,
+    M: MuxerInterface,
+    Self: BuildGraph,
+{
     type EventHandler = EventHandler;
     type Requester = Requester;
     type Responder = Responder;
@@ -284,6 +355,21 @@ fn send_to_one(&self, index: NodeIndex, message: Bytes) {
         );
     }
 
+    async fn send_to_one_with_ack(&self, index: NodeIndex, message: Bytes) -> io::Result<()> {
+        let (respond_tx, respond_rx) = oneshot::channel();
+        self.request_tx
+            .send(Event::SendMessageWithAck {
+                dst: index,
+                service_scope: self.service_scope,
+                message,
+                respond: respond_tx,
+            })
+            .await
+            .map_err(|_| io::ErrorKind::BrokenPipe)?;
+
+        respond_rx.await.map_err(|_| io::ErrorKind::BrokenPipe)?
+    }
+
     // This method is cancel-safe.
     async fn receive(&mut self) -> Option<(NodeIndex, Bytes)> {
         self.event_rx.recv().await
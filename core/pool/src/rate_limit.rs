@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use lightning_interfaces::types::NodeIndex;
+
+use crate::config::RateLimitConfig;
+
+/// Token bucket rate limiter for the messages received from each peer.
+pub struct PeerRateLimiter {
+    /// Number of tokens added to a bucket every second.
+    rate: f64,
+    /// Maximum number of tokens a bucket can hold.
+    burst: f64,
+    buckets: HashMap<NodeIndex, Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl PeerRateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            rate: config.messages_per_sec as f64,
+            burst: config.burst.max(1) as f64,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Takes a token from the peer's bucket. Returns false if the peer has exceeded its budget.
+    #[inline]
+    pub fn check(&mut self, peer: NodeIndex) -> bool {
+        self.check_at(peer, Instant::now())
+    }
+
+    pub fn check_at(&mut self, peer: NodeIndex, now: Instant) -> bool {
+        let burst = self.burst;
+        let bucket = self.buckets.entry(peer).or_insert(Bucket {
+            tokens: burst,
+            last_refill: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.rate).min(burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Forgets the state of a peer.
+    #[inline]
+    pub fn remove(&mut self, peer: &NodeIndex) {
+        self.buckets.remove(peer);
+    }
+
+    /// Forgets the state of the peers whose buckets have refilled by now. A full bucket is the
+    /// same as a new one, so this doesn't give any peer more budget.
+    pub fn remove_refilled(&mut self, now: Instant) {
+        let (rate, burst) = (self.rate, self.burst);
+        self.buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.last_refill);
+            bucket.tokens + elapsed.as_secs_f64() * rate < burst
+        });
+    }
+}
@@ -4,8 +4,14 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use bytes::Bytes;
-use fleek_crypto::{AccountOwnerSecretKey, NodePublicKey, SecretKey};
+use bytes::{Bytes, BytesMut};
+use fleek_crypto::{
+    AccountOwnerSecretKey,
+    ConsensusPublicKey,
+    NodePublicKey,
+    NodeSecretKey,
+    SecretKey,
+};
 use futures::future::join_all;
 use futures::StreamExt;
 use lightning_application::app::Application;
@@ -23,12 +29,18 @@
 use lightning_topology::Topology;
 use lightning_types::{Param, PeerFilter};
 use tempfile::{tempdir, TempDir};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, oneshot};
 
 use crate::endpoint::EndpointTask;
-use crate::event::{Event, EventReceiver};
-use crate::{provider, Config, PoolProvider};
+use crate::event::{Event, EventReceiver, Message, MESSAGE_VERSION};
+use crate::muxer::mock::{self, MockMuxer, MockRegistry};
+use crate::muxer::quinn::{self, QuinnMuxer};
+use crate::muxer::{CloseReason, ConnectionInterface, MuxerInterface};
+use crate::rate_limit::PeerRateLimiter;
+use crate::state::NodeInfo;
+use crate::{provider, tls, Config, PoolProvider, RateLimitConfig};
 
 partial_node_components!(TestBinding {
     ConfigProviderInterface = JsonConfigProvider;
@@ -41,8 +53,19 @@
     ReputationAggregatorInterface = ReputationAggregator<Self>;
 });
 
-pub struct Peer {
-    inner: Node<TestBinding>,
+partial_node_components!(MockTestBinding {
+    ConfigProviderInterface = JsonConfigProvider;
+    ApplicationInterface = Application<Self>;
+    PoolInterface = PoolProvider<Self, MockMuxer>;
+    KeystoreInterface = EphemeralKeystore<Self>;
+    SignerInterface = Signer<Self>;
+    NotifierInterface = Notifier<Self>;
+    TopologyInterface = Topology<Self>;
+    ReputationAggregatorInterface = ReputationAggregator<Self>;
+});
+
+pub struct Peer<C: NodeComponents = TestBinding> {
+    inner: Node<C>,
     pub node_public_key: NodePublicKey,
     pub node_index: NodeIndex,
 }
@@ -62,13 +85,22 @@ fn notifier(&self) -> fdi::Ref<Notifier<TestBinding>> {
     }
 }
 
-async fn get_pools(
+impl Peer<MockTestBinding> {
+    fn app(&self) -> fdi::Ref<Application<MockTestBinding>> {
+        self.inner.provider.get()
+    }
+    fn pool(&self) -> fdi::Ref<PoolProvider<MockTestBinding, MockMuxer>> {
+        self.inner.provider.get()
+    }
+}
+
+// Writes a genesis with a node for each of the given keys, and returns the application config
+// that uses it.
+fn write_genesis(
     temp_dir: &TempDir,
     port_offset: u16,
-    num_peers: usize,
-    state_server_address_port: Option<u16>,
-) -> (Vec<Peer>, ApplicationConfig) {
-    let mut keystores = Vec::new();
+    keys: &[(NodePublicKey, ConsensusPublicKey)],
+) -> ApplicationConfig {
     let mut genesis = Genesis::default();
 
     let owner_secret_key = AccountOwnerSecretKey::generate();
@@ -78,20 +110,15 @@ async fn get_pools(
     genesis.topology_min_nodes = 16;
     genesis.node_info = vec![];
 
-    // Create signer configs and add nodes to state.
-    for i in 0..num_peers {
-        let keystore = EphemeralKeystore::<TestBinding>::default();
-        let (consensus_secret_key, node_secret_key) =
-            (keystore.get_bls_sk(), keystore.get_ed25519_sk());
-        keystores.push(keystore);
-
+    // Add nodes to state.
+    for (i, (node_public_key, consensus_public_key)) in keys.iter().enumerate() {
         genesis.node_info.push(GenesisNode::new(
             owner_public_key.into(),
-            node_secret_key.to_pk(),
+            *node_public_key,
             "127.0.0.1".parse().unwrap(),
-            consensus_secret_key.to_pk(),
+            *consensus_public_key,
             "127.0.0.1".parse().unwrap(),
-            node_secret_key.to_pk(),
+            *node_public_key,
             NodePorts {
                 primary: 48000_u16,
                 worker: 48101_u16,
@@ -110,7 +137,23 @@ async fn get_pools(
     let genesis_path = genesis
         .write_to_dir(temp_dir.path().to_path_buf().try_into().unwrap())
         .unwrap();
-    let app_config = ApplicationConfig::test(genesis_path);
+    ApplicationConfig::test(genesis_path)
+}
+
+async fn get_pools(
+    temp_dir: &TempDir,
+    port_offset: u16,
+    num_peers: usize,
+    state_server_address_port: Option<u16>,
+) -> (Vec<Peer>, ApplicationConfig) {
+    let keystores: Vec<_> = (0..num_peers)
+        .map(|_| EphemeralKeystore::<TestBinding>::default())
+        .collect();
+    let keys: Vec<_> = keystores
+        .iter()
+        .map(|keystore| (keystore.get_ed25519_pk(), keystore.get_bls_pk()))
+        .collect();
+    let app_config = write_genesis(temp_dir, port_offset, &keys);
 
     // Create peers.
     let mut peers = Vec::new();
@@ -135,10 +178,32 @@ async fn get_pools(
     (peers, app_config)
 }
 
-// Create a peer that is not in state.
-fn create_unknown_peer(app_config: ApplicationConfig, address: SocketAddr) -> Peer {
-    let keystore = EphemeralKeystore::default();
-    create_peer(app_config, keystore, address, false, None)
+// Same as `get_pools`, but the peers are connected over the in-memory mock muxer. No sockets are
+// bound, so the ports are only used as the addresses in state.
+fn get_mock_pools(
+    temp_dir: &TempDir,
+    registry: &MockRegistry,
+    num_peers: usize,
+) -> (Vec<Peer<MockTestBinding>>, ApplicationConfig) {
+    let keystores: Vec<_> = (0..num_peers)
+        .map(|_| EphemeralKeystore::<MockTestBinding>::default())
+        .collect();
+    let keys: Vec<_> = keystores
+        .iter()
+        .map(|keystore| (keystore.get_ed25519_pk(), keystore.get_bls_pk()))
+        .collect();
+    let app_config = write_genesis(temp_dir, 1, &keys);
+
+    let peers = keystores
+        .into_iter()
+        .enumerate()
+        .map(|(i, keystore)| {
+            let address = SocketAddr::from(([0, 0, 0, 0], 1 + i as u16));
+            create_mock_peer(app_config.clone(), keystore, address, true, registry)
+        })
+        .collect();
+
+    (peers, app_config)
 }
 
 fn create_peer(
@@ -158,6 +223,7 @@ fn create_peer(
                         address,
                         http: state_server_address_port
                             .map(|port| SocketAddr::from((IpAddr::from([127, 0, 0, 1]), port))),
+                        ..Default::default()
                     })
                     .with::<Application<TestBinding>>(app_config),
             )
@@ -181,6 +247,46 @@ fn create_peer(
     }
 }
 
+fn create_mock_peer(
+    app_config: ApplicationConfig,
+    keystore: EphemeralKeystore<MockTestBinding>,
+    address: SocketAddr,
+    in_state: bool,
+    registry: &MockRegistry,
+) -> Peer<MockTestBinding> {
+    let node_public_key = keystore.get_ed25519_pk();
+    let node = Node::<MockTestBinding>::init_with_provider(
+        fdi::Provider::default()
+            .with(
+                JsonConfigProvider::default()
+                    .with::<PoolProvider<MockTestBinding, MockMuxer>>(Config {
+                        max_idle_timeout: Duration::from_secs(5),
+                        address,
+                        ..Default::default()
+                    })
+                    .with::<Application<MockTestBinding>>(app_config),
+            )
+            .with(keystore)
+            .with(registry.clone()),
+    )
+    .expect("failed to init node");
+
+    let node_index = if in_state {
+        node.provider
+            .get::<QueryRunner>()
+            .pubkey_to_index(&node_public_key)
+            .unwrap()
+    } else {
+        u32::MAX
+    };
+
+    Peer {
+        inner: node,
+        node_public_key,
+        node_index,
+    }
+}
+
 struct EventReceiverTestState {
     _event_tx: Sender<Event>,
     endpoint_task_rx: Receiver<EndpointTask>,
@@ -205,6 +311,7 @@ fn event_receiver(peer: &Peer) -> (EventReceiver<TestBinding>, EventReceiverTest
             endpoint_task_tx,
             pk,
             dial_info,
+            None,
         ),
         EventReceiverTestState {
             _event_tx,
@@ -252,7 +359,8 @@ async fn test_pool_with_random_listen_address() {
 async fn test_send_to_one() {
     // Given: two peers.
     let temp_dir = tempdir().unwrap();
-    let (mut peers, _) = get_pools(&temp_dir, 48000, 2, None).await;
+    let registry = MockRegistry::default();
+    let (mut peers, _) = get_mock_pools(&temp_dir, &registry, 2);
     let query_runner = peers[0].app().sync_query();
 
     // Check that the pool does not yet have a listen address.
@@ -282,7 +390,7 @@ async fn test_send_to_one() {
 
     // Check that the peer pools are ready with the correct listen address.
     for (i, peer) in peers.iter().enumerate() {
-        let expected_listen_addr = format!("0.0.0.0:{}", 48000 + i);
+        let expected_listen_addr = format!("0.0.0.0:{}", 1 + i);
         let ready_state = peer.pool().wait_for_ready().await;
         assert_eq!(
             ready_state.listen_address.unwrap().to_string(),
@@ -312,17 +420,69 @@ async fn test_send_to_one() {
     .await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_send_to_one_with_ack() {
+    // Given: two peers.
+    let temp_dir = tempdir().unwrap();
+    let registry = MockRegistry::default();
+    let (mut peers, _) = get_mock_pools(&temp_dir, &registry, 2);
+    let query_runner = peers[0].app().sync_query();
+
+    let node_index1 = query_runner
+        .pubkey_to_index(&peers[0].node_public_key)
+        .unwrap();
+    let node_index2 = query_runner
+        .pubkey_to_index(&peers[1].node_public_key)
+        .unwrap();
+
+    let event_handler1 = peers[0].pool().open_event(ServiceScope::Broadcast);
+    let mut event_handler2 = peers[1].pool().open_event(ServiceScope::Broadcast);
+
+    // Start all peers.
+    join_all(peers.iter().map(|peer| async { peer.inner.start().await })).await;
+
+    // Wait for the topology to send the connections.
+    tokio::time::sleep(Duration::from_secs(4)).await;
+
+    // When: one of the peers sends a message to the other peer.
+    let msg = Bytes::from("hello");
+    let result = event_handler1
+        .send_to_one_with_ack(node_index2, msg.clone())
+        .await;
+
+    // Then: the send is acknowledged and the other peer receives the message.
+    assert!(result.is_ok());
+    let (sender, recv_msg) = event_handler2.receive().await.unwrap();
+    assert_eq!(recv_msg, msg);
+    assert_eq!(sender, node_index1);
+
+    // Then: sending to a peer we are not connected to fails.
+    assert!(event_handler1
+        .send_to_one_with_ack(6969, msg)
+        .await
+        .is_err());
+
+    // Clean up.
+    join_all(
+        peers
+            .iter_mut()
+            .map(|peer| async { peer.inner.shutdown().await }),
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_send_to_all() {
     // Given: a list of peers that are in state and some that are not.
-    let port_offset = 49000;
     let temp_dir = tempdir().unwrap();
-    let (peers, app) = get_pools(&temp_dir, port_offset, 4, None).await;
-    let mut unknown_peer = create_unknown_peer(
+    let registry = MockRegistry::default();
+    let (peers, app) = get_mock_pools(&temp_dir, &registry, 4);
+    let mut unknown_peer = create_mock_peer(
         app,
-        format!("0.0.0.0:{}", port_offset + peers.len() as u16)
-            .parse()
-            .unwrap(),
+        EphemeralKeystore::default(),
+        SocketAddr::from(([0, 0, 0, 0], 1 + peers.len() as u16)),
+        false,
+        &registry,
     );
     let query_runner = peers[0].app().sync_query();
 
@@ -857,6 +1017,154 @@ async fn test_log_pool_only_broadcast_to_one_peer() {
     );
 }
 
+#[test]
+fn test_peer_rate_limiter() {
+    let mut limiter = PeerRateLimiter::new(&RateLimitConfig {
+        messages_per_sec: 2,
+        burst: 4,
+    });
+    let now = std::time::Instant::now();
+
+    // A peer can send up to the burst at once.
+    assert_eq!((0..10).filter(|_| limiter.check_at(0, now)).count(), 4);
+
+    // Other peers have their own budget.
+    assert!(limiter.check_at(1, now));
+
+    // The budget refills over time.
+    let later = now + Duration::from_secs(1);
+    assert_eq!((0..10).filter(|_| limiter.check_at(0, later)).count(), 2);
+
+    // Removed peers start over with a full bucket.
+    limiter.remove(&0);
+    assert_eq!((0..10).filter(|_| limiter.check_at(0, later)).count(), 4);
+}
+
+#[test]
+fn test_peer_rate_limiter_keeps_drained_buckets() {
+    let mut limiter = PeerRateLimiter::new(&RateLimitConfig {
+        messages_per_sec: 2,
+        burst: 4,
+    });
+    let now = std::time::Instant::now();
+    assert_eq!((0..10).filter(|_| limiter.check_at(0, now)).count(), 4);
+    assert!(limiter.check_at(1, now));
+
+    // The drained bucket is kept until it refills.
+    let later = now + Duration::from_secs(1);
+    limiter.remove_refilled(later);
+    assert_eq!((0..10).filter(|_| limiter.check_at(0, later)).count(), 2);
+
+    // Once it has refilled, the peer is forgotten without getting more budget.
+    let much_later = later + Duration::from_secs(10);
+    limiter.remove_refilled(much_later);
+    assert_eq!(
+        (0..10).filter(|_| limiter.check_at(0, much_later)).count(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn test_log_pool_rate_limit_incoming_messages() {
+    // Given: an event receiver that accepts a burst of 5 messages per peer.
+    let temp_dir = tempdir().unwrap();
+    let (peers, _) = get_pools(&temp_dir, 8000, 2, None).await;
+    let (mut event_receiver, _state) = event_receiver(&peers[0]);
+    event_receiver.rate_limiter = Some(PeerRateLimiter::new(&RateLimitConfig {
+        messages_per_sec: 1,
+        burst: 5,
+    }));
+    let mut broadcast_rx = event_receiver.register_broadcast_service(ServiceScope::Broadcast);
+
+    // Given: we are connected to the peer.
+    let remote = peers[1].node_index;
+    let info = event_receiver
+        .handler
+        .node_info_from_state(&remote)
+        .unwrap();
+    event_receiver.handler.pin_connection(remote, info);
+
+    // When: the peer floods us with messages.
+    for _ in 0..20 {
+        event_receiver
+            .handle_event(Event::MessageReceived {
+                remote,
+                message: Message {
+                    service: ServiceScope::Broadcast,
+                    payload: b"flood".to_vec(),
+                },
+            })
+            .unwrap();
+    }
+
+    // Then: only the burst is forwarded to the service.
+    for _ in 0..5 {
+        let (sender, _) = broadcast_rx.recv().await.unwrap();
+        assert_eq!(sender, remote);
+    }
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), broadcast_rx.recv())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_rate_limit_survives_reconnect() {
+    // Given: an event receiver that accepts a burst of 5 messages per peer.
+    let temp_dir = tempdir().unwrap();
+    let (peers, _) = get_pools(&temp_dir, 8100, 2, None).await;
+    let (mut event_receiver, _state) = event_receiver(&peers[0]);
+    event_receiver.rate_limiter = Some(PeerRateLimiter::new(&RateLimitConfig {
+        messages_per_sec: 1,
+        burst: 5,
+    }));
+    let mut broadcast_rx = event_receiver.register_broadcast_service(ServiceScope::Broadcast);
+
+    // Given: we are connected to the peer.
+    let remote = peers[1].node_index;
+    let info = event_receiver
+        .handler
+        .node_info_from_state(&remote)
+        .unwrap();
+    event_receiver.handler.pin_connection(remote, info);
+
+    let message = || Event::MessageReceived {
+        remote,
+        message: Message {
+            service: ServiceScope::Broadcast,
+            payload: b"flood".to_vec(),
+        },
+    };
+
+    // Given: the peer used up its burst.
+    for _ in 0..5 {
+        event_receiver.handle_event(message()).unwrap();
+        broadcast_rx.recv().await.unwrap();
+    }
+
+    // When: the peer reconnects and floods us again.
+    event_receiver
+        .handle_event(Event::ConnectionEnded { remote })
+        .unwrap();
+    event_receiver
+        .handle_event(Event::NewConnection {
+            remote,
+            service_request_sent: false,
+        })
+        .unwrap();
+    for _ in 0..5 {
+        event_receiver.handle_event(message()).unwrap();
+    }
+
+    // Then: the peer did not get a new budget.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), broadcast_rx.recv())
+            .await
+            .is_err()
+    );
+}
+
 #[tokio::test]
 async fn test_start_shutdown() {
     // Given: two peers.
@@ -873,3 +1181,281 @@ async fn test_start_shutdown() {
         peer.inner.shutdown().await;
     }
 }
+
+fn create_quinn_muxer(sk: NodeSecretKey, max_concurrent_connections: Option<usize>) -> QuinnMuxer {
+    let tls_config = tls::make_server_config(&sk).unwrap();
+    let server_config = ::quinn::ServerConfig::with_crypto(Arc::new(tls_config));
+    QuinnMuxer::init(quinn::Config {
+        server_config,
+        address: "127.0.0.1:0".parse().unwrap(),
+        sk,
+        max_idle_timeout: Duration::from_secs(5),
+        max_concurrent_connections,
+        keep_alive_interval: None,
+        max_concurrent_bidi_streams: None,
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_quinn_muxer_connection_count() {
+    // Given: two muxers where the dialer only allows one connection.
+    let dialer = create_quinn_muxer(NodeSecretKey::generate(), Some(1));
+    let listener_sk = NodeSecretKey::generate();
+    let listener_pk = listener_sk.to_pk();
+    let listener = create_quinn_muxer(listener_sk, None);
+    let listener_info = NodeInfo {
+        index: 0,
+        pk: listener_pk,
+        socket_address: listener.listen_address().unwrap(),
+    };
+
+    // When: the dialer connects to the listener.
+    let (outgoing, incoming) = tokio::join!(
+        async {
+            dialer
+                .connect(listener_info.clone(), "lightning-node")
+                .await
+                .unwrap()
+                .await
+                .unwrap()
+        },
+        async { listener.accept().await.unwrap().await.unwrap() }
+    );
+
+    // Then: both sides count the connection, including its clones only once.
+    let outgoing_clone = outgoing.clone();
+    assert_eq!(dialer.connection_count(), 1);
+    assert_eq!(listener.connection_count(), 1);
+
+    // Then: the dialer refuses to open more connections than allowed.
+    assert!(dialer
+        .connect(listener_info, "lightning-node")
+        .await
+        .is_err());
+
+    // When: every handle to the connections is dropped.
+    drop(outgoing);
+    assert_eq!(dialer.connection_count(), 1);
+    drop(outgoing_clone);
+    drop(incoming);
+
+    // Then: the counts go back to zero.
+    assert_eq!(dialer.connection_count(), 0);
+    assert_eq!(listener.connection_count(), 0);
+}
+
+async fn assert_drain_waits_for_streams<M: MuxerInterface>(
+    dialer: M,
+    listener: M,
+    listener_info: NodeInfo,
+) {
+    // Given: a connection between two muxers.
+    let (mut outgoing, mut incoming) = tokio::join!(
+        async {
+            dialer
+                .connect(listener_info, "lightning-node")
+                .await
+                .unwrap()
+                .await
+                .unwrap()
+        },
+        async { listener.accept().await.unwrap().await.unwrap() }
+    );
+
+    // Given: a stream that is still in flight.
+    let (mut send, recv) = outgoing.open_bi_stream().await.unwrap();
+    send.write_all(b"hello").await.unwrap();
+    let (_, mut incoming_recv) = incoming.accept_bi_stream().await.unwrap();
+
+    // When: the dialer drains the connection while the stream is completed.
+    let complete_stream = async move {
+        send.write_all(b" world").await.unwrap();
+        send.shutdown().await.unwrap();
+        drop(recv);
+        let mut received = Vec::new();
+        incoming_recv.read_to_end(&mut received).await.unwrap();
+        received
+    };
+    let ((), received) = tokio::join!(outgoing.drain(Duration::from_secs(5)), complete_stream);
+
+    // Then: the stream completed instead of being reset.
+    assert_eq!(received, b"hello world");
+
+    // Then: no new streams can be opened on the drained connection.
+    assert!(outgoing.open_bi_stream().await.is_err());
+}
+
+#[tokio::test]
+async fn test_quinn_connection_drain_waits_for_streams() {
+    let dialer = create_quinn_muxer(NodeSecretKey::generate(), None);
+    let listener_sk = NodeSecretKey::generate();
+    let listener_pk = listener_sk.to_pk();
+    let listener = create_quinn_muxer(listener_sk, None);
+    let listener_info = NodeInfo {
+        index: 0,
+        pk: listener_pk,
+        socket_address: listener.listen_address().unwrap(),
+    };
+    assert_drain_waits_for_streams(dialer, listener, listener_info).await;
+}
+
+fn create_mock_muxer(registry: &MockRegistry, pk: NodePublicKey, port: u16) -> MockMuxer {
+    MockMuxer::init(mock::Config {
+        registry: registry.clone(),
+        pk,
+        address: SocketAddr::from(([127, 0, 0, 1], port)),
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_mock_connection_drain_waits_for_streams() {
+    let registry = MockRegistry::default();
+    let dialer = create_mock_muxer(&registry, NodeSecretKey::generate().to_pk(), 1);
+    let listener_pk = NodeSecretKey::generate().to_pk();
+    let listener = create_mock_muxer(&registry, listener_pk, 2);
+    let listener_info = NodeInfo {
+        index: 0,
+        pk: listener_pk,
+        socket_address: listener.listen_address().unwrap(),
+    };
+    assert_drain_waits_for_streams(dialer, listener, listener_info).await;
+}
+
+#[tokio::test]
+async fn test_mock_muxer_injected_failures_and_latency() {
+    let registry = MockRegistry::default();
+    let dialer = create_mock_muxer(&registry, NodeSecretKey::generate().to_pk(), 1);
+    let listener_pk = NodeSecretKey::generate().to_pk();
+    let listener = create_mock_muxer(&registry, listener_pk, 2);
+    let listener_info = NodeInfo {
+        index: 0,
+        pk: listener_pk,
+        socket_address: listener.listen_address().unwrap(),
+    };
+
+    // Given: connections to the listener are set to fail.
+    registry.fail_connections_to(listener_pk);
+    // Then: the dialer fails to connect.
+    assert!(dialer
+        .connect(listener_info.clone(), "lightning-node")
+        .await
+        .is_err());
+
+    // Given: connections are allowed again, but with latency.
+    registry.allow_connections_to(&listener_pk);
+    registry.set_latency(Duration::from_millis(200));
+    // Then: the connection is established after the latency.
+    let start = tokio::time::Instant::now();
+    let connection = dialer
+        .connect(listener_info, "lightning-node")
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(connection.peer_identity(), Some(listener_pk));
+    assert_eq!(
+        connection.remote_address(),
+        SocketAddr::from(([127, 0, 0, 1], 2))
+    );
+}
+
+#[tokio::test]
+async fn test_mock_connection_close_reason_round_trip() {
+    let registry = MockRegistry::default();
+    let dialer = create_mock_muxer(&registry, NodeSecretKey::generate().to_pk(), 1);
+    let listener_pk = NodeSecretKey::generate().to_pk();
+    let listener = create_mock_muxer(&registry, listener_pk, 2);
+    let listener_info = NodeInfo {
+        index: 0,
+        pk: listener_pk,
+        socket_address: listener.listen_address().unwrap(),
+    };
+    let (outgoing, mut incoming) = tokio::join!(
+        async {
+            dialer
+                .connect(listener_info, "lightning-node")
+                .await
+                .unwrap()
+                .await
+                .unwrap()
+        },
+        async { listener.accept().await.unwrap().await.unwrap() }
+    );
+
+    // When: the dialer closes the connection because the peer misbehaved.
+    outgoing.close_with(CloseReason::Misbehavior);
+
+    // Then: the listener sees the connection closed, and the reason the dialer gave.
+    assert!(incoming.accept_bi_stream().await.is_err());
+    assert_eq!(incoming.close_reason(), Some(CloseReason::Misbehavior));
+    // Then: the reason is only reported by the peer of the side that closed the connection.
+    assert_eq!(outgoing.close_reason(), None);
+}
+
+#[test]
+fn test_close_reason_error_codes() {
+    for reason in [
+        CloseReason::Disconnect,
+        CloseReason::Shutdown,
+        CloseReason::Idle,
+        CloseReason::Misbehavior,
+        CloseReason::VersionMismatch,
+        CloseReason::Redundant,
+    ] {
+        assert_eq!(
+            CloseReason::from_error_code(reason.error_code()),
+            Some(reason)
+        );
+    }
+    assert_eq!(CloseReason::from_error_code(u8::MAX), None);
+}
+
+#[test]
+fn test_message_round_trip() {
+    let message = Message {
+        service: ServiceScope::Broadcast,
+        payload: b"hello".to_vec(),
+    };
+    let bytes = Bytes::from(message.clone());
+    assert_eq!(bytes[0], MESSAGE_VERSION);
+
+    let decoded = Message::try_from(BytesMut::from(bytes.as_ref())).unwrap();
+    assert_eq!(decoded.service, message.service);
+    assert_eq!(decoded.payload, message.payload);
+
+    // Empty payloads are valid too.
+    let message = Message {
+        service: ServiceScope::Broadcast,
+        payload: Vec::new(),
+    };
+    let bytes = Bytes::from(message);
+    let decoded = Message::try_from(BytesMut::from(bytes.as_ref())).unwrap();
+    assert!(decoded.payload.is_empty());
+}
+
+#[test]
+fn test_message_rejects_malformed_bytes() {
+    let bytes = Bytes::from(Message {
+        service: ServiceScope::Broadcast,
+        payload: b"hello".to_vec(),
+    });
+
+    // Truncated header.
+    assert!(Message::try_from(BytesMut::from(&bytes[..3])).is_err());
+
+    // Truncated payload.
+    assert!(Message::try_from(BytesMut::from(&bytes[..bytes.len() - 1])).is_err());
+
+    // Trailing bytes after the payload.
+    let mut extended = BytesMut::from(bytes.as_ref());
+    extended.extend_from_slice(b"!");
+    assert!(Message::try_from(extended).is_err());
+
+    // Unknown version.
+    let mut unversioned = BytesMut::from(bytes.as_ref());
+    unversioned[0] = MESSAGE_VERSION + 1;
+    assert!(Message::try_from(unversioned).is_err());
+}
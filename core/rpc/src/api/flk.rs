@@ -9,6 +9,7 @@
     AccountInfo,
     AggregateCheckpoint,
     Blake3Hash,
+    CommitteeInfo,
     Epoch,
     EpochInfo,
     Event,
@@ -20,13 +21,18 @@
     PublicKeys,
     ReportedReputationMeasurements,
     TotalServed,
+    TransactionReceipt,
     TransactionRequest,
+    TransactionResponse,
+    UpdateMethod,
 };
-use lightning_interfaces::{NodePagingParams, WithdrawPagingParams};
+use lightning_interfaces::{NodePagingParams, ReputationPagingParams, WithdrawPagingParams};
 use lightning_openrpc_macros::open_rpc;
 use lightning_types::{ProtocolParamKey, StateProofKey, StateProofValue, WithdrawInfoWithId};
 use merklize::{StateRootHash, StateTree};
 
+use crate::api_types::{HealthStatus, SendTxnError};
+
 #[open_rpc(namespace = "flk", tag = "1.0.0")]
 #[rpc(client, server, namespace = "flk")]
 pub trait FleekApi {
@@ -97,6 +103,12 @@ async fn get_node_info_epoch(
         public_key: NodePublicKey,
     ) -> RpcResult<(Option<NodeInfo>, Epoch)>;
 
+    #[method(name = "get_node_info_by_index")]
+    async fn get_node_info_by_index(
+        &self,
+        index: NodeIndex,
+    ) -> RpcResult<Option<NodeInfoWithIndex>>;
+
     #[method(name = "get_public_keys")]
     async fn get_public_keys(&self) -> RpcResult<PublicKeys>;
 
@@ -116,6 +128,12 @@ async fn get_account_info(
     #[method(name = "get_committee_members")]
     async fn get_committee_members(&self, epoch: Option<u64>) -> RpcResult<Vec<NodePublicKey>>;
 
+    #[method(name = "get_committee_members_index")]
+    async fn get_committee_members_index(&self) -> RpcResult<Vec<NodeIndex>>;
+
+    #[method(name = "get_committee_info")]
+    async fn get_committee_info(&self) -> RpcResult<CommitteeInfo>;
+
     #[method(name = "get_genesis_committee")]
     async fn get_genesis_committee(&self) -> RpcResult<Vec<(NodeIndex, NodeInfo)>>;
 
@@ -170,11 +188,15 @@ async fn get_reputation(
         epoch: Option<u64>,
     ) -> RpcResult<Option<u8>>;
 
+    /// Returns the measurements reported about the given node. The measurements can be limited to
+    /// the ones submitted by a single `reporter` and paged through with `paging`.
     #[method(name = "get_reputation_measurements")]
     async fn get_reputation_measurements(
         &self,
         public_key: NodePublicKey,
         epoch: Option<u64>,
+        paging: Option<ReputationPagingParams>,
+        reporter: Option<NodeIndex>,
     ) -> RpcResult<Vec<ReportedReputationMeasurements>>;
 
     #[method(name = "get_latencies")]
@@ -211,12 +233,42 @@ async fn get_aggregate_checkpoint(
     #[method(name = "send_txn")]
     async fn send_txn(&self, tx: TransactionRequest) -> RpcResult<()>;
 
+    /// Submit the transactions in the given order. The result of each submission is returned at
+    /// the same position, a failed submission does not stop the remaining ones.
+    #[method(name = "send_txn_batch")]
+    async fn send_txn_batch(
+        &self,
+        txns: Vec<TransactionRequest>,
+    ) -> RpcResult<Vec<Result<(), SendTxnError>>>;
+
+    /// Returns the nonce and method of every transaction that was submitted by this node's signer
+    /// but has not been ordered yet.
+    #[method(name = "get_pending_transactions")]
+    async fn get_pending_transactions(&self) -> RpcResult<Vec<(u64, UpdateMethod)>>;
+
+    /// Execute the transaction against the current state without submitting it or persisting any
+    /// of its changes.
+    #[method(name = "simulate_txn")]
+    async fn simulate_txn(&self, txn: TransactionRequest) -> RpcResult<TransactionResponse>;
+
+    /// Returns the receipt of an executed transaction. Only the most recent receipts are kept in
+    /// memory, older ones are looked up in the archive if it is enabled.
+    #[method(name = "get_transaction_receipt")]
+    async fn get_transaction_receipt(
+        &self,
+        hash: [u8; 32],
+    ) -> RpcResult<Option<TransactionReceipt>>;
+
     #[method(name = "put")]
     async fn put(&self, data: Vec<u8>) -> RpcResult<Blake3Hash>;
 
     #[method(name = "health")]
     async fn health(&self) -> RpcResult<String>;
 
+    /// Returns the health and sync status of the node. Use `health` for simple liveness probes.
+    #[method(name = "get_health_status")]
+    async fn get_health_status(&self) -> RpcResult<HealthStatus>;
+
     #[method(name = "metrics")]
     async fn metrics(&self) -> RpcResult<String>;
 
@@ -232,4 +284,11 @@ async fn get_withdraws(
 
     #[subscription(name = "subscribe", item = Event)]
     async fn handle_subscription(&self, event_type: Option<EventType>) -> SubscriptionResult;
+
+    /// Streams the events of every executed block as a single item.
+    ///
+    /// Block execution never waits on subscribers: a subscriber that falls more than the event
+    /// channel capacity behind is dropped and has to resubscribe.
+    #[subscription(name = "subscribe_blocks", item = Vec<Event>)]
+    async fn subscribe_blocks(&self) -> SubscriptionResult;
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use ethers::types::{Address, Bytes, H256, U256};
+use lightning_interfaces::types::Epoch;
 use serde::{Deserialize, Serialize};
 
 /// A set of account overrides
@@ -45,3 +46,30 @@ pub struct CallRequest {
     pub data: Option<Bytes>,
     pub value: Option<U256>,
 }
+
+/// The health and sync status of the node, returned by `flk_get_health_status`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// The number of the last executed block.
+    pub last_executed_block: u64,
+    /// The current epoch.
+    pub current_epoch: Epoch,
+    /// Whether this node is a member of the current committee.
+    pub is_committee_member: bool,
+    /// The number of parcels that have a quorum of attestations, but can't be executed yet because
+    /// the parcels leading up to them are missing.
+    pub pending_parcel_count: usize,
+    /// Whether the node has executed every parcel it received from the committee.
+    pub synced: bool,
+}
+
+/// The error of a single transaction of `flk_send_txn_batch`.
+///
+/// Uses the same code and message as the error that `flk_send_txn` returns for the transaction.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SendTxnError {
+    /// The JSON-RPC error code.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+}
@@ -5,6 +5,8 @@
 use jsonrpsee::types::ErrorObject;
 use ruint::ParseError;
 
+use crate::api_types::SendTxnError;
+
 #[derive(Debug)]
 pub struct SocketErrorWrapper(String);
 
@@ -96,6 +98,16 @@ fn from(e: RPCError) -> Self {
     }
 }
 
+impl From<RPCError> for SendTxnError {
+    fn from(e: RPCError) -> Self {
+        let e = ErrorObject::from(e);
+        Self {
+            code: e.code(),
+            message: e.message().to_string(),
+        }
+    }
+}
+
 fn internal_err<E: Error>(e: E) -> ErrorObject<'static> {
     jsonrpsee::types::ErrorObject::owned::<()>(INTERNAL_ERROR_CODE, e.to_string(), None)
 }
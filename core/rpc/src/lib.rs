@@ -9,14 +9,18 @@
 use jsonrpsee::{Methods, RpcModule};
 use lightning_firewall::Firewall;
 use lightning_interfaces::prelude::*;
-use lightning_interfaces::{Events, FetcherSocket, MempoolSocket};
+use lightning_interfaces::types::TransactionReceipt;
+use lightning_interfaces::{Events, ExecutionProgress, FetcherSocket, MempoolSocket};
 use lightning_utils::config::LIGHTNING_HOME_DIR;
 use once_cell::sync::Lazy;
+use quick_cache::sync::Cache;
 use rand::{RngCore, SeedableRng};
 use ready::tokio::TokioReadyWaiter;
 use ready::ReadyWaiter;
 use reqwest::StatusCode;
 use resolved_pathbuf::ResolvedPathBuf;
+use tokio::pin;
+use tokio::sync::watch;
 
 use crate::api::AdminApiServer;
 pub use crate::api::{EthApiServer, FleekApiServer, NetApiServer};
@@ -55,6 +59,9 @@ pub mod interface {
 
 pub static HMAC_SALT: &[u8] = b"lightning-hmac-salt";
 
+/// The number of most recent transaction receipts that are kept in memory.
+const RECEIPT_CACHE_SIZE: usize = 10_000;
+
 static VERSION: Lazy<String> = Lazy::new(|| {
     format!(
         "lightning-rpc {}-{}",
@@ -108,12 +115,17 @@ pub(crate) struct Data<C: NodeComponents> {
     pub query_runner: c!(C::ApplicationInterface::SyncExecutor),
     pub checkpointer_query: c!(C::CheckpointerInterface::Query),
     pub mempool_socket: MempoolSocket,
+    pub signer_pending_socket: SignerPendingTxSocket,
     pub fetcher_socket: FetcherSocket,
     pub _blockstore: C::BlockstoreInterface,
     pub node_public_key: NodePublicKey,
     pub consensus_public_key: ConsensusPublicKey,
     pub archive: C::ArchiveInterface,
     pub events: Events,
+    /// The receipts of the most recently executed transactions, keyed by transaction hash.
+    pub receipts: Cache<[u8; 32], TransactionReceipt>,
+    /// The progress of consensus executing the parcels of the committee.
+    pub execution_progress: watch::Receiver<ExecutionProgress>,
 }
 
 impl<C: NodeComponents> Data<C> {
@@ -164,6 +176,8 @@ fn init(
         blockstore: &C::BlockstoreInterface,
         fetcher: &C::FetcherInterface,
         keystore: &C::KeystoreInterface,
+        signer: &C::SignerInterface,
+        consensus: &C::ConsensusInterface,
         fdi::Cloned(archive): fdi::Cloned<c!(C::ArchiveInterface)>,
         fdi::Cloned(query_runner): fdi::Cloned<c!(C::ApplicationInterface::SyncExecutor)>,
         fdi::Cloned(checkpointer_query): fdi::Cloned<c!(C::CheckpointerInterface::Query)>,
@@ -173,6 +187,7 @@ fn init(
         let data: Arc<Data<C>> = Arc::new(Data {
             query_runner,
             mempool_socket: forwarder.mempool_socket(),
+            signer_pending_socket: signer.get_pending_socket(),
             fetcher_socket: fetcher.get_socket(),
             _blockstore: blockstore.clone(),
             node_public_key: keystore.get_ed25519_pk(),
@@ -183,6 +198,8 @@ fn init(
                 let (tx, _) = tokio::sync::broadcast::channel(8);
                 tx.into()
             },
+            receipts: Cache::new(RECEIPT_CACHE_SIZE),
+            execution_progress: consensus.execution_progress(),
         });
         let module = Self::create_modules_from_config(&config, data.clone())?;
         let admin_module = Self::create_admin_module_from_config(&config, data.clone())?;
@@ -198,7 +215,11 @@ fn init(
         })
     }
 
-    fn start(&self, shutdown: fdi::Cloned<ShutdownWaiter>) {
+    fn start(
+        &self,
+        shutdown: fdi::Cloned<ShutdownWaiter>,
+        fdi::Cloned(notifier): fdi::Cloned<C::NotifierInterface>,
+    ) {
         let (stop, server_handle) = stop_channel();
 
         let disallowed = self.config.disallowed_methods.as_ref().map(|s| s.as_ref());
@@ -236,6 +257,11 @@ fn start(&self, shutdown: fdi::Cloned<ShutdownWaiter>) {
             crucial(panic_waiter)
         );
 
+        spawn!(
+            index_receipts::<C>(self.data.clone(), notifier, shutdown.0.clone()),
+            "RPC: receipt indexer"
+        );
+
         spawn!(
             async move {
                 shutdown.wait_for_shutdown().await;
@@ -330,6 +356,31 @@ fn build_graph() -> fdi::DependencyGraph {
     }
 }
 
+/// Keeps the receipts of the executed transactions, so that they can be looked up by hash even
+/// when the archive is disabled.
+async fn index_receipts<C: NodeComponents>(
+    data: Arc<Data<C>>,
+    notifier: C::NotifierInterface,
+    waiter: ShutdownWaiter,
+) {
+    let mut block_executed_sub = notifier.subscribe_block_executed();
+    let shutdown_fut = waiter.wait_for_shutdown();
+    pin!(shutdown_fut);
+
+    loop {
+        tokio::select! {
+            biased;
+            _ = &mut shutdown_fut => break,
+            Some(n) = block_executed_sub.recv() => {
+                for receipt in n.response.txn_receipts {
+                    data.receipts.insert(receipt.transaction_hash, receipt);
+                }
+            },
+            else => break,
+        }
+    }
+}
+
 pub type RpcReadyWaiter = TokioReadyWaiter<RpcReadyState>;
 
 #[derive(Clone)]
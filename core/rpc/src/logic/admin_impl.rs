@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
 use jsonrpsee::core::RpcResult;
+use lightning_blockstore::put_reader;
 use lightning_firewall::{CommandCenter, FirewallCommand};
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::Blake3Hash;
-use lightning_interfaces::FileTrustedWriter;
 
 use crate::api::AdminApiServer;
 use crate::error::RPCError;
@@ -23,22 +23,11 @@ pub(crate) fn new(data: Arc<Data<C>>) -> Self {
 #[async_trait::async_trait]
 impl<C: NodeComponents> AdminApiServer for AdminApi<C> {
     async fn store(&self, path: String) -> RpcResult<Blake3Hash> {
-        let file = tokio::fs::read(path)
+        let file = tokio::fs::File::open(path)
             .await
             .map_err(|e| RPCError::custom(e.to_string()))?;
 
-        let mut writer = self
-            .data
-            ._blockstore
-            .file_writer()
-            .await
-            .map_err(|e| RPCError::custom(e.to_string()))?;
-        writer
-            .write(file.as_ref(), true)
-            .await
-            .map_err(|e| RPCError::custom(e.to_string()))?;
-        let hash = writer
-            .commit()
+        let hash = put_reader(&self.data._blockstore, file)
             .await
             .map_err(|e| RPCError::custom(e.to_string()))?;
         Ok(hash)
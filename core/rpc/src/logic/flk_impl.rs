@@ -10,6 +10,7 @@
 use lightning_interfaces::types::{
     AccountInfo,
     Blake3Hash,
+    CommitteeInfo,
     Epoch,
     EpochInfo,
     EventType,
@@ -25,17 +26,23 @@
     PublicKeys,
     ReportedReputationMeasurements,
     TotalServed,
+    TransactionReceipt,
     TransactionRequest,
+    TransactionResponse,
+    UpdateMethod,
     Value,
 };
-use lightning_interfaces::{NodePagingParams, WithdrawPagingParams};
+use lightning_interfaces::{NodePagingParams, ReputationPagingParams, WithdrawPagingParams};
+use lightning_metrics::increment_counter_by;
 use lightning_types::{AggregateCheckpoint, StateProofKey, StateProofValue};
 use lightning_utils::application::QueryRunnerExt;
 use merklize::{StateRootHash, StateTree};
 use serde_json::Value as JsonValue;
+use tokio::sync::broadcast::error::RecvError;
 use types::{ProtocolParamKey, WithdrawInfoWithId};
 
 use crate::api::FleekApiServer;
+use crate::api_types::{HealthStatus, SendTxnError};
 use crate::error::RPCError;
 use crate::Data;
 
@@ -177,6 +184,17 @@ async fn get_node_info_epoch(&self, pk: NodePublicKey) -> RpcResult<(Option<Node
         ))
     }
 
+    async fn get_node_info_by_index(
+        &self,
+        index: NodeIndex,
+    ) -> RpcResult<Option<NodeInfoWithIndex>> {
+        Ok(self
+            .data
+            .query_runner
+            .get_node_info::<NodeInfo>(&index, |n| n)
+            .map(|info| NodeInfoWithIndex { index, info }))
+    }
+
     async fn get_public_keys(&self) -> RpcResult<PublicKeys> {
         Ok(PublicKeys {
             node_public_key: self.data.node_public_key,
@@ -213,6 +231,29 @@ async fn get_committee_members(&self, epoch: Option<u64>) -> RpcResult<Vec<NodeP
         Ok(self.data.query_runner(epoch).await?.get_committee_members())
     }
 
+    async fn get_committee_members_index(&self) -> RpcResult<Vec<NodeIndex>> {
+        Ok(self.data.query_runner.get_committee_members_by_index())
+    }
+
+    async fn get_committee_info(&self) -> RpcResult<CommitteeInfo> {
+        let epoch = self.data.query_runner.get_current_epoch();
+        let committee = self
+            .data
+            .query_runner
+            .get_committee_info(&epoch, |c| c)
+            .unwrap_or_default();
+
+        Ok(CommitteeInfo {
+            epoch,
+            members: committee.members,
+            ready_to_change: committee.ready_to_change,
+            beacon_phase: self
+                .data
+                .query_runner
+                .get_committee_selection_beacon_phase(),
+        })
+    }
+
     async fn get_genesis_committee(&self) -> RpcResult<Vec<(NodeIndex, NodeInfo)>> {
         Ok(self.data.query_runner.get_genesis_committee())
     }
@@ -335,6 +376,8 @@ async fn get_reputation_measurements(
         &self,
         pk: NodePublicKey,
         epoch: Option<u64>,
+        paging: Option<ReputationPagingParams>,
+        reporter: Option<NodeIndex>,
     ) -> RpcResult<Vec<ReportedReputationMeasurements>> {
         let rep_measurements = self
             .data
@@ -346,7 +389,16 @@ async fn get_reputation_measurements(
                     .query_runner
                     .get_reputation_measurements(&node_idx)
             })
-            .unwrap_or_default();
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| reporter.map_or(true, |index| m.reporting_node == index));
+        let rep_measurements = match paging {
+            Some(paging) => rep_measurements
+                .skip(paging.start)
+                .take(paging.limit)
+                .collect(),
+            None => rep_measurements.collect(),
+        };
         Ok(rep_measurements)
     }
 
@@ -431,6 +483,50 @@ async fn send_txn(&self, tx: TransactionRequest) -> RpcResult<()> {
             .map_err(|e| RPCError::socket(e.to_string()))?)
     }
 
+    async fn send_txn_batch(
+        &self,
+        txns: Vec<TransactionRequest>,
+    ) -> RpcResult<Vec<Result<(), SendTxnError>>> {
+        let mut results = Vec::with_capacity(txns.len());
+        for tx in txns {
+            // Enqueue one by one to preserve the ordering of the nonces.
+            let result = self
+                .data
+                .mempool_socket
+                .enqueue(tx)
+                .await
+                .map_err(|e| SendTxnError::from(RPCError::socket(e.to_string())));
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    async fn get_pending_transactions(&self) -> RpcResult<Vec<(u64, UpdateMethod)>> {
+        Ok(self
+            .data
+            .signer_pending_socket
+            .run(())
+            .await
+            .map_err(|e| RPCError::socket(e.to_string()))?)
+    }
+
+    async fn simulate_txn(&self, txn: TransactionRequest) -> RpcResult<TransactionResponse> {
+        Ok(self.data.query_runner(None).await?.simulate_txn(txn))
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        hash: [u8; 32],
+    ) -> RpcResult<Option<TransactionReceipt>> {
+        if let Some(receipt) = self.data.receipts.get(&hash) {
+            return Ok(Some(receipt));
+        }
+        if !self.data.archive.is_active() {
+            return Ok(None);
+        }
+        Ok(self.data.archive.get_transaction_receipt(hash).await)
+    }
+
     async fn put(&self, data: Vec<u8>) -> RpcResult<Blake3Hash> {
         let pointer = ImmutablePointer {
             origin: OriginProvider::IPFS,
@@ -461,6 +557,20 @@ async fn health(&self) -> RpcResult<String> {
         Ok("OK".to_string())
     }
 
+    async fn get_health_status(&self) -> RpcResult<HealthStatus> {
+        let query_runner = &self.data.query_runner;
+        let progress = *self.data.execution_progress.borrow();
+
+        Ok(HealthStatus {
+            last_executed_block: query_runner.get_block_number().unwrap_or_default(),
+            current_epoch: query_runner.get_current_epoch(),
+            is_committee_member: query_runner.is_in_committee(&self.data.node_public_key),
+            pending_parcel_count: progress.pending_parcels,
+            synced: progress.pending_parcels == 0
+                && query_runner.get_sub_dag_index() >= progress.highest_known_sub_dag_index,
+        })
+    }
+
     async fn metrics(&self) -> RpcResult<String> {
         match prometheus::TextEncoder::new().encode_to_string(&prometheus::gather()) {
             Ok(metrics) => Ok(metrics),
@@ -489,7 +599,23 @@ async fn handle_subscription(
 
         let mut rx = self.data.events.subscribe();
 
-        while let Ok(events) = rx.recv().await {
+        loop {
+            let events = match rx.recv().await {
+                Ok(events) => events,
+                Err(RecvError::Lagged(skipped)) => {
+                    // The oldest events were dropped because we fell behind, keep going with the
+                    // ones that are still buffered.
+                    tracing::debug!(skipped, "event subscriber lagged behind");
+                    increment_counter_by!(
+                        skipped,
+                        "rpc_event_dropped",
+                        Some("Number of event batches dropped because an RPC subscriber lagged behind")
+                    );
+                    continue;
+                },
+                Err(RecvError::Closed) => break,
+            };
+
             for event in events {
                 if let Some(ref typee) = event_type {
                     if &event.event_type() != typee {
@@ -511,4 +637,37 @@ async fn handle_subscription(
 
         Ok(())
     }
+
+    async fn subscribe_blocks(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let sink = pending.accept().await?;
+
+        let mut rx = self.data.events.subscribe();
+
+        loop {
+            let events = match rx.recv().await {
+                Ok(events) => events,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "dropping lagging block subscriber");
+                    increment_counter_by!(
+                        skipped,
+                        "rpc_event_dropped",
+                        Some("Number of event batches dropped because an RPC subscriber lagged behind")
+                    );
+                    break;
+                },
+                Err(RecvError::Closed) => break,
+            };
+
+            if sink
+                .send(SubscriptionMessage::from_json(&events)?)
+                .await
+                .is_err()
+            {
+                tracing::trace!("flk block subscription closed");
+                break;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -2,10 +2,12 @@
 use std::time::Duration;
 
 use bit_set::BitSet;
-use fleek_crypto::{AccountOwnerSecretKey, EthAddress, SecretKey};
+use fleek_crypto::{AccountOwnerSecretKey, EthAddress, NodePublicKey, SecretKey};
 use hp_fixed::unsigned::HpUfixed;
 use lightning_application::env::ApplicationStateTree;
 use lightning_interfaces::prelude::*;
+use lightning_interfaces::ReputationPagingParams;
+use lightning_test_utils::consensus::MockConsensusConfig;
 use lightning_test_utils::e2e::{
     DowncastToTestFullNode,
     TestFullNodeComponentsWithMockConsensus,
@@ -16,15 +18,19 @@
     AggregateCheckpoint,
     Epoch,
     Event,
+    ExecutionError,
     GenesisAccount,
     GenesisNodeServed,
     Metadata,
     NodeIndex,
     NodeServed,
+    ReputationMeasurements,
     Staking,
     StateProofKey,
     StateProofValue,
     TotalServed,
+    TransactionRequest,
+    TransactionResponse,
     UpdateMethod,
     Value,
 };
@@ -35,6 +41,8 @@
 use types::ProtocolParamKey;
 
 use crate::api::{AdminApiClient, FleekApiClient};
+use crate::api_types::SendTxnError;
+use crate::error::RPCError;
 
 #[tokio::test]
 async fn test_rpc_send_txn() {
@@ -66,6 +74,215 @@ async fn test_rpc_send_txn() {
     network.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_rpc_send_txn_batch() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let chain_id = node.app_query().get_chain_id();
+    let nonce = node.get_owner_nonce();
+    let txns = (1..=3)
+        .map(|i| {
+            TransactionBuilder::from_update(
+                UpdateMethod::IncrementNonce {},
+                chain_id,
+                nonce + i,
+                &node.get_owner_signer(),
+            )
+            .into()
+        })
+        .collect::<Vec<_>>();
+    let response = FleekApiClient::send_txn_batch(&node.rpc_client().unwrap(), txns)
+        .await
+        .unwrap();
+    assert_eq!(response, vec![Ok(()); 3]);
+
+    // All of the transactions should be executed in order.
+    poll_until(
+        || async {
+            (node.get_owner_nonce() == nonce + 3)
+                .then_some(())
+                .ok_or(PollUntilError::ConditionNotSatisfied)
+        },
+        Duration::from_secs(10),
+        Duration::from_millis(100),
+    )
+    .await
+    .unwrap();
+
+    network.shutdown().await;
+}
+
+#[test]
+fn test_send_txn_error_matches_send_txn() {
+    // A failed transaction of a batch is reported like `send_txn` would report it.
+    let error = SendTxnError::from(RPCError::socket("mempool closed".to_string()));
+    let expected =
+        jsonrpsee::types::ErrorObject::from(RPCError::socket("mempool closed".to_string()));
+    assert_eq!(error.code, expected.code());
+    assert_eq!(error.code, jsonrpsee::types::error::INTERNAL_ERROR_CODE);
+    assert_eq!(error.message, expected.message());
+}
+
+#[tokio::test]
+async fn test_rpc_simulate_txn() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let chain_id = node.app_query().get_chain_id();
+    let nonce = node.get_owner_nonce();
+
+    // A nonce that skips ahead should revert.
+    let txn = TransactionBuilder::from_update(
+        UpdateMethod::IncrementNonce {},
+        chain_id,
+        nonce + 2,
+        &node.get_owner_signer(),
+    )
+    .into();
+    let response = FleekApiClient::simulate_txn(&node.rpc_client().unwrap(), txn)
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        TransactionResponse::Revert(ExecutionError::InvalidNonce)
+    );
+
+    // A valid transaction should succeed without being applied to the state.
+    let txn = TransactionBuilder::from_update(
+        UpdateMethod::IncrementNonce {},
+        chain_id,
+        nonce + 1,
+        &node.get_owner_signer(),
+    )
+    .into();
+    let response = FleekApiClient::simulate_txn(&node.rpc_client().unwrap(), txn)
+        .await
+        .unwrap();
+    assert!(response.is_success());
+    assert_eq!(node.get_owner_nonce(), nonce);
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_rpc_get_transaction_receipt() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+    let client = node.rpc_client().unwrap();
+
+    let chain_id = node.app_query().get_chain_id();
+    let nonce = node.get_owner_nonce();
+    let txn: TransactionRequest = TransactionBuilder::from_update(
+        UpdateMethod::IncrementNonce {},
+        chain_id,
+        nonce + 1,
+        &node.get_owner_signer(),
+    )
+    .into();
+    let hash = txn.hash();
+
+    // The receipt is not known before the transaction is executed.
+    assert_eq!(
+        FleekApiClient::get_transaction_receipt(&client, hash)
+            .await
+            .unwrap(),
+        None
+    );
+
+    FleekApiClient::send_txn(&client, txn).await.unwrap();
+
+    let receipt = poll_until(
+        || async {
+            FleekApiClient::get_transaction_receipt(&client, hash)
+                .await
+                .unwrap()
+                .ok_or(PollUntilError::ConditionNotSatisfied)
+        },
+        Duration::from_secs(10),
+        Duration::from_millis(100),
+    )
+    .await
+    .unwrap();
+    assert_eq!(receipt.transaction_hash, hash);
+    assert!(receipt.response.is_success());
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_rpc_get_health_status() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+    let client = node.rpc_client().unwrap();
+
+    let status = FleekApiClient::get_health_status(&client).await.unwrap();
+    let last_executed_block = status.last_executed_block;
+
+    // Execute a block.
+    let chain_id = node.app_query().get_chain_id();
+    let nonce = node.get_owner_nonce();
+    FleekApiClient::send_txn(
+        &client,
+        TransactionBuilder::from_update(
+            UpdateMethod::IncrementNonce {},
+            chain_id,
+            nonce + 1,
+            &node.get_owner_signer(),
+        )
+        .into(),
+    )
+    .await
+    .unwrap();
+
+    let status = poll_until(
+        || async {
+            let status = FleekApiClient::get_health_status(&client).await.unwrap();
+            (status.last_executed_block > last_executed_block)
+                .then_some(status)
+                .ok_or(PollUntilError::ConditionNotSatisfied)
+        },
+        Duration::from_secs(10),
+        Duration::from_millis(100),
+    )
+    .await
+    .unwrap();
+    assert_eq!(status.current_epoch, 0);
+    assert!(status.is_committee_member);
+    assert_eq!(status.pending_parcel_count, 0);
+    assert!(status.synced);
+
+    network.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_rpc_get_flk_balance() {
     let owner_secret_key = AccountOwnerSecretKey::generate();
@@ -123,6 +340,132 @@ async fn test_rpc_get_reputation() {
     network.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_rpc_get_reputation_measurements() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(3)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let target = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+    let target_index = target.index();
+
+    // Submit a measurement about the first node from each of the other nodes.
+    let mut reporters = Vec::new();
+    for i in 1..3 {
+        let node = network
+            .node(i)
+            .downcast::<TestFullNodeComponentsWithMockConsensus>();
+        let measurements = ReputationMeasurements {
+            latency: Some(Duration::from_millis(100 * i as u64)),
+            interactions: None,
+            inbound_bandwidth: None,
+            outbound_bandwidth: None,
+            bytes_received: None,
+            bytes_sent: None,
+            uptime: None,
+            hops: None,
+        };
+        node.execute_transaction_from_node(UpdateMethod::SubmitReputationMeasurements {
+            measurements: [(target_index, measurements)].into(),
+        })
+        .await
+        .unwrap();
+        reporters.push(node.index());
+    }
+    poll_until(
+        || async {
+            (target
+                .app_query()
+                .get_reputation_measurements(&target_index)
+                .unwrap_or_default()
+                .len()
+                == 2)
+                .then_some(())
+                .ok_or(PollUntilError::ConditionNotSatisfied)
+        },
+        Duration::from_secs(10),
+        Duration::from_millis(100),
+    )
+    .await
+    .unwrap();
+
+    let client = target.rpc_client().unwrap();
+    let public_key = target.get_node_public_key();
+
+    // Without paging or a filter all of the measurements are returned.
+    let response =
+        FleekApiClient::get_reputation_measurements(&client, public_key, None, None, None)
+            .await
+            .unwrap();
+    assert_eq!(
+        response
+            .iter()
+            .map(|m| m.reporting_node)
+            .collect::<Vec<_>>(),
+        reporters
+    );
+
+    // Only the measurements of the given reporter are returned.
+    let response = FleekApiClient::get_reputation_measurements(
+        &client,
+        public_key,
+        None,
+        None,
+        Some(reporters[1]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].reporting_node, reporters[1]);
+
+    // Paging skips to the requested position.
+    let response = FleekApiClient::get_reputation_measurements(
+        &client,
+        public_key,
+        None,
+        Some(ReputationPagingParams { start: 1, limit: 1 }),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].reporting_node, reporters[1]);
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_rpc_get_node_uptime() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let response =
+        FleekApiClient::get_node_uptime(&node.rpc_client().unwrap(), node.get_node_public_key())
+            .await
+            .unwrap();
+    assert_eq!(node.app_query().get_node_uptime(&node.index()), response);
+
+    // Unknown nodes should not have an uptime.
+    let response =
+        FleekApiClient::get_node_uptime(&node.rpc_client().unwrap(), NodePublicKey([9; 32]))
+            .await
+            .unwrap();
+    assert!(response.is_none());
+
+    network.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_rpc_get_staked() {
     let mut network = TestNetwork::builder()
@@ -341,6 +684,75 @@ async fn test_rpc_get_node_info() {
     network.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_rpc_get_node_info_by_index() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let response = FleekApiClient::get_node_info_by_index(&node.rpc_client().unwrap(), 0)
+        .await
+        .unwrap()
+        .unwrap();
+    let node_info = node
+        .app_query()
+        .get_node_info(&0, |node_info| node_info)
+        .unwrap();
+    assert_eq!(0, response.index);
+    assert_eq!(node_info, response.info);
+
+    let response = FleekApiClient::get_node_info_by_index(&node.rpc_client().unwrap(), 1)
+        .await
+        .unwrap();
+    assert!(response.is_none());
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_rpc_get_pending_transactions() {
+    let mut network = TestNetwork::builder()
+        .with_mock_consensus(MockConsensusConfig {
+            // Lose the first transaction so that it is never ordered.
+            transactions_to_lose: HashSet::from([1]),
+            new_block_interval: Duration::from_secs(0),
+            block_buffering_interval: Duration::from_millis(0),
+            ..Default::default()
+        })
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let response = FleekApiClient::get_pending_transactions(&node.rpc_client().unwrap())
+        .await
+        .unwrap();
+    assert!(response.is_empty());
+
+    node.signer()
+        .get_socket()
+        .run(UpdateMethod::IncrementNonce {}.into())
+        .await
+        .unwrap();
+
+    let response = FleekApiClient::get_pending_transactions(&node.rpc_client().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response, vec![(1, UpdateMethod::IncrementNonce {})]);
+
+    network.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_rpc_get_staking_amount() {
     let mut network = TestNetwork::builder()
@@ -381,6 +793,56 @@ async fn test_rpc_get_committee_members() {
     network.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_rpc_get_committee_members_index() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(2)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let response = FleekApiClient::get_committee_members_index(&node.rpc_client().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(node.app_query().get_committee_members_by_index(), response);
+    assert_eq!(response.len(), 2);
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_rpc_get_committee_info() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(2)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+
+    let response = FleekApiClient::get_committee_info(&node.rpc_client().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.epoch, 0);
+    assert_eq!(
+        response.members,
+        node.app_query().get_committee_members_by_index()
+    );
+    assert!(response.ready_to_change.is_empty());
+    assert_eq!(
+        response.beacon_phase,
+        node.app_query().get_committee_selection_beacon_phase()
+    );
+
+    network.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_rpc_get_epoch() {
     let mut network = TestNetwork::builder()
@@ -730,6 +1192,95 @@ async fn test_rpc_events() {
     network.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_rpc_events_with_stalled_subscriber() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+    let sender = node.rpc().event_tx();
+
+    // A subscriber that never drains its events.
+    let _stalled = sender.subscribe();
+
+    let client = node.rpc_ws_client().await.unwrap();
+    let mut sub = FleekApiClient::handle_subscription(&client, None)
+        .await
+        .unwrap();
+
+    // Sending far more events than can be buffered doesn't block the sender.
+    let event = |amount: u16| {
+        Event::transfer(
+            EthAddress::from([0; 20]),
+            EthAddress::from([1; 20]),
+            EthAddress::from([2; 20]),
+            HpUfixed::<18>::from(amount),
+        )
+    };
+    for amount in 0..100 {
+        sender.send(vec![event(amount)]);
+    }
+    let last = event(1000);
+    sender.send(vec![last.clone()]);
+
+    // The subscription skips the events it missed, but still receives the latest one.
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let received = sub.next().await.expect("An event from the sub").unwrap();
+            if received == last {
+                break;
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    network.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_rpc_subscribe_blocks() {
+    let mut network = TestNetwork::builder()
+        .with_committee_nodes::<TestFullNodeComponentsWithMockConsensus>(1)
+        .await
+        .build()
+        .await
+        .unwrap();
+    let node = network
+        .node(0)
+        .downcast::<TestFullNodeComponentsWithMockConsensus>();
+    let sender = node.rpc().event_tx();
+
+    let client = node.rpc_ws_client().await.unwrap();
+    let mut sub = FleekApiClient::subscribe_blocks(&client).await.unwrap();
+    let events = vec![
+        Event::transfer(
+            EthAddress::from([0; 20]),
+            EthAddress::from([1; 20]),
+            EthAddress::from([2; 20]),
+            HpUfixed::<18>::from(10_u16),
+        ),
+        Event::transfer(
+            EthAddress::from([0; 20]),
+            EthAddress::from([2; 20]),
+            EthAddress::from([1; 20]),
+            HpUfixed::<18>::from(5_u16),
+        ),
+    ];
+    sender.send(events.clone());
+    assert_eq!(
+        sub.next().await.expect("A block from the sub").unwrap(),
+        events
+    );
+
+    network.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_rpc_get_state_root() {
     let owner_secret_key = AccountOwnerSecretKey::generate();
@@ -15,6 +15,7 @@
 pub const REQ_ACCESS_TOKEN_TAG: u8 = 0x01;
 pub const REQ_EXTEND_ACCESS_TOKEN_TAG: u8 = 0x02;
 pub const REQ_DELIVERY_ACK_TAG: u8 = 0x03;
+pub const REQ_REVOKE_ACCESS_TOKEN_TAG: u8 = 0x04;
 
 pub const RES_SERVICE_PAYLOAD_TAG: u8 = 0x00;
 pub const RES_SERVICE_PAYLOAD_CHUNK_TAG: u8 = 0x40;
@@ -185,13 +186,27 @@ pub enum RequestFrame {
     AccessToken { ttl: u64 },
     /// Extend the access token associated with this primary connection.
     ExtendAccessToken { ttl: u64 },
+    /// Revoke the access token associated with this primary connection.
+    RevokeAccessToken,
     /// Delivery acknowledgment, a client signature for some work the node and
-    /// service committed to.
+    /// service committed to. The signature is over [`delivery_acknowledgment_digest`].
     DeliveryAcknowledgment {
-        // TODO:
+        /// The total number of payload bytes received by the client on this connection.
+        commodity: u128,
+        signature: ClientSignature,
     },
 }
 
+/// Returns the message a client has to sign in order to acknowledge the delivery of `commodity`
+/// bytes from the given service.
+pub fn delivery_acknowledgment_digest(service_id: u32, commodity: u128) -> [u8; 25] {
+    let mut buf = [0; 25];
+    buf[0..5].copy_from_slice(NETWORK_PREFIX);
+    buf[5..9].copy_from_slice(&service_id.to_be_bytes());
+    buf[9..25].copy_from_slice(&commodity.to_be_bytes());
+    buf
+}
+
 impl RequestFrame {
     /// Encode a complete frame into bytes
     pub fn encode(&self) -> Bytes {
@@ -214,8 +229,17 @@ pub fn encode(&self) -> Bytes {
                 buf.put_u64(*ttl);
                 buf.into()
             },
-            // TODO: encode signature bytes
-            Self::DeliveryAcknowledgment { .. } => vec![REQ_DELIVERY_ACK_TAG].into(),
+            Self::RevokeAccessToken => vec![REQ_REVOKE_ACCESS_TOKEN_TAG].into(),
+            Self::DeliveryAcknowledgment {
+                commodity,
+                signature,
+            } => {
+                let mut buf = Vec::with_capacity(65);
+                buf.put_u8(REQ_DELIVERY_ACK_TAG);
+                buf.put_u128(*commodity);
+                buf.put_slice(&signature.0);
+                buf.into()
+            },
         }
     }
 
@@ -246,8 +270,25 @@ pub fn decode(bytes: &[u8]) -> Result<Self> {
                 let ttl = u64::from_be_bytes(*array_ref!(bytes, 1, 8));
                 Ok(Self::ExtendAccessToken { ttl })
             },
-            // TODO: decode signature bytes
-            REQ_DELIVERY_ACK_TAG => Ok(Self::DeliveryAcknowledgment {}),
+            REQ_REVOKE_ACCESS_TOKEN_TAG => {
+                if bytes.len() != 1 {
+                    return Err(anyhow!("wrong number of bytes"));
+                }
+
+                Ok(Self::RevokeAccessToken)
+            },
+            REQ_DELIVERY_ACK_TAG => {
+                if bytes.len() != 65 {
+                    return Err(anyhow!("wrong number of bytes"));
+                }
+
+                let commodity = u128::from_be_bytes(*array_ref!(bytes, 1, 16));
+                let signature = ClientSignature(*array_ref!(bytes, 17, 48));
+                Ok(Self::DeliveryAcknowledgment {
+                    commodity,
+                    signature,
+                })
+            },
             _ => Err(anyhow!("invalid frame tag")),
         }
     }
@@ -351,6 +392,8 @@ pub enum TerminationReason {
     ResourcesUnavailable,
     InternalError,
     Shutdown,
+    ServiceSocketClosed,
+    PayloadTooLarge,
     Unknown = 0xFF,
 }
 
@@ -366,6 +409,11 @@ pub fn from_u8(byte: u8) -> Self {
             0x85 => Self::ServiceTerminated,
             0x86 => Self::ConnectionInUse,
             0x87 => Self::WrongPermssion,
+            0x88 => Self::ResourcesUnavailable,
+            0x89 => Self::InternalError,
+            0x8A => Self::Shutdown,
+            0x8B => Self::ServiceSocketClosed,
+            0x8C => Self::PayloadTooLarge,
             _ => Self::Unknown,
         }
     }
@@ -425,7 +473,11 @@ fn request_frames() {
             },
             RequestFrame::AccessToken { ttl: 2 },
             RequestFrame::ExtendAccessToken { ttl: 12 },
-            RequestFrame::DeliveryAcknowledgment {}
+            RequestFrame::RevokeAccessToken,
+            RequestFrame::DeliveryAcknowledgment {
+                commodity: 420,
+                signature: ClientSignature([7; 48]),
+            }
         );
     }
 
@@ -467,6 +519,27 @@ fn response_frames() {
             ResponseFrame::Termination {
                 reason: TerminationReason::ServiceTerminated
             },
+            ResponseFrame::Termination {
+                reason: TerminationReason::ConnectionInUse
+            },
+            ResponseFrame::Termination {
+                reason: TerminationReason::WrongPermssion
+            },
+            ResponseFrame::Termination {
+                reason: TerminationReason::ResourcesUnavailable
+            },
+            ResponseFrame::Termination {
+                reason: TerminationReason::InternalError
+            },
+            ResponseFrame::Termination {
+                reason: TerminationReason::Shutdown
+            },
+            ResponseFrame::Termination {
+                reason: TerminationReason::ServiceSocketClosed
+            },
+            ResponseFrame::Termination {
+                reason: TerminationReason::PayloadTooLarge
+            },
             ResponseFrame::Termination {
                 reason: TerminationReason::Unknown
             }
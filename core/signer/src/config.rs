@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use resolved_pathbuf::ResolvedPathBuf;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Config {
+    /// The duration the signer will wait for a transaction to get ordered before resending it
+    /// to the mempool for the first time. In mainnet, this should be less than 12 secs.
+    #[serde(with = "humantime_serde")]
+    pub resend_timeout: Duration,
+    /// The factor by which the wait before resending a transaction grows on every retry.
+    pub resend_backoff_multiplier: f64,
+    /// The maximum duration the signer will wait before resending a transaction.
+    #[serde(with = "humantime_serde")]
+    pub max_resend_timeout: Duration,
+    /// The maximum number of times a transaction will be sent to the mempool before it is
+    /// dropped.
+    pub max_retries: u8,
+    /// The number of new block notifications that can be queued for the signer. When the queue
+    /// is full, new notifications are dropped since the signer only needs to process the latest.
+    pub new_block_buffer: usize,
+    /// The path to the account owner key used to sign transactions that must be sent by the
+    /// account of the node operator. If not set, the signer can only sign as the node.
+    pub account_key_path: Option<ResolvedPathBuf>,
+    /// If set, transactions are simulated before they are submitted, and the ones that would
+    /// revert are rejected without being assigned a nonce.
+    pub simulate_before_submit: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            resend_timeout: Duration::from_secs(60),
+            resend_backoff_multiplier: 2.0,
+            max_resend_timeout: Duration::from_secs(600),
+            max_retries: 3,
+            new_block_buffer: 16,
+            account_key_path: None,
+            simulate_before_submit: false,
+        }
+    }
+}
+
+impl Config {
+    /// Checks that the resend backoff is a finite multiplier of at least 1 and that the first
+    /// resend timeout does not exceed the maximum one.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.resend_backoff_multiplier.is_finite() || self.resend_backoff_multiplier < 1.0 {
+            anyhow::bail!(
+                "the resend backoff multiplier must be a finite number of at least 1, got {}",
+                self.resend_backoff_multiplier
+            );
+        }
+        if self.resend_timeout > self.max_resend_timeout {
+            anyhow::bail!(
+                "the resend timeout {:?} is longer than the max resend timeout {:?}",
+                self.resend_timeout,
+                self.max_resend_timeout
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns how long to wait before resending a transaction that has already been sent `tries`
+    /// times, before jitter is applied. The delay never exceeds `max_resend_timeout`, even if the
+    /// backoff overflows.
+    pub fn resend_delay(&self, tries: u8) -> Duration {
+        let exponent = i32::from(tries.saturating_sub(1));
+        let delay =
+            self.resend_timeout.as_secs_f64() * self.resend_backoff_multiplier.powi(exponent);
+        Duration::try_from_secs_f64(delay).map_or(self.max_resend_timeout, |delay| {
+            delay.min(self.max_resend_timeout)
+        })
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 pub mod tests;
 
+pub mod config;
 mod listener;
 mod signer;
+pub use config::Config as SignerConfig;
 pub use signer::Signer;
@@ -1,15 +1,23 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 
 use affair::AsyncWorker;
-use anyhow::anyhow;
-use fleek_crypto::{NodePublicKey, NodeSecretKey, SecretKey, TransactionSender};
+use anyhow::{anyhow, Context};
+use fleek_crypto::{
+    AccountOwnerSecretKey,
+    EthAddress,
+    NodePublicKey,
+    NodeSecretKey,
+    SecretKey,
+    TransactionSender,
+};
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::{
     ExecuteTransaction,
     NodeIndex,
+    TransactionConfirmationError,
     TransactionReceipt,
     TransactionResponse,
     UpdateMethod,
@@ -17,30 +25,22 @@
     UpdateRequest,
 };
 use lightning_interfaces::{spawn_worker, BlockExecutedNotification};
+use lightning_metrics::increment_counter;
 use lightning_utils::application::QueryRunnerExt;
 use quick_cache::sync::Cache;
-use tokio::sync::{oneshot, Mutex};
+use rand::Rng;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tracing::{debug, error, warn};
 
+use crate::config::Config;
 use crate::listener::BlockListener;
 
-// If a transaction does not get ordered, the signer will try to resend it.
-// `TIMEOUT` specifies the duration the signer will wait before resending transactions to the
-// mempool.
-// In mainnet, this should be less than 12 secs.
-#[cfg(not(test))]
-const TIMEOUT: Duration = Duration::from_secs(60);
-#[cfg(test)]
-const TIMEOUT: Duration = Duration::from_secs(3);
-
 // Timeout for awaiting the transaction receipt.
 const TXN_RECEIPT_TIMEOUT: Duration = Duration::from_secs(30);
 // Interval for checking if the receipt is available.
 const TXN_RECEIPT_INTERVAL: Duration = Duration::from_millis(100);
 
-// Maximum number of times we will resend a transaction.
-const MAX_RETRIES: u8 = 3;
-
 // Maximum number of times we will resend to the forwarder.
 const MAX_FORWARDER_RETRIES: u8 = 3;
 
@@ -50,28 +50,71 @@
 // Receipt cache capacity.
 const CACHE_CAPACITY: usize = 1000;
 
+// Fraction of the resend delay that is randomized, so that nodes don't resend in lockstep.
+const RESEND_JITTER: f64 = 0.1;
+
+type ConfirmationSender = oneshot::Sender<Result<TransactionReceipt, TransactionConfirmationError>>;
+
 pub struct Signer<C: NodeComponents> {
     socket: SignerSubmitTxSocket,
-    worker: SignerWorker<C>,
+    confirming_socket: SignerConfirmTxSocket,
+    batch_socket: SignerBatchSubmitTxSocket,
+    pending_socket: SignerPendingTxSocket,
+    account_socket: SignerAccountTxSocket,
+    pub(crate) worker: SignerWorker<C>,
     _c: PhantomData<C>,
 }
 
 #[derive(Clone)]
-struct SignerWorker<C: NodeComponents> {
+pub(crate) struct SignerWorker<C: NodeComponents> {
+    pub(crate) state: Arc<Mutex<SignerState<C>>>,
+}
+
+#[derive(Clone)]
+struct SignerBatchWorker<C: NodeComponents> {
+    state: Arc<Mutex<SignerState<C>>>,
+}
+
+#[derive(Clone)]
+struct SignerConfirmingWorker<C: NodeComponents> {
+    state: Arc<Mutex<SignerState<C>>>,
+}
+
+#[derive(Clone)]
+struct SignerPendingWorker<C: NodeComponents> {
+    state: Arc<Mutex<SignerState<C>>>,
+}
+
+#[derive(Clone)]
+struct SignerAccountWorker<C: NodeComponents> {
     state: Arc<Mutex<SignerState<C>>>,
 }
 
-struct SignerState<C: NodeComponents> {
+pub(crate) struct SignerState<C: NodeComponents> {
+    config: Config,
     query_runner: c![C::ApplicationInterface::SyncExecutor],
     node_secret_key: NodeSecretKey,
     node_public_key: NodePublicKey,
     mempool_socket: MempoolSocket,
     chain_id: Option<u32>,
-    base_nonce: u64,
-    next_nonce: u64,
-    base_timestamp: Option<SystemTime>,
-    pending_transactions: VecDeque<PendingTransaction>,
+    pub(crate) base_nonce: u64,
+    pub(crate) next_nonce: u64,
+    pub(crate) pending_transactions: VecDeque<PendingTransaction>,
+    // Senders for the transactions submitted through the confirming socket, keyed by the
+    // assigned nonce.
+    pending_confirmations: HashMap<u64, ConfirmationSender>,
     receipt_cache: Arc<Cache<[u8; 32], TransactionReceipt>>,
+    pub(crate) account: Option<AccountSigner>,
+}
+
+/// Signs transactions with the account owner key. The account has its own nonce sequence, so
+/// its pending transactions are tracked separately from the ones of the node.
+pub(crate) struct AccountSigner {
+    secret_key: AccountOwnerSecretKey,
+    pub(crate) address: EthAddress,
+    pub(crate) base_nonce: u64,
+    pub(crate) next_nonce: u64,
+    pub(crate) pending_transactions: VecDeque<PendingTransaction>,
 }
 
 struct LazyNodeIndex {
@@ -81,18 +124,39 @@ struct LazyNodeIndex {
 
 impl<C: NodeComponents> Signer<C> {
     pub fn init(
+        config: &C::ConfigProviderInterface,
         keystore: &C::KeystoreInterface,
         forwarder: &C::ForwarderInterface,
         app: &C::ApplicationInterface,
         fdi::Cloned(notifier): fdi::Cloned<C::NotifierInterface>,
         fdi::Cloned(waiter): fdi::Cloned<lightning_interfaces::ShutdownWaiter>,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let query_runner = app.sync_query();
+        let config = config.get::<Self>();
+        config.validate().context("Invalid signer config")?;
+
+        let account = match &config.account_key_path {
+            Some(path) => {
+                let encoded = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read account key from {path:?}"))?;
+                let secret_key = AccountOwnerSecretKey::decode_pem(&encoded)
+                    .context("Failed to decode account pem file")?;
+                Some(AccountSigner {
+                    address: secret_key.to_pk().into(),
+                    secret_key,
+                    base_nonce: 0,
+                    next_nonce: 0,
+                    pending_transactions: VecDeque::new(),
+                })
+            },
+            None => None,
+        };
 
         let receipt_cache = Arc::new(Cache::new(CACHE_CAPACITY));
         let listener = BlockListener::<C>::new(receipt_cache.clone(), notifier.clone());
 
         let state = SignerState {
+            config,
             query_runner,
             node_secret_key: keystore.get_ed25519_sk(),
             node_public_key: keystore.get_ed25519_pk(),
@@ -100,14 +164,27 @@ pub fn init(
             chain_id: None,
             base_nonce: 0,
             next_nonce: 0,
-            base_timestamp: None,
             pending_transactions: VecDeque::new(),
+            pending_confirmations: HashMap::new(),
             receipt_cache,
+            account,
         };
 
         let worker = SignerWorker {
             state: Arc::new(Mutex::new(state)),
         };
+        let confirming_worker = SignerConfirmingWorker {
+            state: worker.state.clone(),
+        };
+        let batch_worker = SignerBatchWorker {
+            state: worker.state.clone(),
+        };
+        let pending_worker = SignerPendingWorker {
+            state: worker.state.clone(),
+        };
+        let account_worker = SignerAccountWorker {
+            state: worker.state.clone(),
+        };
 
         spawn!(
             async move {
@@ -115,13 +192,27 @@ pub fn init(
             },
             "SIGNER: block listener task"
         );
-        let socket = spawn_worker!(worker.clone(), "SIGNER", waiter, crucial);
+        let socket = spawn_worker!(worker.clone(), "SIGNER", waiter.clone(), crucial);
+        let confirming_socket = spawn_worker!(
+            confirming_worker,
+            "SIGNER: confirming",
+            waiter.clone(),
+            crucial
+        );
+        let batch_socket = spawn_worker!(batch_worker, "SIGNER: batch", waiter.clone(), crucial);
+        let pending_socket =
+            spawn_worker!(pending_worker, "SIGNER: pending", waiter.clone(), crucial);
+        let account_socket = spawn_worker!(account_worker, "SIGNER: account", waiter, crucial);
 
-        Self {
+        Ok(Self {
             socket,
+            confirming_socket,
+            batch_socket,
+            pending_socket,
+            account_socket,
             worker,
             _c: PhantomData,
-        }
+        })
     }
 
     pub async fn start(
@@ -137,11 +228,19 @@ pub async fn start(
         let mut node_index = LazyNodeIndex::new(guard.node_public_key);
         let nonce = node_index.query_nonce(&query_runner);
         guard.init_state(nonce);
+        // Tokio panics on a channel without capacity, so the buffer always fits one notification.
+        let (new_block_tx, new_block_rx) = mpsc::channel(guard.config.new_block_buffer.max(1));
         drop(guard);
 
         spawn!(
             async move {
-                new_block_task(node_index, worker, subscriber, query_runner).await;
+                forward_new_blocks(subscriber, new_block_tx).await;
+            },
+            "SIGNER: new block forwarder"
+        );
+        spawn!(
+            async move {
+                new_block_task(node_index, worker, new_block_rx, query_runner).await;
             },
             "SIGNER: new block task"
         );
@@ -161,15 +260,221 @@ impl<C: NodeComponents> SignerInterface<C> for Signer<C> {
     fn get_socket(&self) -> SignerSubmitTxSocket {
         self.socket.clone()
     }
+
+    /// Returns a socket that can be used to submit transactions to the mempool and await
+    /// their confirmation. The response of the socket is a receiver that resolves to the
+    /// transaction receipt once the transaction was executed, or to an error if the signer
+    /// gave up on the transaction.
+    fn get_confirming_socket(&self) -> SignerConfirmTxSocket {
+        self.confirming_socket.clone()
+    }
+
+    /// Returns a socket that can be used to submit a batch of transactions to the mempool. The
+    /// transactions are assigned contiguous nonces, which are returned in the same order as the
+    /// submitted methods.
+    fn get_batch_socket(&self) -> SignerBatchSubmitTxSocket {
+        self.batch_socket.clone()
+    }
+
+    /// Returns a socket that can be used to inspect the transactions that were submitted to the
+    /// mempool but have not been ordered yet. The response contains the assigned nonce and the
+    /// method of each transaction, in the order they were submitted.
+    fn get_pending_socket(&self) -> SignerPendingTxSocket {
+        self.pending_socket.clone()
+    }
+
+    /// Returns a socket that can be used to submit transactions to the mempool that are signed
+    /// by the account owner key of the node operator, if one is configured.
+    fn get_account_socket(&self) -> SignerAccountTxSocket {
+        self.account_socket.clone()
+    }
 }
 
 impl<C: NodeComponents> SignerState<C> {
     fn init_state(&mut self, base_nonce: u64) {
         self.base_nonce = base_nonce;
         self.next_nonce = base_nonce + 1;
+
+        if let Some(account) = &mut self.account {
+            let nonce = self
+                .query_runner
+                .get_account_info(&account.address, |a| a.nonce)
+                .unwrap_or(0);
+            account.base_nonce = nonce;
+            account.next_nonce = nonce + 1;
+        }
+    }
+
+    /// Query the application layer for the last nonce of the account, if the signer has one.
+    fn query_account_nonce(&self) -> Option<u64> {
+        let account = self.account.as_ref()?;
+        Some(
+            self.query_runner
+                .get_account_info(&account.address, |a| a.nonce)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Signs the transaction with the account owner key and the next nonce of the account, and
+    /// sends it to the mempool. Returns the assigned nonce, or `None` if there is no account key.
+    async fn sign_new_account_tx(
+        &mut self,
+        method: UpdateMethod,
+    ) -> Result<Option<u64>, TransactionConfirmationError> {
+        if self.chain_id.is_none() {
+            self.chain_id = Some(self.query_runner.get_chain_id());
+        }
+        let Some(account) = self.account.as_mut() else {
+            return Ok(None);
+        };
+
+        let assigned_nonce = account.next_nonce;
+        let update_payload = UpdatePayload {
+            sender: TransactionSender::AccountOwner(account.address),
+            method,
+            nonce: assigned_nonce,
+            chain_id: self.chain_id.unwrap(),
+        };
+
+        let digest = update_payload.to_digest();
+        let signature = account.secret_key.sign(&digest);
+        let update_request = UpdateRequest {
+            signature: signature.into(),
+            payload: update_payload,
+        };
+
+        if self.config.simulate_before_submit {
+            check_simulation(&self.query_runner, &update_request)?;
+        }
+
+        if let Err(e) = send_to_forwarder(&self.mempool_socket, &update_request).await {
+            error!("failed to send account transaction to mempool: {e:?}");
+        }
+
+        // Optimistically increment nonce
+        account.next_nonce += 1;
+
+        account.pending_transactions.push_back(PendingTransaction {
+            update_request,
+            next_resend: next_resend(&self.config, 1),
+            tries: 1,
+            receipt_tx: None,
+        });
+
+        Ok(Some(assigned_nonce))
+    }
+
+    pub(crate) async fn sync_account_with_application(&mut self, application_nonce: u64) {
+        let Some(account) = &mut self.account else {
+            return;
+        };
+
+        account.base_nonce = application_nonce;
+        while account
+            .pending_transactions
+            .front()
+            .is_some_and(|tx| tx.update_request.payload.nonce <= application_nonce)
+        {
+            account.pending_transactions.pop_front();
+        }
+
+        if application_nonce >= account.next_nonce {
+            warn!(
+                "account nonce {application_nonce} is ahead of next nonce {}, resetting",
+                account.next_nonce
+            );
+            account.pending_transactions.clear();
+            account.next_nonce = application_nonce + 1;
+        }
+
+        let resend_due = account
+            .pending_transactions
+            .front()
+            .is_some_and(|tx| tx.next_resend <= SystemTime::now());
+        if resend_due {
+            if account.pending_transactions[0].tries >= self.config.max_retries {
+                // Unlike the node, the account transactions are not replaced with increment nonce
+                // transactions, so all of them are dropped at once to keep the nonces contiguous.
+                warn!(
+                    "dropping {} account transactions after too many retries",
+                    account.pending_transactions.len()
+                );
+                account.pending_transactions.clear();
+                account.next_nonce = account.base_nonce + 1;
+                return;
+            }
+
+            for pending_tx in account.pending_transactions.iter_mut() {
+                if let Err(e) =
+                    send_to_forwarder(&self.mempool_socket, &pending_tx.update_request).await
+                {
+                    error!("failed to send account transaction to mempool: {e:?}");
+                } else {
+                    pending_tx.tries += 1;
+                }
+                pending_tx.next_resend = next_resend(&self.config, pending_tx.tries);
+            }
+        }
+    }
+
+    async fn sign_new_tx(
+        &mut self,
+        request: ExecuteTransaction,
+    ) -> Result<u64, TransactionConfirmationError> {
+        if self.config.simulate_before_submit {
+            self.simulate(&request.method)?;
+        }
+
+        let assigned_nonce = self.sign_and_send(request).await;
+        Ok(assigned_nonce)
+    }
+
+    /// Simulates the execution of the method as a transaction sent by the node. Returns an error
+    /// if the transaction would revert.
+    fn simulate(&mut self, method: &UpdateMethod) -> Result<(), TransactionConfirmationError> {
+        if self.chain_id.is_none() {
+            self.chain_id = Some(self.query_runner.get_chain_id());
+        }
+
+        let update_payload = UpdatePayload {
+            sender: TransactionSender::NodeMain(self.node_public_key),
+            method: method.clone(),
+            nonce: self.next_nonce,
+            chain_id: self.chain_id.unwrap(),
+        };
+        let digest = update_payload.to_digest();
+        let signature = self.node_secret_key.sign(&digest);
+        let update_request = UpdateRequest {
+            signature: signature.into(),
+            payload: update_payload,
+        };
+
+        check_simulation(&self.query_runner, &update_request)
+    }
+
+    async fn sign_new_batch(
+        &mut self,
+        methods: Vec<UpdateMethod>,
+    ) -> Result<Vec<u64>, TransactionConfirmationError> {
+        // The whole batch is rejected if any of the transactions reverts, so that the nonces of the
+        // submitted transactions stay contiguous. Each transaction is simulated against the current
+        // state, without the effects of the ones before it in the batch.
+        if self.config.simulate_before_submit {
+            for method in &methods {
+                self.simulate(method)?;
+            }
+        }
+
+        let mut assigned_nonces = Vec::with_capacity(methods.len());
+        for method in methods {
+            assigned_nonces.push(self.sign_and_send(method.into()).await);
+        }
+        Ok(assigned_nonces)
     }
 
-    async fn sign_new_tx(&mut self, request: ExecuteTransaction) -> u64 {
+    /// Signs the transaction with the next nonce, sends it to the mempool and pushes it to the
+    /// pending transactions. Returns the assigned nonce.
+    async fn sign_and_send(&mut self, request: ExecuteTransaction) -> u64 {
         if self.chain_id.is_none() {
             self.chain_id = Some(self.query_runner.get_chain_id());
         }
@@ -198,23 +503,41 @@ async fn sign_new_tx(&mut self, request: ExecuteTransaction) -> u64 {
         // Optimistically increment nonce
         self.next_nonce += 1;
 
-        let timestamp = SystemTime::now();
         self.pending_transactions.push_back(PendingTransaction {
             update_request,
-            timestamp,
+            next_resend: next_resend(&self.config, 1),
             tries: 1,
             receipt_tx,
         });
 
-        // Set timer
-        if self.base_timestamp.is_none() {
-            self.base_timestamp = Some(timestamp);
-        }
-
         assigned_nonce
     }
 
-    async fn sync_with_application(&mut self, application_nonce: u64) {
+    pub(crate) async fn sync_with_application(&mut self, application_nonce: u64) {
+        // Every nonce we have assigned is strictly smaller than `next_nonce`. If the application
+        // is already past that, transactions were ordered out of band (e.g. through another node
+        // using the same key). We can't tell which of the pending transactions were ordered, so
+        // they are dropped instead of being confirmed with receipts that may not be theirs.
+        if application_nonce >= self.next_nonce {
+            warn!(
+                "application nonce {application_nonce} is ahead of next nonce {}, resetting",
+                self.next_nonce
+            );
+            for txn in self.pending_transactions.drain(..) {
+                if let Some(confirmation_tx) = self
+                    .pending_confirmations
+                    .remove(&txn.update_request.payload.nonce)
+                {
+                    if let Err(e) =
+                        confirmation_tx.send(Err(TransactionConfirmationError::NonceConflict))
+                    {
+                        warn!("Failed to send transaction confirmation: {e:?}");
+                    }
+                }
+            }
+            self.next_nonce = application_nonce + 1;
+        }
+
         // All transactions in range [base_nonce, application_nonce] have
         // been ordered, so we can remove them from `pending_transactions`.
         self.base_nonce = application_nonce;
@@ -222,16 +545,20 @@ async fn sync_with_application(&mut self, application_nonce: u64) {
             && self.pending_transactions[0].update_request.payload.nonce <= application_nonce
         {
             if let Some(txn) = self.pending_transactions.pop_front() {
+                let confirmation_tx = self
+                    .pending_confirmations
+                    .remove(&txn.update_request.payload.nonce);
                 // Check if the request contained a receipt sender.
                 // In this case we will await the transaction receipt in a task, and send it.
                 // This is done on a best effort basis. Errors won't be handled.
-                if let Some(receipt_tx) = txn.receipt_tx {
+                if txn.receipt_tx.is_some() || confirmation_tx.is_some() {
                     let receipt_cache = self.receipt_cache.clone();
                     spawn!(
                         async move {
                             respond_with_receipt(
                                 receipt_cache,
-                                receipt_tx,
+                                txn.receipt_tx,
+                                confirmation_tx,
                                 &txn.update_request,
                                 TXN_RECEIPT_TIMEOUT,
                             )
@@ -243,71 +570,131 @@ async fn sync_with_application(&mut self, application_nonce: u64) {
             }
         }
 
-        if self.pending_transactions.is_empty() {
-            self.base_timestamp = None;
-        } else if let Some(base_timestamp) = self.base_timestamp {
-            if base_timestamp.elapsed().unwrap() >= TIMEOUT {
-                // At this point we assume that the transactions in the buffer will never get
-                // ordered.
-                self.base_timestamp = None;
-                // Reset `next_nonce` to the nonce the application is expecting.
-                self.next_nonce = self.base_nonce + 1;
-                // Resend all transactions in the buffer.
-
-                for tx in self.pending_transactions.iter_mut() {
-                    if matches!(
-                        self.query_runner
-                            .simulate_txn(tx.update_request.clone().into()),
-                        TransactionResponse::Revert(_)
-                    ) || tx.tries >= MAX_RETRIES
-                    {
-                        // If transaction reverts or we reached the maximum number of retries, don't
-                        // retry again.
-                        // To prevent invalidating the nonces of the following pending transactions,
-                        // we have to increment the nonce on the application state.
-                        let method = UpdateMethod::IncrementNonce {};
-                        let update_payload = UpdatePayload {
-                            sender: TransactionSender::NodeMain(self.node_public_key),
-                            method,
-                            nonce: self.next_nonce,
-                            chain_id: self.chain_id.unwrap(),
-                        };
-                        let digest = update_payload.to_digest();
-                        let signature = self.node_secret_key.sign(&digest);
-                        let update_request = UpdateRequest {
-                            signature: signature.into(),
-                            payload: update_payload,
-                        };
-                        tx.update_request = update_request;
-                    } else {
-                        // Since we just replace transactions that we don't resend with an
-                        // increment nonce transaction, we don't have to update the nonce of the
-                        // transactions we try to resend.
-                        assert_eq!(tx.update_request.payload.nonce, self.next_nonce);
+        // The oldest pending transaction holds back all of the following ones, so its resend
+        // schedule decides when the whole buffer is resent.
+        let resend_due = self
+            .pending_transactions
+            .front()
+            .is_some_and(|tx| tx.next_resend <= SystemTime::now());
+        if resend_due {
+            // At this point we assume that the transactions in the buffer will never get ordered.
+            // Reset `next_nonce` to the nonce the application is expecting.
+            self.next_nonce = self.base_nonce + 1;
+            // Resend all transactions in the buffer.
+
+            for tx in self.pending_transactions.iter_mut() {
+                let dropped = match self
+                    .query_runner
+                    .simulate_txn(tx.update_request.clone().into())
+                {
+                    TransactionResponse::Revert(error) => {
+                        Some(TransactionConfirmationError::Reverted(error))
+                    },
+                    _ if tx.tries >= self.config.max_retries => {
+                        Some(TransactionConfirmationError::MaxRetriesExceeded(tx.tries))
+                    },
+                    _ => None,
+                };
+
+                if let Some(error) = dropped {
+                    // If transaction reverts or we reached the maximum number of retries, don't
+                    // retry again.
+                    let method: &'static str = (&tx.update_request.payload.method).into();
+                    match &error {
+                        TransactionConfirmationError::Reverted(_) => {
+                            increment_counter!(
+                                "signer_transaction_reverted",
+                                Some("Counter for transactions dropped by the signer after reverting"),
+                                "method" => method
+                            );
+                        },
+                        TransactionConfirmationError::MaxRetriesExceeded(_) => {
+                            increment_counter!(
+                                "signer_transaction_exhausted",
+                                Some("Counter for transactions dropped by the signer after too many retries"),
+                                "method" => method
+                            );
+                        },
+                        // The other errors are not produced when resending.
+                        _ => {},
                     }
-                    // Update timestamp to resending time.
-                    tx.timestamp = SystemTime::now();
-                    if self.base_timestamp.is_none() {
-                        self.base_timestamp = Some(tx.timestamp);
+                    if let Some(confirmation_tx) = self
+                        .pending_confirmations
+                        .remove(&tx.update_request.payload.nonce)
+                    {
+                        if let Err(e) = confirmation_tx.send(Err(error)) {
+                            warn!("Failed to send transaction confirmation: {e:?}");
+                        }
                     }
-
-                    self.next_nonce += 1;
+                    // To prevent invalidating the nonces of the following pending transactions,
+                    // we have to increment the nonce on the application state.
+                    let method = UpdateMethod::IncrementNonce {};
+                    let update_payload = UpdatePayload {
+                        sender: TransactionSender::NodeMain(self.node_public_key),
+                        method,
+                        nonce: self.next_nonce,
+                        chain_id: self.chain_id.unwrap(),
+                    };
+                    let digest = update_payload.to_digest();
+                    let signature = self.node_secret_key.sign(&digest);
+                    let update_request = UpdateRequest {
+                        signature: signature.into(),
+                        payload: update_payload,
+                    };
+                    tx.update_request = update_request;
+                    // The replacement is a new transaction, so it gets its own retries and the
+                    // dropped transaction is only reported once.
+                    tx.tries = 0;
+                } else {
+                    // Since we just replace transactions that we don't resend with an
+                    // increment nonce transaction, we don't have to update the nonce of the
+                    // transactions we try to resend.
+                    assert_eq!(tx.update_request.payload.nonce, self.next_nonce);
                 }
 
-                for pending_tx in self.pending_transactions.iter_mut() {
-                    if let Err(e) =
-                        send_to_forwarder(&self.mempool_socket, &pending_tx.update_request).await
-                    {
-                        error!("failed to send transaction to mempool: {e:?}");
-                    } else {
-                        pending_tx.tries += 1;
-                    }
+                self.next_nonce += 1;
+            }
+
+            for pending_tx in self.pending_transactions.iter_mut() {
+                if let Err(e) =
+                    send_to_forwarder(&self.mempool_socket, &pending_tx.update_request).await
+                {
+                    error!("failed to send transaction to mempool: {e:?}");
+                } else {
+                    pending_tx.tries += 1;
+                    let method: &'static str = (&pending_tx.update_request.payload.method).into();
+                    increment_counter!(
+                        "signer_transaction_resent",
+                        Some("Counter for transactions resent to the mempool by the signer"),
+                        "method" => method
+                    );
                 }
+                pending_tx.next_resend = next_resend(&self.config, pending_tx.tries);
             }
         }
     }
 }
 
+// Simulates the execution of the transaction against the current state of the application. Returns
+// an error if the transaction would revert.
+fn check_simulation<Q: SyncQueryRunnerInterface>(
+    query_runner: &Q,
+    update_request: &UpdateRequest,
+) -> Result<(), TransactionConfirmationError> {
+    match query_runner.simulate_txn(update_request.clone().into()) {
+        TransactionResponse::Revert(error) => {
+            let method: &'static str = (&update_request.payload.method).into();
+            increment_counter!(
+                "signer_transaction_rejected",
+                Some("Counter for transactions rejected by the signer because the simulation reverted"),
+                "method" => method
+            );
+            Err(TransactionConfirmationError::Reverted(error))
+        },
+        _ => Ok(()),
+    }
+}
+
 async fn send_to_forwarder(
     mempool_socket: &MempoolSocket,
     update_request: &UpdateRequest,
@@ -358,43 +745,156 @@ impl<C: NodeComponents> AsyncWorker for SignerWorker<C> {
 
     async fn handle(&mut self, request: ExecuteTransaction) {
         let mut state = self.state.lock().await;
-        state.sign_new_tx(request).await;
+        if let Err(e) = state.sign_new_tx(request).await {
+            warn!("rejected transaction before submission: {e:?}");
+        }
+    }
+}
+
+impl<C: NodeComponents> AsyncWorker for SignerBatchWorker<C> {
+    type Request = Vec<UpdateMethod>;
+    type Response = Result<Vec<u64>, TransactionConfirmationError>;
+
+    async fn handle(&mut self, methods: Vec<UpdateMethod>) -> Self::Response {
+        let mut state = self.state.lock().await;
+        state.sign_new_batch(methods).await
+    }
+}
+
+impl<C: NodeComponents> AsyncWorker for SignerConfirmingWorker<C> {
+    type Request = UpdateMethod;
+    type Response = oneshot::Receiver<Result<TransactionReceipt, TransactionConfirmationError>>;
+
+    async fn handle(&mut self, method: UpdateMethod) -> Self::Response {
+        let (confirmation_tx, confirmation_rx) = oneshot::channel();
+        let mut state = self.state.lock().await;
+        match state.sign_new_tx(method.into()).await {
+            Ok(nonce) => {
+                state.pending_confirmations.insert(nonce, confirmation_tx);
+            },
+            Err(e) => {
+                if let Err(e) = confirmation_tx.send(Err(e)) {
+                    warn!("Failed to send transaction confirmation: {e:?}");
+                }
+            },
+        }
+        confirmation_rx
+    }
+}
+
+impl<C: NodeComponents> AsyncWorker for SignerPendingWorker<C> {
+    type Request = ();
+    type Response = Vec<(u64, UpdateMethod)>;
+
+    async fn handle(&mut self, _: ()) -> Self::Response {
+        let state = self.state.lock().await;
+        state
+            .pending_transactions
+            .iter()
+            .map(|tx| {
+                let payload = &tx.update_request.payload;
+                (payload.nonce, payload.method.clone())
+            })
+            .collect()
+    }
+}
+
+impl<C: NodeComponents> AsyncWorker for SignerAccountWorker<C> {
+    type Request = UpdateMethod;
+    type Response = Result<Option<u64>, TransactionConfirmationError>;
+
+    async fn handle(&mut self, method: UpdateMethod) -> Self::Response {
+        let mut state = self.state.lock().await;
+        state.sign_new_account_tx(method).await
     }
 }
 
+impl<C: NodeComponents> ConfigConsumer for Signer<C> {
+    const KEY: &'static str = "signer";
+
+    type Config = Config;
+}
+
 impl<C: NodeComponents> BuildGraph for Signer<C> {
     fn build_graph() -> fdi::DependencyGraph {
-        fdi::DependencyGraph::new().with_infallible(
-            Self::init.with_event_handler("start", Self::start.wrap_with_block_on()),
-        )
+        fdi::DependencyGraph::new()
+            .with(Self::init.with_event_handler("start", Self::start.wrap_with_block_on()))
     }
 }
 
-struct PendingTransaction {
+pub(crate) struct PendingTransaction {
     pub update_request: UpdateRequest,
-    pub timestamp: SystemTime,
+    pub next_resend: SystemTime,
     pub tries: u8,
     pub receipt_tx: Option<oneshot::Sender<TransactionReceipt>>,
 }
 
+// Returns when a transaction that has been sent `tries` times should be resent if it was not
+// ordered by then.
+pub(crate) fn next_resend(config: &Config, tries: u8) -> SystemTime {
+    let jitter = rand::thread_rng().gen_range(1.0 - RESEND_JITTER..=1.0 + RESEND_JITTER);
+    SystemTime::now() + config.resend_delay(tries).mul_f64(jitter)
+}
+
+// Forwards the new block notifications to the signer. If the signer is lagging behind and the
+// queue is full, the notification is dropped, since a queued notification will already trigger a
+// sync with the latest state of the application.
+async fn forward_new_blocks(
+    mut subscriber: impl Subscriber<BlockExecutedNotification>,
+    tx: mpsc::Sender<BlockExecutedNotification>,
+) {
+    while let Some(notification) = subscriber.recv().await {
+        match tx.try_send(notification) {
+            Ok(()) => {},
+            Err(TrySendError::Full(_)) => {
+                increment_counter!(
+                    "signer_notifier_lag",
+                    Some("Counter for new block notifications dropped because the signer is lagging behind")
+                );
+                debug!("signer is lagging behind, dropping new block notification");
+            },
+            Err(TrySendError::Closed(_)) => break,
+        }
+    }
+}
+
+// Waits for the next item on the channel and drains any other queued items. Returns the latest
+// item along with the number of items that were skipped.
+pub(crate) async fn recv_latest<T>(rx: &mut mpsc::Receiver<T>) -> Option<(T, usize)> {
+    let mut latest = rx.recv().await?;
+    let mut skipped = 0;
+    while let Ok(item) = rx.try_recv() {
+        latest = item;
+        skipped += 1;
+    }
+    Some((latest, skipped))
+}
+
 async fn new_block_task<C: NodeComponents>(
     mut node_index: LazyNodeIndex,
     worker: SignerWorker<C>,
-    mut subscriber: impl Subscriber<BlockExecutedNotification>,
+    mut rx: mpsc::Receiver<BlockExecutedNotification>,
     query_runner: c![C::ApplicationInterface::SyncExecutor],
 ) {
-    while let Some(_notification) = subscriber.last().await {
+    while let Some((_notification, skipped)) = recv_latest(&mut rx).await {
+        if skipped > 0 {
+            debug!("coalesced {skipped} new block notifications");
+        }
         let nonce = node_index.query_nonce(&query_runner);
         // TODO(qti3e): Get the lock only if we have to. Timeout should get sep from block.
         // Right now we are relying on the existence of new blocks to handle timeout.
         let mut guard = worker.state.lock().await;
         guard.sync_with_application(nonce).await;
+        if let Some(account_nonce) = guard.query_account_nonce() {
+            guard.sync_account_with_application(account_nonce).await;
+        }
     }
 }
 
-async fn respond_with_receipt(
+pub(crate) async fn respond_with_receipt(
     receipt_cache: Arc<Cache<[u8; 32], TransactionReceipt>>,
-    receipt_tx: oneshot::Sender<TransactionReceipt>,
+    receipt_tx: Option<oneshot::Sender<TransactionReceipt>>,
+    confirmation_tx: Option<ConfirmationSender>,
     txn: &UpdateRequest,
     timeout: Duration,
 ) {
@@ -403,13 +903,27 @@ async fn respond_with_receipt(
     loop {
         if now.elapsed() >= timeout {
             tracing::debug!("Timeout while waiting for transaction receipt");
+            if let Some(confirmation_tx) = confirmation_tx {
+                if let Err(e) =
+                    confirmation_tx.send(Err(TransactionConfirmationError::ReceiptTimeout))
+                {
+                    warn!("Failed to send transaction confirmation: {e:?}");
+                }
+            }
             break;
         }
         interval.tick().await;
 
         if let Some((_txn_hash, receipt)) = receipt_cache.remove(&txn.payload.to_digest()) {
-            if let Err(e) = receipt_tx.send(receipt) {
-                warn!("Failed to send transaction receipt: {e:?}");
+            if let Some(confirmation_tx) = confirmation_tx {
+                if let Err(e) = confirmation_tx.send(Ok(receipt.clone())) {
+                    warn!("Failed to send transaction confirmation: {e:?}");
+                }
+            }
+            if let Some(receipt_tx) = receipt_tx {
+                if let Err(e) = receipt_tx.send(receipt) {
+                    warn!("Failed to send transaction receipt: {e:?}");
+                }
             }
             break;
         }
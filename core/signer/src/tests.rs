@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use fleek_crypto::{AccountOwnerSecretKey, SecretKey};
 use lightning_application::app::Application;
@@ -11,6 +12,8 @@
     Genesis,
     GenesisNode,
     NodePorts,
+    Tokens,
+    TransactionConfirmationError,
     TransactionResponse,
     UpdateMethod,
 };
@@ -19,10 +22,13 @@
 use lightning_test_utils::consensus::{MockConsensus, MockConsensusConfig, MockForwarder};
 use lightning_test_utils::json_config::JsonConfigProvider;
 use lightning_test_utils::keys::EphemeralKeystore;
+use lightning_test_utils::transaction::get_update_transactions;
+use quick_cache::sync::Cache;
 use tempfile::{tempdir, TempDir};
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
 
-use crate::Signer;
+use crate::signer::{next_resend, recv_latest, respond_with_receipt};
+use crate::{Signer, SignerConfig};
 
 partial_node_components!(TestBinding {
     ConfigProviderInterface = JsonConfigProvider;
@@ -35,6 +41,21 @@
 });
 
 fn build_node(temp_dir: &TempDir, transactions_to_lose: &[u32]) -> Node<TestBinding> {
+    build_node_with_signer_config(
+        temp_dir,
+        transactions_to_lose,
+        SignerConfig {
+            resend_timeout: Duration::from_secs(3),
+            ..Default::default()
+        },
+    )
+}
+
+fn build_node_with_signer_config(
+    temp_dir: &TempDir,
+    transactions_to_lose: &[u32],
+    signer_config: SignerConfig,
+) -> Node<TestBinding> {
     let keystore = EphemeralKeystore::<TestBinding>::default();
     let (consensus_secret_key, node_secret_key) =
         (keystore.get_bls_sk(), keystore.get_ed25519_sk());
@@ -73,6 +94,7 @@ fn build_node(temp_dir: &TempDir, transactions_to_lose: &[u32]) -> Node<TestBind
         fdi::Provider::default().with(keystore).with(
             JsonConfigProvider::default()
                 .with::<Application<TestBinding>>(ApplicationConfig::test(genesis_path))
+                .with::<Signer<TestBinding>>(signer_config)
                 .with::<MockConsensus<TestBinding>>(MockConsensusConfig {
                     min_ordering_time: 0,
                     max_ordering_time: 1,
@@ -124,6 +146,33 @@ async fn test_get_txs_receipt() {
     assert_eq!(new_nonce, 1);
 }
 
+#[tokio::test]
+async fn test_get_txs_confirmation() {
+    let temp_dir = tempdir().unwrap();
+    let node = build_node(&temp_dir, &[]);
+    node.start().await;
+
+    let signer_socket = node
+        .provider
+        .get::<Signer<TestBinding>>()
+        .get_confirming_socket();
+
+    let confirmation_rx = signer_socket
+        .run(UpdateMethod::IncrementNonce {})
+        .await
+        .unwrap();
+
+    let receipt = confirmation_rx.await.unwrap().unwrap();
+
+    assert!(matches!(
+        receipt.response,
+        TransactionResponse::Success(ExecutionData::None)
+    ));
+
+    let new_nonce = get_our_nonce(&node);
+    assert_eq!(new_nonce, 1);
+}
+
 #[tokio::test]
 async fn test_send_two_txs_in_a_row() {
     let temp_dir = tempdir().unwrap();
@@ -149,6 +198,31 @@ async fn test_send_two_txs_in_a_row() {
     assert_eq!(new_nonce, 2);
 }
 
+#[tokio::test]
+async fn test_send_batch() {
+    let temp_dir = tempdir().unwrap();
+    let node = build_node(&temp_dir, &[]);
+    node.start().await;
+
+    let signer_socket = node
+        .provider
+        .get::<Signer<TestBinding>>()
+        .get_batch_socket();
+
+    let methods = vec![
+        UpdateMethod::IncrementNonce {},
+        UpdateMethod::IncrementNonce {},
+        UpdateMethod::IncrementNonce {},
+    ];
+    let nonces = signer_socket.run(methods).await.unwrap().unwrap();
+    assert_eq!(nonces, vec![1, 2, 3]);
+
+    // Each transaction will take at most 2 seconds to get ordered.
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let new_nonce = get_our_nonce(&node);
+    assert_eq!(new_nonce, 3);
+}
+
 #[tokio::test]
 async fn test_retry_send() {
     let temp_dir = tempdir().unwrap();
@@ -177,3 +251,450 @@ async fn test_retry_send() {
     let new_nonce = get_our_nonce(&node);
     assert_eq!(new_nonce, 3);
 }
+
+#[tokio::test]
+async fn test_reset_on_nonce_gap() {
+    let temp_dir = tempdir().unwrap();
+    // Lose both transactions so that they stay in the pending buffer.
+    let node = build_node(&temp_dir, &[1, 2]);
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    let confirming_socket = signer.get_confirming_socket();
+
+    let mut first_rx = confirming_socket.run(UpdateMethod::OptIn {}).await.unwrap();
+    let mut second_rx = confirming_socket.run(UpdateMethod::OptIn {}).await.unwrap();
+
+    let mut state = signer.worker.state.lock().await;
+    assert_eq!(state.pending_transactions.len(), 2);
+    assert_eq!(state.next_nonce, 3);
+
+    // Pretend that the application nonce jumped past everything in the buffer.
+    state.sync_with_application(5).await;
+
+    assert!(state.pending_transactions.is_empty());
+    assert_eq!(state.base_nonce, 5);
+    assert_eq!(state.next_nonce, 6);
+
+    // The nonces may have been used by other transactions, so ours are not confirmed.
+    assert_eq!(
+        first_rx.try_recv().unwrap(),
+        Err(TransactionConfirmationError::NonceConflict)
+    );
+    assert_eq!(
+        second_rx.try_recv().unwrap(),
+        Err(TransactionConfirmationError::NonceConflict)
+    );
+}
+
+#[tokio::test]
+async fn test_get_pending_transactions() {
+    let temp_dir = tempdir().unwrap();
+    // Lose both transactions so that they stay in the pending buffer.
+    let node = build_node(&temp_dir, &[1, 2]);
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    let signer_socket = signer.get_socket();
+    let pending_socket = signer.get_pending_socket();
+    assert!(pending_socket.run(()).await.unwrap().is_empty());
+
+    signer_socket
+        .run(UpdateMethod::IncrementNonce {}.into())
+        .await
+        .unwrap();
+    signer_socket
+        .run(UpdateMethod::OptIn {}.into())
+        .await
+        .unwrap();
+    assert_eq!(
+        pending_socket.run(()).await.unwrap(),
+        vec![
+            (1, UpdateMethod::IncrementNonce {}),
+            (2, UpdateMethod::OptIn {}),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_new_block_notifications_are_coalesced() {
+    let (tx, mut rx) = mpsc::channel(SignerConfig::default().new_block_buffer);
+    for block in 0..100u64 {
+        // Once the queue is full the notifications are dropped, like the signer's forwarder does.
+        let _ = tx.try_send(block);
+    }
+
+    // Only the latest queued notification is processed.
+    let (latest, skipped) = recv_latest(&mut rx).await.unwrap();
+    assert_eq!(latest, 15);
+    assert_eq!(skipped, 15);
+    assert!(rx.try_recv().is_err());
+
+    tx.send(100).await.unwrap();
+    assert_eq!(recv_latest(&mut rx).await.unwrap(), (100, 0));
+}
+
+#[tokio::test]
+async fn test_zero_new_block_buffer() {
+    let temp_dir = tempdir().unwrap();
+    let node = build_node_with_signer_config(
+        &temp_dir,
+        &[],
+        SignerConfig {
+            resend_timeout: Duration::from_secs(3),
+            new_block_buffer: 0,
+            ..Default::default()
+        },
+    );
+    node.start().await;
+
+    // The signer still syncs with the application on new blocks.
+    let confirming_socket = node
+        .provider
+        .get::<Signer<TestBinding>>()
+        .get_confirming_socket();
+    let confirmation_rx = confirming_socket
+        .run(UpdateMethod::IncrementNonce {})
+        .await
+        .unwrap();
+    confirmation_rx.await.unwrap().unwrap();
+    assert_eq!(get_our_nonce(&node), 1);
+}
+
+#[tokio::test]
+async fn test_sign_as_account_owner() {
+    let temp_dir = tempdir().unwrap();
+    let account_secret_key = AccountOwnerSecretKey::generate();
+    let account_key_path = temp_dir.path().join("account.pem");
+    std::fs::write(&account_key_path, account_secret_key.encode_pem()).unwrap();
+    let node = build_node_with_signer_config(
+        &temp_dir,
+        &[],
+        SignerConfig {
+            resend_timeout: Duration::from_secs(3),
+            account_key_path: Some(account_key_path.try_into().unwrap()),
+            ..Default::default()
+        },
+    );
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    let signer_socket = signer.get_socket();
+    let account_socket = signer.get_account_socket();
+
+    signer_socket
+        .run(UpdateMethod::IncrementNonce {}.into())
+        .await
+        .unwrap();
+    // The account has its own nonce sequence, independent from the one of the node.
+    assert_eq!(
+        account_socket
+            .run(UpdateMethod::IncrementNonce {})
+            .await
+            .unwrap(),
+        Ok(Some(1))
+    );
+    assert_eq!(
+        account_socket
+            .run(UpdateMethod::IncrementNonce {})
+            .await
+            .unwrap(),
+        Ok(Some(2))
+    );
+
+    // Each transaction will take at most 2 seconds to get ordered.
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(get_our_nonce(&node), 1);
+    // The account nonce is only incremented if the transactions were sent by the account owner.
+    let query_runner = node
+        .provider
+        .get::<<TestBinding as NodeComponents>::ApplicationInterface>()
+        .sync_query();
+    let account_address = account_secret_key.to_pk().into();
+    assert_eq!(
+        query_runner.get_account_info(&account_address, |a| a.nonce),
+        Some(2)
+    );
+}
+
+#[tokio::test]
+async fn test_account_socket_without_account_key() {
+    let temp_dir = tempdir().unwrap();
+    let node = build_node(&temp_dir, &[]);
+    node.start().await;
+
+    let account_socket = node
+        .provider
+        .get::<Signer<TestBinding>>()
+        .get_account_socket();
+    assert_eq!(
+        account_socket
+            .run(UpdateMethod::IncrementNonce {})
+            .await
+            .unwrap(),
+        Ok(None)
+    );
+}
+
+#[tokio::test]
+async fn test_simulate_before_submit_rejects_reverting_tx() {
+    let temp_dir = tempdir().unwrap();
+    let node = build_node_with_signer_config(
+        &temp_dir,
+        &[],
+        SignerConfig {
+            resend_timeout: Duration::from_secs(3),
+            simulate_before_submit: true,
+            ..Default::default()
+        },
+    );
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    let confirming_socket = signer.get_confirming_socket();
+
+    // Withdrawals can only be sent by account owners, so this reverts when sent by the node.
+    let confirmation_rx = confirming_socket
+        .run(UpdateMethod::Withdraw {
+            amount: 10_u64.into(),
+            token: Tokens::FLK,
+            receiving_address: AccountOwnerSecretKey::generate().to_pk().into(),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(
+        confirmation_rx.await.unwrap(),
+        Err(TransactionConfirmationError::Reverted(_))
+    ));
+
+    // The rejected transaction was not assigned a nonce.
+    assert_eq!(signer.worker.state.lock().await.next_nonce, 1);
+    assert!(signer
+        .get_pending_socket()
+        .run(())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let confirmation_rx = confirming_socket
+        .run(UpdateMethod::IncrementNonce {})
+        .await
+        .unwrap();
+    confirmation_rx.await.unwrap().unwrap();
+    assert_eq!(get_our_nonce(&node), 1);
+}
+
+#[tokio::test]
+async fn test_simulate_before_submit_rejects_reverting_batch() {
+    let temp_dir = tempdir().unwrap();
+    let node = build_node_with_signer_config(
+        &temp_dir,
+        &[],
+        SignerConfig {
+            resend_timeout: Duration::from_secs(3),
+            simulate_before_submit: true,
+            ..Default::default()
+        },
+    );
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    let batch_socket = signer.get_batch_socket();
+
+    // Withdrawals can only be sent by account owners, so this reverts when sent by the node.
+    let result = batch_socket
+        .run(vec![
+            UpdateMethod::IncrementNonce {},
+            UpdateMethod::Withdraw {
+                amount: 10_u64.into(),
+                token: Tokens::FLK,
+                receiving_address: AccountOwnerSecretKey::generate().to_pk().into(),
+            },
+        ])
+        .await
+        .unwrap();
+    assert!(matches!(
+        result,
+        Err(TransactionConfirmationError::Reverted(_))
+    ));
+
+    // None of the transactions in the rejected batch were assigned a nonce.
+    assert_eq!(signer.worker.state.lock().await.next_nonce, 1);
+    assert!(signer
+        .get_pending_socket()
+        .run(())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let nonces = batch_socket
+        .run(vec![
+            UpdateMethod::IncrementNonce {},
+            UpdateMethod::IncrementNonce {},
+        ])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(nonces, vec![1, 2]);
+}
+
+#[test]
+fn test_resend_backoff_grows_geometrically() {
+    let config = SignerConfig {
+        resend_timeout: Duration::from_secs(2),
+        resend_backoff_multiplier: 2.0,
+        max_resend_timeout: Duration::from_secs(20),
+        ..Default::default()
+    };
+    let delays: Vec<_> = (1..=5).map(|tries| config.resend_delay(tries)).collect();
+    assert_eq!(
+        delays,
+        [2, 4, 8, 16, 20].map(Duration::from_secs).to_vec(),
+        "the delay should double on every retry until it is capped"
+    );
+
+    // The jitter keeps every resend within 10% of the delay.
+    for tries in 1..=4 {
+        let before = SystemTime::now();
+        let resend_in = next_resend(&config, tries).duration_since(before).unwrap();
+        let delay = config.resend_delay(tries);
+        assert!(resend_in >= delay.mul_f64(0.9));
+        assert!(resend_in <= delay.mul_f64(1.1) + Duration::from_millis(100));
+    }
+}
+
+fn exhausted_increment_nonce_count() -> f64 {
+    prometheus::gather()
+        .iter()
+        .filter(|family| family.get_name() == "signer_transaction_exhausted")
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .any(|label| label.get_name() == "method" && label.get_value() == "IncrementNonce")
+        })
+        .map(|metric| metric.get_counter().get_value())
+        .sum()
+}
+
+#[tokio::test]
+async fn test_exhausted_transaction_is_counted_once() {
+    let temp_dir = tempdir().unwrap();
+    // Lose the transaction so that it stays in the pending buffer.
+    let node = build_node(&temp_dir, &[1]);
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    signer
+        .get_socket()
+        .run(UpdateMethod::IncrementNonce {}.into())
+        .await
+        .unwrap();
+
+    let mut state = signer.worker.state.lock().await;
+    let max_retries = SignerConfig::default().max_retries;
+    state.pending_transactions[0].tries = max_retries;
+    let exhausted_before = exhausted_increment_nonce_count();
+
+    // Keep the buffer due for a resend on every sync.
+    for _ in 0..max_retries {
+        state.pending_transactions[0].next_resend = SystemTime::now();
+        state.sync_with_application(0).await;
+    }
+
+    assert_eq!(exhausted_increment_nonce_count() - exhausted_before, 1.0);
+    // The replacement has its own retries.
+    assert_eq!(state.pending_transactions[0].tries, max_retries);
+}
+
+#[test]
+fn test_resend_delay_is_capped_on_overflow() {
+    let config = SignerConfig {
+        resend_backoff_multiplier: f64::MAX,
+        ..Default::default()
+    };
+    assert_eq!(config.resend_delay(3), config.max_resend_timeout);
+    assert_eq!(config.resend_delay(u8::MAX), config.max_resend_timeout);
+}
+
+#[test]
+fn test_validate_rejects_invalid_backoff() {
+    assert!(SignerConfig::default().validate().is_ok());
+    for multiplier in [f64::NAN, f64::INFINITY, -2.0, 0.5] {
+        let config = SignerConfig {
+            resend_backoff_multiplier: multiplier,
+            ..Default::default()
+        };
+        assert!(
+            config.validate().is_err(),
+            "{multiplier} should be rejected"
+        );
+    }
+
+    let config = SignerConfig {
+        resend_timeout: Duration::from_secs(60),
+        max_resend_timeout: Duration::from_secs(30),
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_resend_uses_backoff() {
+    let temp_dir = tempdir().unwrap();
+    // Lose the transaction so that it stays in the pending buffer.
+    let config = SignerConfig {
+        resend_timeout: Duration::from_secs(3),
+        resend_backoff_multiplier: 3.0,
+        max_resend_timeout: Duration::from_secs(60),
+        ..Default::default()
+    };
+    let node = build_node_with_signer_config(&temp_dir, &[1], config.clone());
+    node.start().await;
+
+    let signer = node.provider.get::<Signer<TestBinding>>();
+    signer
+        .get_socket()
+        .run(UpdateMethod::IncrementNonce {}.into())
+        .await
+        .unwrap();
+
+    let mut state = signer.worker.state.lock().await;
+    assert_eq!(state.pending_transactions[0].tries, 1);
+
+    // Make the transaction due for a resend.
+    state.pending_transactions[0].next_resend = SystemTime::now();
+    let before = SystemTime::now();
+    state.sync_with_application(0).await;
+
+    // The second resend waits for the grown delay, within the jitter.
+    let tx = &state.pending_transactions[0];
+    assert_eq!(tx.tries, 2);
+    let resend_in = tx.next_resend.duration_since(before).unwrap();
+    let delay = config.resend_delay(2);
+    assert_eq!(delay, Duration::from_secs(9));
+    assert!(resend_in >= delay.mul_f64(0.9));
+    assert!(resend_in <= delay.mul_f64(1.1) + Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_receipt_timeout_is_reported() {
+    let txn = get_update_transactions(1).pop().unwrap();
+    let (confirmation_tx, confirmation_rx) = oneshot::channel();
+
+    // The receipt never shows up in the cache.
+    respond_with_receipt(
+        Arc::new(Cache::new(1)),
+        None,
+        Some(confirmation_tx),
+        &txn,
+        Duration::from_millis(100),
+    )
+    .await;
+
+    assert_eq!(
+        confirmation_rx.await.unwrap(),
+        Err(TransactionConfirmationError::ReceiptTimeout)
+    );
+}
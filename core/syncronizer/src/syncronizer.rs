@@ -6,7 +6,6 @@
 use lightning_interfaces::types::{
     Blake3Hash,
     Epoch,
-    EpochInfo,
     NodeIndex,
     NodeInfo,
     Participation,
@@ -194,14 +193,10 @@ async fn run(&mut self, tx_update_ready: async_channel::Sender<Blake3Hash>) {
         let mut epoch_changed_sub = self.notifier.subscribe_epoch_changed();
 
         loop {
-            let EpochInfo { epoch_end, .. } = self.query_runner.get_epoch_info();
-
-            let now = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis();
-            let until_epoch_ends: u64 = (epoch_end as u128).saturating_sub(now).try_into().unwrap();
-            let time_until_epoch_change = Duration::from_millis(until_epoch_ends);
+            let time_until_epoch_change = self
+                .query_runner
+                .time_until_epoch_end(SystemTime::now())
+                .unwrap_or_default();
 
             let time_to_check =
                 tokio::time::sleep(time_until_epoch_change + self.epoch_change_delta);
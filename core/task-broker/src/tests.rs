@@ -153,6 +153,7 @@ fn build_cluster(
                                     max_idle_timeout: Duration::from_millis(100),
                                     address: ([127, 0, 0, 1], port_start + i as u16).into(),
                                     http: None,
+                                    ..Default::default()
                                 })
                                 .with::<TaskBroker<TestBinding>>(TaskBrokerConfig {
                                     connect_timeout: Duration::from_secs(5),
@@ -13,14 +13,15 @@
 use affair::AsyncWorkerUnordered;
 use fdi::Cloned;
 use lightning_interfaces::prelude::*;
-use lightning_interfaces::spawn_worker;
 use lightning_interfaces::types::{Block, TransactionRequest};
+use lightning_interfaces::{spawn_worker, ExecutionProgress};
+use lightning_utils::application::QueryRunnerExt;
 use rand::{thread_rng, Rng, SeedableRng};
 use rand_chacha::ChaCha12Rng;
 use rand_distr::{Bernoulli, Distribution};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::{broadcast, mpsc, watch};
 use tokio::task::JoinSet;
 use tokio::time::{sleep, Interval};
 use types::ForwarderError;
@@ -167,6 +168,7 @@ fn mempool_socket(&self) -> MempoolSocket {
 pub struct MockConsensus<C: NodeComponents> {
     group: broadcast::Receiver<Block>,
     execution_socket: ExecutionEngineSocket,
+    query_runner: c!(C::ApplicationInterface::SyncExecutor),
     notifier: c![C::NotifierInterface::Emitter],
 }
 
@@ -180,6 +182,7 @@ pub fn new(
         Self {
             group: group.block_producer_rx.take().unwrap(),
             execution_socket: app.transaction_executor(),
+            query_runner: app.sync_query(),
             notifier,
         }
     }
@@ -187,6 +190,7 @@ pub fn new(
     async fn start(mut this: fdi::Consume<Self>, Cloned(waiter): Cloned<ShutdownWaiter>) {
         waiter
             .run_until_shutdown(async move {
+                let mut committee = this.query_runner.get_committee_members_by_index();
                 loop {
                     match this.group.recv().await {
                         Ok(block) => {
@@ -197,6 +201,13 @@ async fn start(mut this: fdi::Consume<Self>, Cloned(waiter): Cloned<ShutdownWait
                                 .map_err(|r| anyhow::anyhow!(format!("{r:?}")))
                                 .unwrap();
                             this.notifier.new_block(block, response);
+
+                            let new_committee = this.query_runner.get_committee_members_by_index();
+                            this.notifier.committee_changed(
+                                this.query_runner.get_current_epoch(),
+                                std::mem::replace(&mut committee, new_committee.clone()),
+                                new_committee,
+                            );
                         },
                         Err(RecvError::Closed) => break,
                         Err(RecvError::Lagged(_)) => continue,
@@ -212,6 +223,11 @@ impl<C: NodeComponents> ConsensusInterface<C> for MockConsensus<C> {
     type ReadyState = ();
 
     async fn wait_for_ready(&self) -> Self::ReadyState {}
+
+    fn execution_progress(&self) -> watch::Receiver<ExecutionProgress> {
+        // Blocks are executed as soon as they are produced, so there is never a parcel pending.
+        watch::channel(ExecutionProgress::default()).1
+    }
 }
 
 impl<C: NodeComponents> BuildGraph for MockConsensus<C> {
@@ -2,12 +2,20 @@
 use std::time::Duration;
 
 use anyhow::Result;
+use fleek_crypto::AccountOwnerSecretKey;
 use futures::future::join_all;
+use hp_fixed::unsigned::HpUfixed;
 use lightning_interfaces::prelude::*;
 use lightning_interfaces::types::CommitteeSelectionBeaconRound;
 use lightning_utils::application::QueryRunnerExt;
 use lightning_utils::poll::{poll_until, PollUntilError};
-use types::{Epoch, UpdateMethod};
+use types::{
+    CommitteeSelectionBeaconPhase,
+    Epoch,
+    ExecuteTransactionError,
+    NodeIndex,
+    UpdateMethod,
+};
 
 use super::{BoxedTestNode, TestNetwork};
 
@@ -58,6 +66,44 @@ pub async fn change_epoch(&self) -> Result<Epoch> {
         Ok(epoch + 1)
     }
 
+    /// Wait for the current committee selection beacon phase to time out, and send the matching
+    /// timeout transaction from all nodes. Returns the phase that was timed out.
+    ///
+    /// The application doesn't track when a phase ends, so like the nodes themselves, this waits
+    /// for the phase duration from the protocol params after all nodes observed the phase.
+    pub async fn advance_beacon_phase(&self) -> Result<CommitteeSelectionBeaconPhase> {
+        let phase = poll_until(
+            || async {
+                let mut phases = self
+                    .nodes()
+                    .map(|node| node.app_query().get_committee_selection_beacon_phase());
+                match phases.next().flatten() {
+                    Some(phase) if phases.all(|other| other.as_ref() == Some(&phase)) => Ok(phase),
+                    _ => Err(PollUntilError::ConditionNotSatisfied),
+                }
+            },
+            Duration::from_secs(20),
+            Duration::from_millis(100),
+        )
+        .await?;
+
+        let query = self.node(0).app_query();
+        match phase {
+            CommitteeSelectionBeaconPhase::Commit((_, round)) => {
+                let duration = query.get_committee_beacon_commit_phase_duration();
+                tokio::time::sleep(Duration::from_millis(duration)).await;
+                self.commit_phase_timeout(round).await?;
+            },
+            CommitteeSelectionBeaconPhase::Reveal((_, round)) => {
+                let duration = query.get_committee_beacon_reveal_phase_duration();
+                tokio::time::sleep(Duration::from_millis(duration)).await;
+                self.reveal_phase_timeout(round).await?;
+            },
+        }
+
+        Ok(phase)
+    }
+
     pub async fn commit_phase_timeout(
         &self,
         round: CommitteeSelectionBeaconRound,
@@ -92,6 +138,28 @@ pub async fn reveal_phase_timeout(
         Ok(epoch)
     }
 
+    /// Deposit FLK tokens from the given account and stake them on the node with the given index.
+    ///
+    /// This waits for both the deposit and the stake transactions to be executed.
+    pub async fn deposit_and_stake(
+        &self,
+        node: NodeIndex,
+        amount: HpUfixed<18>,
+        owner: &AccountOwnerSecretKey,
+    ) -> Result<(), ExecuteTransactionError> {
+        self.node(node).deposit_and_stake(amount, owner).await
+    }
+
+    /// Lock the stake of the node with the given index for the given number of epochs.
+    pub async fn stake_lock(
+        &self,
+        node: NodeIndex,
+        locked_for: u64,
+        owner: &AccountOwnerSecretKey,
+    ) -> Result<(), ExecuteTransactionError> {
+        self.node(node).stake_lock(locked_for, owner).await
+    }
+
     /// Wait for the epoch to match the given epoch across all nodes.
     ///
     /// Returns an error if the epoch does not match the given epoch within 20 seconds.
@@ -7,6 +7,7 @@
 use lightning_committee_beacon::CommitteeBeaconConfig;
 use lightning_interfaces::types::{Genesis, Staking};
 use lightning_interfaces::{ApplicationInterface, NodeComponents};
+use lightning_notifier::Notifier;
 use lightning_utils::poll::{poll_until, PollUntilError};
 use tempfile::tempdir;
 
@@ -21,6 +22,9 @@
 };
 use crate::consensus::{MockConsensusConfig, MockConsensusGroup};
 
+/// Returns the number of peers the node at the given position is expected to be connected to.
+pub type ExpectedPeers = Arc<dyn Fn(usize) -> usize>;
+
 pub struct TestNetworkBuilder {
     pub nodes: Vec<BoxedTestNode>,
     pub generated_committee_nodes: u8,
@@ -30,6 +34,7 @@ pub struct TestNetworkBuilder {
     pub mock_consensus_group: Option<MockConsensusGroup>,
     pub committee_beacon_config: Option<CommitteeBeaconConfig>,
     pub ping_interval: Option<Duration>,
+    pub expected_peers: Option<ExpectedPeers>,
 }
 
 impl TestNetworkBuilder {
@@ -43,6 +48,7 @@ pub fn new() -> Self {
             mock_consensus_group: None,
             committee_beacon_config: None,
             ping_interval: None,
+            expected_peers: None,
         }
         .with_mock_consensus(MockConsensusConfig {
             max_ordering_time: 1,
@@ -63,6 +69,7 @@ pub fn with_node(mut self, node: BoxedTestNode) -> Self {
     pub async fn with_committee_nodes<C: NodeComponents>(mut self, num_nodes: usize) -> Self
     where
         C::ApplicationInterface: ApplicationInterface<C, SyncExecutor = QueryRunner>,
+        C: NodeComponents<NotifierInterface = Notifier<C>>,
     {
         for _ in 0..num_nodes {
             let mut builder = TestNodeBuilder::new().with_is_genesis_committee(true);
@@ -92,6 +99,7 @@ pub async fn with_committee_nodes<C: NodeComponents>(mut self, num_nodes: usize)
     pub async fn with_non_committee_nodes<C: NodeComponents>(mut self, num_nodes: usize) -> Self
     where
         C::ApplicationInterface: ApplicationInterface<C, SyncExecutor = QueryRunner>,
+        C: NodeComponents<NotifierInterface = Notifier<C>>,
     {
         for _ in 0..num_nodes {
             let mut builder = TestNodeBuilder::new().with_is_genesis_committee(false);
@@ -136,6 +144,16 @@ pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
         self
     }
 
+    /// Sets the number of peers each node is expected to be connected to before the network is
+    /// considered ready. Defaults to a full mesh.
+    pub fn with_expected_peers<F>(mut self, expected_peers: F) -> Self
+    where
+        F: Fn(usize) -> usize + 'static,
+    {
+        self.expected_peers = Some(Arc::new(expected_peers));
+        self
+    }
+
     /// Sets up a mock consensus group with the given config.
     ///
     /// This will overwrite any existing mock consensus group, and will not configure existing nodes
@@ -210,7 +228,12 @@ pub async fn build(mut self) -> Result<TestNetwork> {
         .await;
 
         // Wait for the pool to establish all of the node connections.
-        self.wait_for_connected_peers(&self.nodes).await?;
+        if let Some(expected_peers) = self.expected_peers.clone() {
+            self.wait_for_connected_peers_with(&self.nodes, expected_peers.as_ref())
+                .await?;
+        } else {
+            self.wait_for_connected_peers(&self.nodes).await?;
+        }
 
         // Notify the shared mock consensus group that it can start.
         if let Some(consensus_group) = &self.mock_consensus_group {
@@ -221,10 +244,28 @@ pub async fn build(mut self) -> Result<TestNetwork> {
         Ok(network)
     }
 
+    /// Wait for every node to be connected to every other node (full mesh).
     pub async fn wait_for_connected_peers(
         &self,
         nodes: &[BoxedTestNode],
     ) -> Result<(), PollUntilError> {
+        self.wait_for_connected_peers_with(nodes, |_| nodes.len() - 1)
+            .await
+    }
+
+    /// Wait for every node to be connected to the expected number of peers.
+    ///
+    /// The `expected_peers` function is given the position of a node in `nodes` and returns the
+    /// number of peers that node should be connected to. This is useful for networks that are
+    /// intentionally partitioned or restricted by topology.
+    pub async fn wait_for_connected_peers_with<F>(
+        &self,
+        nodes: &[BoxedTestNode],
+        expected_peers: F,
+    ) -> Result<(), PollUntilError>
+    where
+        F: Fn(usize) -> usize,
+    {
         poll_until(
             || async {
                 let peers_by_node = join_all(nodes.iter().map(|node| node.pool_connected_peers()))
@@ -235,7 +276,8 @@ pub async fn wait_for_connected_peers(
 
                 peers_by_node
                     .iter()
-                    .all(|peers| peers.len() == nodes.len() - 1)
+                    .enumerate()
+                    .all(|(i, peers)| peers.len() == expected_peers(i))
                     .then_some(())
                     .ok_or(PollUntilError::ConditionNotSatisfied)
             },
@@ -15,6 +15,7 @@
     NodeSecretKey,
     SecretKey,
 };
+use hp_fixed::unsigned::HpUfixed;
 use lightning_application::state::QueryRunner;
 use lightning_application::Application;
 use lightning_checkpointer::Checkpointer;
@@ -82,6 +83,16 @@ async fn execute_transaction_from_node_with_receipt(
         method: UpdateMethod,
         timeout: Duration,
     ) -> Result<TransactionReceipt, ExecuteTransactionError>;
+    async fn deposit_and_stake(
+        &self,
+        amount: HpUfixed<18>,
+        account: &AccountOwnerSecretKey,
+    ) -> Result<(), ExecuteTransactionError>;
+    async fn stake_lock(
+        &self,
+        locked_for: u64,
+        account: &AccountOwnerSecretKey,
+    ) -> Result<(), ExecuteTransactionError>;
 }
 
 pub type BoxedTestNode = Box<dyn TestNetworkNode>;
@@ -97,7 +108,11 @@ pub struct TestFullNode<C: NodeComponents> {
 }
 
 #[async_trait::async_trait]
-impl<C: NodeComponents> TestNetworkNode for TestFullNode<C> {
+impl<C: NodeComponents> TestNetworkNode for TestFullNode<C>
+where
+    C::ApplicationInterface: ApplicationInterface<C, SyncExecutor = QueryRunner>,
+    C: NodeComponents<NotifierInterface = Notifier<C>>,
+{
     async fn start(&mut self) -> Result<()> {
         // Start the node.
         tokio::time::timeout(Duration::from_secs(30), self.inner.spawn()).await???;
@@ -271,6 +286,22 @@ async fn execute_transaction_from_node_with_receipt(
             .map_err(|_| ExecuteTransactionError::Timeout((method, None, 1)))?
             .map_err(|e| ExecuteTransactionError::Other(format!("{e:?}")))
     }
+
+    async fn deposit_and_stake(
+        &self,
+        amount: HpUfixed<18>,
+        account: &AccountOwnerSecretKey,
+    ) -> Result<(), ExecuteTransactionError> {
+        TestFullNode::deposit_and_stake(self, amount, account).await
+    }
+
+    async fn stake_lock(
+        &self,
+        locked_for: u64,
+        account: &AccountOwnerSecretKey,
+    ) -> Result<(), ExecuteTransactionError> {
+        TestFullNode::stake_lock(self, locked_for, account).await
+    }
 }
 
 impl<C: NodeComponents> TestFullNode<C> {
@@ -16,6 +16,7 @@
 use lightning_consensus::{Consensus, ConsensusConfig};
 use lightning_interfaces::prelude::*;
 use lightning_node::ContainedNode;
+use lightning_notifier::Notifier;
 use lightning_pinger::{Config as PingerConfig, Pinger};
 use lightning_pool::{Config as PoolConfig, PoolProvider};
 use lightning_rpc::config::Config as RpcConfig;
@@ -92,6 +93,7 @@ pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
     pub async fn build<C: NodeComponents>(self, node_name: Option<String>) -> Result<BoxedTestNode>
     where
         C::ApplicationInterface: ApplicationInterface<C, SyncExecutor = QueryRunner>,
+        C: NodeComponents<NotifierInterface = Notifier<C>>,
     {
         let config = TomlConfigProvider::<C>::new();
 
@@ -132,6 +134,7 @@ pub async fn build<C: NodeComponents>(self, node_name: Option<String>) -> Result
         } else {
             config.inject::<Consensus<C>>(ConsensusConfig {
                 store_path: self.home_dir.join("consensus").try_into().unwrap(),
+                ..Default::default()
             });
         }
 
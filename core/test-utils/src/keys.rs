@@ -8,6 +8,7 @@
     SecretKey,
 };
 use lightning_interfaces::prelude::*;
+use lightning_interfaces::RotatedKeys;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
@@ -61,4 +62,8 @@ fn get_bls_sk(&self) -> ConsensusSecretKey {
     fn generate_keys(_provider: Self::Config, _accept_partial: bool) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn rotate_keys(_config: Self::Config) -> anyhow::Result<RotatedKeys> {
+        anyhow::bail!("Ephemeral keys cannot be rotated")
+    }
 }
@@ -23,7 +23,7 @@
 };
 
 #[serde_as]
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Genesis {
     pub chain_id: u32,
     pub epoch_start: u64,
@@ -66,6 +66,49 @@ pub struct Genesis {
     pub total_intervals: u64,
 }
 
+impl Default for Genesis {
+    /// An empty genesis that still passes [`Genesis::validate`], so it can be used as a base that
+    /// is written to and loaded from a file.
+    fn default() -> Self {
+        Self {
+            chain_id: 0,
+            epoch_start: 0,
+            epoch_time: 120000,
+            epochs_per_year: 0,
+            committee_size: 0,
+            node_count: 0,
+            min_stake: 0,
+            eligibility_time: 0,
+            lock_time: 0,
+            max_inflation: 0,
+            protocol_share: 0,
+            node_share: 80,
+            service_builder_share: 20,
+            consumer_rebate: 0,
+            max_boost: 0,
+            max_lock_time: 0,
+            min_num_measurements: 0,
+            node_info: Vec::new(),
+            service: Vec::new(),
+            account: Vec::new(),
+            client: HashMap::new(),
+            commodity_prices: Vec::new(),
+            supply_at_genesis: 0,
+            protocol_fund_address: EthAddress::default(),
+            governance_address: EthAddress::default(),
+            total_served: HashMap::new(),
+            latencies: None,
+            reputation_ping_timeout: Duration::default(),
+            topology_target_k: 0,
+            topology_min_nodes: 0,
+            committee_selection_beacon_commit_phase_duration: 10,
+            committee_selection_beacon_reveal_phase_duration: 10,
+            committee_selection_beacon_non_reveal_slash_amount: 0,
+            total_intervals: 0,
+        }
+    }
+}
+
 impl Genesis {
     pub fn load_from_file(path: ResolvedPathBuf) -> Result<Self> {
         let raw = fs::read_to_string(path)?;
@@ -77,10 +120,44 @@ pub fn load_from_file(path: ResolvedPathBuf) -> Result<Self> {
         {
             anyhow::bail!("the beacon commit and reveal phases should not be longer than 2/3 of the epoch duration");
         }
+        genesis.validate().context("Invalid genesis file")?;
 
         Ok(genesis)
     }
 
+    /// Checks that the genesis committee members have at least `min_stake` staked, that the
+    /// reward shares add up to 100, and that the committee selection beacon phases are not empty.
+    pub fn validate(&self) -> Result<()> {
+        let shares =
+            self.node_share as u32 + self.protocol_share as u32 + self.service_builder_share as u32;
+        if shares != 100 {
+            anyhow::bail!(
+                "the node, protocol and service builder shares add up to {shares} instead of 100"
+            );
+        }
+
+        if self.committee_selection_beacon_commit_phase_duration == 0 {
+            anyhow::bail!("the committee selection beacon commit phase duration must be positive");
+        }
+        if self.committee_selection_beacon_reveal_phase_duration == 0 {
+            anyhow::bail!("the committee selection beacon reveal phase duration must be positive");
+        }
+
+        let min_stake = HpUfixed::<18>::from(self.min_stake);
+        for node in self.node_info.iter().filter(|node| node.genesis_committee) {
+            if node.stake.staked < min_stake {
+                anyhow::bail!(
+                    "genesis committee member {} has {} staked, which is less than the minimum stake of {}",
+                    node.primary_public_key,
+                    node.stake.staked,
+                    self.min_stake
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn write_to_file(&self, path: ResolvedPathBuf) -> Result<()> {
         let raw = toml::to_string_pretty(self)?;
         fs::write(path, raw)?;
@@ -283,4 +360,79 @@ fn write_to_dir_load_from_file() {
         let loaded_genesis = Genesis::load_from_file(genesis_path).unwrap();
         assert_eq!(genesis, loaded_genesis);
     }
+
+    fn valid_genesis() -> Genesis {
+        let node = GenesisNode::new(
+            [0; 20].into(),
+            [1; 32].into(),
+            [127, 0, 0, 1].into(),
+            [0; 96].into(),
+            [127, 0, 0, 1].into(),
+            [1; 32].into(),
+            NodePorts::default(),
+            Some(Staking {
+                staked: 1000u32.into(),
+                ..Default::default()
+            }),
+            true,
+        );
+        Genesis {
+            min_stake: 1000,
+            node_info: vec![node],
+            ..Genesis::default()
+        }
+    }
+
+    #[test]
+    fn validate_accepts_consistent_genesis() {
+        Genesis::default().validate().unwrap();
+        valid_genesis().validate().unwrap();
+    }
+
+    #[test]
+    fn load_from_file_rejects_invalid_genesis() {
+        let temp_dir = tempdir().unwrap();
+        let genesis = Genesis {
+            protocol_share: 10,
+            ..Genesis::default()
+        };
+        let genesis_path = genesis
+            .write_to_dir(temp_dir.path().to_path_buf().try_into().unwrap())
+            .unwrap();
+        let error = Genesis::load_from_file(genesis_path).unwrap_err();
+        assert!(format!("{error:#}").contains("add up to 110"), "{error:#}");
+    }
+
+    #[test]
+    fn validate_rejects_understaked_committee_member() {
+        let mut genesis = valid_genesis();
+        genesis.node_info[0].stake.staked = 999u32.into();
+        let error = genesis.validate().unwrap_err().to_string();
+        assert!(error.contains("less than the minimum stake"), "{error}");
+
+        // Nodes that are not on the genesis committee are not required to be staked.
+        genesis.node_info[0].genesis_committee = false;
+        genesis.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_shares_not_adding_up_to_100() {
+        let mut genesis = valid_genesis();
+        genesis.protocol_share = 10;
+        let error = genesis.validate().unwrap_err().to_string();
+        assert!(error.contains("add up to 110"), "{error}");
+    }
+
+    #[test]
+    fn validate_rejects_empty_beacon_phases() {
+        let mut genesis = valid_genesis();
+        genesis.committee_selection_beacon_commit_phase_duration = 0;
+        let error = genesis.validate().unwrap_err().to_string();
+        assert!(error.contains("commit phase duration"), "{error}");
+
+        let mut genesis = valid_genesis();
+        genesis.committee_selection_beacon_reveal_phase_duration = 0;
+        let error = genesis.validate().unwrap_err().to_string();
+        assert!(error.contains("reveal phase duration"), "{error}");
+    }
 }
@@ -22,7 +22,9 @@ pub struct EpochInfo {
     pub epoch_transition: u64,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 pub enum TransactionResponse {
     Success(ExecutionData),
     Revert(ExecutionError),
@@ -103,7 +105,9 @@ fn from(value: TransactionReceipt) -> Self {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 pub enum ExecutionData {
     None,
     String(String),
@@ -113,7 +117,9 @@ pub enum ExecutionData {
 }
 
 /// Error type for transaction execution on the application layer
-#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 pub enum ExecutionError {
     CommitteeSelectionBeaconNodeNotActive,
     CommitteeSelectionBeaconNotCommitted,
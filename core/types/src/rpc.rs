@@ -1,8 +1,21 @@
 use fleek_crypto::{ConsensusPublicKey, NodePublicKey};
 use serde::{Deserialize, Serialize};
 
+use crate::{CommitteeSelectionBeaconPhase, Epoch, NodeIndex};
+
 #[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug, schemars::JsonSchema)]
 pub struct PublicKeys {
     pub node_public_key: NodePublicKey,
     pub consensus_public_key: ConsensusPublicKey,
 }
+
+/// A summary of the current committee and its progress towards the next epoch.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug, schemars::JsonSchema)]
+pub struct CommitteeInfo {
+    pub epoch: Epoch,
+    pub members: Vec<NodeIndex>,
+    /// The members that already signalled they are ready to change the epoch.
+    pub ready_to_change: Vec<NodeIndex>,
+    /// The current phase of the committee selection beacon, if any.
+    pub beacon_phase: Option<CommitteeSelectionBeaconPhase>,
+}
@@ -1,6 +1,7 @@
+use thiserror::Error;
 use tokio::sync::oneshot;
 
-use crate::{TransactionReceipt, UpdateMethod};
+use crate::{ExecutionError, TransactionReceipt, UpdateMethod};
 
 #[derive(Debug)]
 pub struct ExecuteTransaction {
@@ -16,3 +17,25 @@ fn from(value: UpdateMethod) -> Self {
         }
     }
 }
+
+/// The reason a transaction submitted through the confirming signer socket was not confirmed.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+pub enum TransactionConfirmationError {
+    /// The transaction reverted when it was simulated. Before submission, the signer rejects it
+    /// without assigning a nonce. Before a resend, the signer replaces it with a nonce increment.
+    #[error("Transaction reverted: {0:?}")]
+    Reverted(ExecutionError),
+
+    /// The transaction was not ordered after the maximum number of retries.
+    #[error("Transaction was not ordered after {0} tries")]
+    MaxRetriesExceeded(u8),
+
+    /// The application nonce moved past the transaction's nonce because of transactions the
+    /// signer did not submit, so it can't tell whether the transaction was ordered.
+    #[error("Transaction nonce was used by a transaction submitted elsewhere")]
+    NonceConflict,
+
+    /// The transaction was ordered, but its receipt did not show up in time.
+    #[error("Timed out waiting for the transaction receipt")]
+    ReceiptTimeout,
+}
@@ -336,7 +336,17 @@ pub struct UpdatePayload {
 }
 
 /// All of the update functions in our logic, along their parameters.
-#[derive(Debug, Hash, Clone, Serialize, Deserialize, Eq, PartialEq, schemars::JsonSchema)]
+#[derive(
+    Debug,
+    Hash,
+    Clone,
+    Serialize,
+    Deserialize,
+    Eq,
+    PartialEq,
+    schemars::JsonSchema,
+    strum::IntoStaticStr,
+)]
 pub enum UpdateMethod {
     /// The main function of the application layer. After aggregating ProofOfAcknowledgements a
     /// node will submit this transaction to get paid.
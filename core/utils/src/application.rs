@@ -1,5 +1,5 @@
 use std::collections::{HashMap, HashSet};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use fleek_crypto::{EthAddress, NodePublicKey};
 use hp_fixed::unsigned::HpUfixed;
@@ -79,6 +79,23 @@ fn get_epoch_info(&self) -> EpochInfo {
         }
     }
 
+    /// Returns the time left until the end of the current epoch, or `None` if the end of the
+    /// epoch is unknown or has already passed.
+    fn time_until_epoch_end(&self, now: SystemTime) -> Option<Duration> {
+        let epoch = self.get_current_epoch();
+        let epoch_end = self.get_committee_info(&epoch, |c| c.epoch_end_timestamp)?;
+        let now = now.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+        Duration::from_millis(epoch_end)
+            .checked_sub(now)
+            .filter(|delta| !delta.is_zero())
+    }
+
+    /// Returns true if the node is a member of the committee of the current epoch.
+    fn is_in_committee(&self, node: &NodePublicKey) -> bool {
+        self.pubkey_to_index(node)
+            .is_some_and(|index| self.get_committee_members_by_index().contains(&index))
+    }
+
     /// Return all latencies measurements for the current epoch.
     fn get_current_latencies(&self) -> HashMap<(NodePublicKey, NodePublicKey), Duration> {
         self.get_latencies_iter::<HashMap<(NodePublicKey, NodePublicKey), Duration>>(
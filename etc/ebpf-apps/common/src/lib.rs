@@ -2,6 +2,10 @@
 
 pub const MAX_DEVICES: usize = 2;
 pub const MAX_FILE_RULES: usize = 20;
+/// Maximum number of chunks a single profile can be split into.
+///
+/// Each chunk holds up to `MAX_FILE_RULES` rules.
+pub const MAX_PROFILE_CHUNKS: u64 = 4;
 pub const EVENT_HEADER_SIZE: usize = 2;
 pub const MAX_BUFFER_LEN: usize = 1024;
 pub const FILE_OPEN_PROG_ID: u8 = 0;
@@ -30,6 +34,23 @@ pub struct PacketFilter {
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for PacketFilter {}
 
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct PacketFilter6 {
+    /// Source IPv6 address.
+    pub ip: [u8; 16],
+    /// Source port.
+    pub port: u16,
+    /// Transport protocol.
+    ///
+    /// Uses values from Ipv6 header.
+    /// Use `u16::MAX` to indicate `any`.
+    pub proto: u16,
+}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for PacketFilter6 {}
+
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
 #[repr(C)]
 pub struct PacketFilterParams {
@@ -48,11 +69,36 @@ pub struct PacketFilterParams {
     /// XDP_TX       = 3;
     /// XDP_REDIRECT = 4;
     pub action: u32,
+    /// Time after which the filter is ignored, in seconds of the
+    /// monotonic clock (`CLOCK_MONOTONIC`).
+    ///
+    /// Use `0` to indicate that the filter never expires.
+    pub expires_at: u32,
+}
+
+impl PacketFilterParams {
+    /// Returns true if the filter has expired at the given time.
+    pub fn is_expired(&self, now: u32) -> bool {
+        self.expires_at != 0 && self.expires_at <= now
+    }
 }
 
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for PacketFilterParams {}
 
+/// Settings of the packet filter that apply to every packet.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct PacketFilterConfig {
+    /// Action to take on packets that match no filter.
+    ///
+    /// Uses the same values as [`PacketFilterParams::action`].
+    pub default_action: u32,
+}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for PacketFilterConfig {}
+
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
 #[repr(C)]
 pub struct SubnetFilterParams {
@@ -67,10 +113,19 @@ pub struct SubnetFilterParams {
     pub extra: PacketFilterParams,
 }
 
+impl SubnetFilterParams {
+    /// Returns true if the filter applies to packets with the given port and protocol.
+    ///
+    /// A port of `0` matches any port and a protocol of `u16::MAX` matches any protocol.
+    pub fn matches(&self, port: u16, proto: u16) -> bool {
+        (self.port == 0 || self.port == port) && (self.proto == u16::MAX || self.proto == proto)
+    }
+}
+
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for SubnetFilterParams {}
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(C)]
 pub struct Profile {
     /// The files that are being protected.
@@ -80,6 +135,28 @@ pub struct Profile {
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for Profile {}
 
+/// Key of a profile chunk.
+///
+/// Profiles with more than `MAX_FILE_RULES` rules are split
+/// into chunks with contiguous indexes starting from 0.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct ProfileKey {
+    /// The executable file that the profile applies to.
+    pub file: File,
+    /// Index of the chunk.
+    pub chunk: u64,
+}
+
+impl ProfileKey {
+    pub fn new(file: File, chunk: u64) -> Self {
+        Self { file, chunk }
+    }
+}
+
+#[cfg(feature = "userspace")]
+unsafe impl aya::Pod for ProfileKey {}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 #[repr(C)]
 pub struct File {
@@ -90,19 +167,19 @@ pub struct File {
 }
 
 impl File {
-    pub fn new(inode: u64) -> Self {
-        Self {
-            inode,
-            // Todo: This is not supported yet.
-            dev: 0,
-        }
+    /// Creates a new file.
+    ///
+    /// The device ID must use the kernel's internal encoding,
+    /// i.e. `major << 20 | minor`.
+    pub fn new(inode: u64, dev: u64) -> Self {
+        Self { inode, dev }
     }
 }
 
 #[cfg(feature = "userspace")]
 unsafe impl aya::Pod for File {}
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct FileRule {
     /// The operations that are permitted.
     ///
@@ -135,6 +212,36 @@ impl FileRule {
     pub const READ_MASK: u32 = 0x01 << 1;
     pub const WRITE_MASK: u32 = 0x01 << 2;
     pub const EXEC_MASK: u32 = 0x01 << 3;
+
+    /// File is open for reading.
+    pub const FMODE_READ: u32 = 0x01;
+    /// File is open for writing.
+    pub const FMODE_WRITE: u32 = 0x02;
+    /// File is open for execution.
+    pub const FMODE_EXEC: u32 = 0x20;
+
+    /// Returns the permissions required to open a file with the given kernel `f_mode`.
+    pub fn required_permissions(f_mode: u32) -> u32 {
+        let mut mask = Self::OPEN_MASK;
+        if f_mode & Self::FMODE_READ > 0 {
+            mask |= Self::READ_MASK;
+        }
+        if f_mode & Self::FMODE_WRITE > 0 {
+            mask |= Self::WRITE_MASK;
+        }
+        if f_mode & Self::FMODE_EXEC > 0 {
+            mask |= Self::EXEC_MASK;
+        }
+        mask
+    }
+
+    /// Returns true if every operation in the mask is permitted by this rule.
+    ///
+    /// Rules that only have the open permission predate the read, write and exec permissions, so
+    /// they keep allowing every operation.
+    pub fn allows(&self, mask: u32) -> bool {
+        self.permissions == Self::OPEN_MASK || self.permissions & mask == mask
+    }
 }
 
 pub struct FileCacheKey {
@@ -25,6 +25,7 @@
     pub fn dentry_d_name_name(target: *const dentry) -> *const *const u8;
     pub fn file_inode(target: *const file) -> *const *const inode;
     pub fn file_dentry(target: *const file) -> *const *const dentry;
+    pub fn file_f_mode(target: *const file) -> c_uint;
     pub fn file_f_path(target: *const file) -> *const path;
     pub fn inode_i_ino(inode: *const inode) -> *const c_ulong;
     pub fn inode_i_sb(inode: *const inode) -> *const *const super_block;
@@ -5,6 +5,7 @@
     FileCacheKey,
     FileRule,
     GlobalConfig,
+    ProfileKey,
     ACCESS_DENIED_EVENT,
     FILE_OPEN_PROG_ID,
     MAX_BUFFER_LEN,
@@ -22,8 +23,10 @@ unsafe fn try_file_open(ctx: LsmContext) -> Result<i32, i32> {
     let file: *const vmlinux::file = ctx.arg(0);
     let task_file = utils::get_file_from_current_task().map_err(|_| ALLOW)?;
     let task_inode = utils::read_file_inode(task_file).map_err(|_| ALLOW)?;
+    let task_dev = utils::read_file_dev(task_file).map_err(|_| ALLOW)?;
 
-    if let Some(profile) = maps::PROFILES.get(&File::new(task_inode)) {
+    let task_exec = File::new(task_inode, task_dev);
+    if maps::PROFILES.get(&ProfileKey::new(task_exec, 0)).is_some() {
         let global_config = maps::GLOBAL_CONFIG.get(&0).ok_or(DENY)?;
 
         // Get the path for the target file.
@@ -32,6 +35,9 @@ unsafe fn try_file_open(ctx: LsmContext) -> Result<i32, i32> {
         utils::read_path(file, path)?;
 
         if global_config.mode == GlobalConfig::ENFORCE_MODE {
+            // Get the operations that are being requested on the target file.
+            let mask = FileRule::required_permissions(access::file_f_mode(file));
+
             // Get the inode of the target file.
             let target_inode = {
                 let inode = aya_ebpf::helpers::bpf_probe_read_kernel(access::file_inode(file))
@@ -47,7 +53,7 @@ unsafe fn try_file_open(ctx: LsmContext) -> Result<i32, i32> {
             };
             if let Some(cached_rule) = maps::FILE_CACHE.get(&cache_key) {
                 if utils::contains(cached_rule.path.as_slice(), path, MAX_BUFFER_LEN)
-                    && cached_rule.permissions & FileRule::OPEN_MASK > 0
+                    && cached_rule.allows(mask)
                 {
                     return Ok(ALLOW);
                 }
@@ -56,7 +62,7 @@ unsafe fn try_file_open(ctx: LsmContext) -> Result<i32, i32> {
             }
 
             // Go through the rules in this profile and try to find a match.
-            if let Some(rule) = utils::find_match(profile, path, FileRule::OPEN_MASK)? {
+            if let Some(rule) = utils::find_match_in_chunks(task_exec, path, mask)? {
                 let _ = maps::FILE_CACHE.insert(cache_key, rule, 0);
                 return Ok(ALLOW);
             }
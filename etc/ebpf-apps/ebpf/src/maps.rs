@@ -7,8 +7,11 @@
     FileRule,
     GlobalConfig,
     PacketFilter,
+    PacketFilter6,
+    PacketFilterConfig,
     PacketFilterParams,
     Profile,
+    ProfileKey,
     SubnetFilterParams,
 };
 
@@ -16,10 +19,17 @@
 pub static PACKET_FILTERS: HashMap<PacketFilter, PacketFilterParams> =
     HashMap::<PacketFilter, PacketFilterParams>::with_max_entries(1024, 0);
 #[map]
-pub static SUBNET_FILTER: LpmTrie<u32, SubnetFilterParams> =
+pub static PACKET_FILTERS_V6: HashMap<PacketFilter6, PacketFilterParams> =
+    HashMap::<PacketFilter6, PacketFilterParams>::with_max_entries(1024, 0);
+#[map]
+pub static PACKET_FILTER_CONFIG: HashMap<u32, PacketFilterConfig> =
+    HashMap::<u32, PacketFilterConfig>::with_max_entries(1, 0);
+#[map]
+pub static SUBNET_FILTERS: LpmTrie<u32, SubnetFilterParams> =
     LpmTrie::<u32, SubnetFilterParams>::with_max_entries(1024, 0);
 #[map]
-pub static PROFILES: HashMap<File, Profile> = HashMap::<File, Profile>::with_max_entries(1024, 0);
+pub static PROFILES: HashMap<ProfileKey, Profile> =
+    HashMap::<ProfileKey, Profile>::with_max_entries(1024, 0);
 #[map]
 pub static BUFFERS: PerCpuHashMap<u32, Buffer> =
     PerCpuHashMap::<u32, Buffer>::with_max_entries(8, 0);
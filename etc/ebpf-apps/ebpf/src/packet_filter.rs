@@ -1,13 +1,14 @@
 use core::mem;
 
 use aya_ebpf::bindings::xdp_action;
+use aya_ebpf::helpers::bpf_ktime_get_ns;
 use aya_ebpf::macros::xdp;
 use aya_ebpf::maps::lpm_trie::Key;
 use aya_ebpf::programs::XdpContext;
-use lightning_ebpf_common::{PacketFilter, PacketFilterParams, SubnetFilterParams};
+use lightning_ebpf_common::{PacketFilter, PacketFilter6, PacketFilterParams, SubnetFilterParams};
 use memoffset::offset_of;
 use network_types::eth::{EthHdr, EtherType};
-use network_types::ip::{IpProto, Ipv4Hdr};
+use network_types::ip::{IpProto, Ipv4Hdr, Ipv6Hdr};
 use network_types::tcp::TcpHdr;
 use network_types::udp::UdpHdr;
 
@@ -27,6 +28,7 @@ unsafe fn filter(ctx: XdpContext) -> Result<u32, ()> {
     let h_proto = unsafe { *ptr_at::<EtherType>(&ctx, offset_of!(EthHdr, ether_type))? };
     match h_proto {
         EtherType::Ipv4 => process_ipv4(&ctx),
+        EtherType::Ipv6 => process_ipv6(&ctx),
         _ => Ok(xdp_action::XDP_PASS),
     }
 }
@@ -47,8 +49,10 @@ fn process_ipv4(ctx: &XdpContext) -> Result<XdpAction, ()> {
         IpProto::Udp => u16::from_be_bytes(unsafe {
             *ptr_at(&ctx, EthHdr::LEN + Ipv4Hdr::LEN + offset_of!(UdpHdr, dest))?
         }),
+        // ICMP has no notion of ports so it can only match filters for any port.
+        IpProto::Icmp => 0,
         _ => {
-            return Ok(xdp_action::XDP_PASS);
+            return Ok(default_action());
         },
     };
 
@@ -64,7 +68,59 @@ fn process_ipv4(ctx: &XdpContext) -> Result<XdpAction, ()> {
         return Ok(params.extra.action);
     }
 
-    Ok(xdp_action::XDP_PASS)
+    Ok(default_action())
+}
+
+// Extension headers are not supported yet so packets carrying them are only
+// matched by address.
+fn process_ipv6(ctx: &XdpContext) -> Result<XdpAction, ()> {
+    let ip: [u8; 16] = unsafe { *ptr_at(&ctx, EthHdr::LEN + offset_of!(Ipv6Hdr, src_addr))? };
+
+    if let Some(params) = try_match_only_ip6(ip) {
+        return Ok(params.action);
+    }
+
+    let proto = unsafe { *ptr_at::<IpProto>(&ctx, EthHdr::LEN + offset_of!(Ipv6Hdr, next_hdr))? };
+    let port = match proto {
+        IpProto::Tcp => u16::from_be_bytes(unsafe {
+            *ptr_at(&ctx, EthHdr::LEN + Ipv6Hdr::LEN + offset_of!(TcpHdr, dest))?
+        }),
+        IpProto::Udp => u16::from_be_bytes(unsafe {
+            *ptr_at(&ctx, EthHdr::LEN + Ipv6Hdr::LEN + offset_of!(UdpHdr, dest))?
+        }),
+        // ICMPv6 has no notion of ports so it can only match filters for any port.
+        IpProto::Ipv6Icmp => 0,
+        _ => {
+            return Ok(default_action());
+        },
+    };
+
+    if let Some(params) = try_match6(PacketFilter6 {
+        ip,
+        port,
+        proto: proto as u16,
+    }) {
+        return Ok(params.action);
+    }
+
+    Ok(default_action())
+}
+
+// Expired filters are ignored until they get removed from user space.
+fn is_live(params: &PacketFilterParams) -> bool {
+    // The kernel time is measured on the same monotonic clock as the expiry.
+    let now = unsafe { bpf_ktime_get_ns() } / 1_000_000_000;
+    !params.is_expired(now as u32)
+}
+
+// Packets that match no filter pass unless a default action was configured.
+fn default_action() -> XdpAction {
+    unsafe {
+        maps::PACKET_FILTER_CONFIG
+            .get(&0)
+            .map(|config| config.default_action)
+            .unwrap_or(xdp_action::XDP_PASS)
+    }
 }
 
 // Before any data access, the verifier requires us to do a bound check.
@@ -83,7 +139,7 @@ fn ptr_at<T>(ctx: &XdpContext, offset: usize) -> Result<*const T, ()> {
 fn try_match(filter: PacketFilter) -> Option<PacketFilterParams> {
     unsafe {
         // Try a specific match.
-        let mut result = maps::PACKET_FILTERS.get(&filter).copied();
+        let mut result = maps::PACKET_FILTERS.get(&filter).copied().filter(is_live);
 
         // Try for any port.
         if result.is_none() {
@@ -93,7 +149,8 @@ fn try_match(filter: PacketFilter) -> Option<PacketFilterParams> {
                     port: 0,
                     proto: filter.proto,
                 })
-                .copied();
+                .copied()
+                .filter(is_live);
         }
 
         // Try for any protocol.
@@ -105,6 +162,7 @@ fn try_match(filter: PacketFilter) -> Option<PacketFilterParams> {
                     proto: u16::MAX,
                 })
                 .copied()
+                .filter(is_live)
         }
 
         result
@@ -120,23 +178,70 @@ fn try_match_only_ip(ip: u32) -> Option<PacketFilterParams> {
                 proto: u16::MAX,
             })
             .copied()
+            .filter(is_live)
+    }
+}
+
+fn try_match6(filter: PacketFilter6) -> Option<PacketFilterParams> {
+    unsafe {
+        // Try a specific match.
+        let mut result = maps::PACKET_FILTERS_V6
+            .get(&filter)
+            .copied()
+            .filter(is_live);
+
+        // Try for any port.
+        if result.is_none() {
+            result = maps::PACKET_FILTERS_V6
+                .get(&PacketFilter6 {
+                    ip: filter.ip,
+                    port: 0,
+                    proto: filter.proto,
+                })
+                .copied()
+                .filter(is_live);
+        }
+
+        // Try for any protocol.
+        if result.is_none() {
+            result = maps::PACKET_FILTERS_V6
+                .get(&PacketFilter6 {
+                    ip: filter.ip,
+                    port: filter.port,
+                    proto: u16::MAX,
+                })
+                .copied()
+                .filter(is_live)
+        }
+
+        result
+    }
+}
+
+fn try_match_only_ip6(ip: [u8; 16]) -> Option<PacketFilterParams> {
+    unsafe {
+        maps::PACKET_FILTERS_V6
+            .get(&PacketFilter6 {
+                ip,
+                port: 0,
+                proto: u16::MAX,
+            })
+            .copied()
+            .filter(is_live)
     }
 }
 
+// Looks up the longest subnet prefix containing the address.
 fn try_match_subnet(ip: u32, port: u16, proto: u16) -> Option<SubnetFilterParams> {
-    let subnet_filter = maps::SUBNET_FILTER
+    // The trie matches prefixes on the bytes of the key, so the address
+    // has to be in network byte order.
+    let subnet_filter = maps::SUBNET_FILTERS
         .get(&Key {
             prefix_len: 32,
-            data: ip,
+            data: ip.to_be(),
         })
-        .copied()?;
-
-    if (subnet_filter.port == port && subnet_filter.proto == proto)
-        || (subnet_filter.port == port && subnet_filter.proto == u16::MAX)
-        || (subnet_filter.proto == proto && subnet_filter.port == 0)
-    {
-        Some(subnet_filter)
-    } else {
-        None
-    }
+        .copied()
+        .filter(|params| is_live(&params.extra))?;
+
+    subnet_filter.matches(port, proto).then_some(subnet_filter)
 }
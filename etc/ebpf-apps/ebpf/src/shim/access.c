@@ -20,6 +20,10 @@ struct inode ** file_inode(struct file *target) {
 	return __builtin_preserve_access_index(&target->f_inode);
 }
 
+fmode_t file_f_mode(struct file *target) {
+	return __builtin_preserve_access_index(target->f_mode);
+}
+
 struct path * file_f_path(struct file *target) {
 	return __builtin_preserve_access_index(&target->f_path);
 }
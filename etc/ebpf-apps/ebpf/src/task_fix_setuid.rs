@@ -3,6 +3,7 @@
 use lightning_ebpf_common::{
     File,
     GlobalConfig,
+    ProfileKey,
     LEARNING_MODE_EVENT,
     MAX_BUFFER_LEN,
     TASK_FIX_SETUID_PROG_ID,
@@ -21,10 +22,14 @@ pub unsafe fn try_task_fix_setuid(ctx: LsmContext) -> Result<i32, i32> {
     // Get the current process's binary file information.
     let file = utils::get_file_from_current_task().map_err(|_| ALLOW)?;
     let inode = utils::read_file_inode(file).map_err(|_| ALLOW)?;
+    let dev = utils::read_file_dev(file).map_err(|_| ALLOW)?;
 
     // Check the current mode that we're running on.
     let global_config = maps::GLOBAL_CONFIG.get(&0).ok_or(ALLOW)?;
-    if maps::PROFILES.get(&File::new(inode)).is_some() {
+    if maps::PROFILES
+        .get(&ProfileKey::new(File::new(inode, dev), 0))
+        .is_some()
+    {
         if global_config.mode == GlobalConfig::LEARN_MODE {
             let buf = maps::BUFFERS.get_ptr_mut(&1).ok_or(DENY)?;
             let scratch_buf = buf.as_mut().ok_or(ALLOW)?.as_mut_slice();
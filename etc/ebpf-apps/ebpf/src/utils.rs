@@ -1,6 +1,15 @@
 use core::ffi::c_char;
 
-use lightning_ebpf_common::{FileRule, Profile, EVENT_HEADER_SIZE, MAX_BUFFER_LEN, MAX_FILE_RULES};
+use lightning_ebpf_common::{
+    File,
+    FileRule,
+    Profile,
+    ProfileKey,
+    EVENT_HEADER_SIZE,
+    MAX_BUFFER_LEN,
+    MAX_FILE_RULES,
+    MAX_PROFILE_CHUNKS,
+};
 
 use crate::{access, maps, vmlinux};
 
@@ -31,7 +40,7 @@ pub fn find_match<'a>(
     for i in 0..MAX_FILE_RULES {
         let rule = profile.rules.get(i).ok_or(DENY)?;
 
-        if rule.permissions & mask == 0 {
+        if !rule.allows(mask) {
             continue;
         }
 
@@ -43,6 +52,27 @@ pub fn find_match<'a>(
     Ok(None)
 }
 
+/// Tries to find a rule that matches the target path and mask
+/// in any of the chunks of the executable's profile.
+pub fn find_match_in_chunks(
+    exec: File,
+    target_path: &[u8],
+    mask: u32,
+) -> Result<Option<&'static FileRule>, i32> {
+    for chunk in 0..MAX_PROFILE_CHUNKS {
+        // Chunks are contiguous so we can stop at the first missing one.
+        let Some(profile) = (unsafe { maps::PROFILES.get(&ProfileKey::new(exec, chunk)) }) else {
+            break;
+        };
+
+        if let Some(rule) = find_match(profile, target_path, mask)? {
+            return Ok(Some(rule));
+        }
+    }
+
+    Ok(None)
+}
+
 /// Get the current process's binary file.
 pub unsafe fn get_file_from_current_task() -> Result<*const vmlinux::file, i64> {
     let task = aya_ebpf::helpers::bpf_get_current_task() as *mut vmlinux::task_struct;
@@ -56,6 +86,14 @@ pub unsafe fn read_file_inode(file: *const vmlinux::file) -> Result<u64, i64> {
     aya_ebpf::helpers::bpf_probe_read_kernel(access::inode_i_ino(f_inode))
 }
 
+/// Read the ID of the device that the file is located on from the kernel file struct.
+pub unsafe fn read_file_dev(file: *const vmlinux::file) -> Result<u64, i64> {
+    let f_inode = aya_ebpf::helpers::bpf_probe_read_kernel(access::file_inode(file))?;
+    let sb = aya_ebpf::helpers::bpf_probe_read_kernel(access::inode_i_sb(f_inode))?;
+    let dev = aya_ebpf::helpers::bpf_probe_read_kernel(access::super_block_s_dev(sb))?;
+    Ok(dev as u64)
+}
+
 /// Read the file name from the kernel file struct.
 unsafe fn read_file_name(file: *const vmlinux::file, dst: &mut [u8]) -> Result<&[u8], i64> {
     let dentry = aya_ebpf::helpers::bpf_probe_read_kernel(access::file_dentry(file))?;
@@ -1,18 +1,21 @@
 use std::path::PathBuf;
 
 use anyhow::Context;
-use aya::maps::{HashMap, PerCpuHashMap, PerCpuValues, RingBuf};
+use aya::maps::{HashMap, LpmTrie, PerCpuHashMap, PerCpuValues, RingBuf};
 use aya::programs::{Lsm, Xdp, XdpFlags};
 use aya::{include_bytes_aligned, Btf, Ebpf};
 use aya_log::EbpfLogger;
 use clap::Parser;
 use lightning_ebpf_common::{
     Buffer,
-    File,
     GlobalConfig,
     PacketFilter,
+    PacketFilter6,
+    PacketFilterConfig,
     PacketFilterParams,
     Profile,
+    ProfileKey,
+    SubnetFilterParams,
     MAX_BUFFER_LEN,
 };
 use lightning_guard::map::SharedMap;
@@ -95,10 +98,16 @@ async fn main() -> anyhow::Result<()> {
         _task_fix_setuid = Some(prog);
     }
 
-    let file_open_allow: HashMap<_, File, Profile> =
+    let file_open_allow: HashMap<_, ProfileKey, Profile> =
         HashMap::try_from(handle.take_map("PROFILES").unwrap())?;
     let packet_filters: HashMap<_, PacketFilter, PacketFilterParams> =
         HashMap::try_from(handle.take_map("PACKET_FILTERS").unwrap())?;
+    let packet_filters6: HashMap<_, PacketFilter6, PacketFilterParams> =
+        HashMap::try_from(handle.take_map("PACKET_FILTERS_V6").unwrap())?;
+    let subnet_filters: LpmTrie<_, u32, SubnetFilterParams> =
+        LpmTrie::try_from(handle.take_map("SUBNET_FILTERS").unwrap())?;
+    let packet_filter_config: HashMap<_, u32, PacketFilterConfig> =
+        HashMap::try_from(handle.take_map("PACKET_FILTER_CONFIG").unwrap())?;
     let events: RingBuf<_> = RingBuf::try_from(handle.take_map("EVENTS").unwrap())?;
 
     let mut buffers: PerCpuHashMap<_, u32, Buffer> =
@@ -139,7 +148,14 @@ async fn main() -> anyhow::Result<()> {
     let _ = tokio::fs::remove_file(opt.bind.as_path()).await;
     let listener = UnixListener::bind(opt.bind.as_path())?;
 
-    let shared_state = SharedMap::new(packet_filters, file_open_allow, config_src.clone());
+    let shared_state = SharedMap::new(
+        packet_filters,
+        packet_filters6,
+        subnet_filters,
+        packet_filter_config,
+        file_open_allow,
+        config_src.clone(),
+    );
     let server = Server::new(listener, shared_state, config_src, events)?;
 
     log::info!("Enter Ctrl-C to shutdown");
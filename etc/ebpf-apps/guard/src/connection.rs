@@ -23,10 +23,14 @@ pub fn new(socket: UnixStream, shared_state: SharedMap) -> Self {
     async fn pf_handle(&mut self, message: Pf) -> anyhow::Result<()> {
         match message.op {
             Pf::ADD => {
-                self.shared_state.packet_filter_add(message.addr).await?;
+                self.shared_state
+                    .packet_filter_add(message.addr, message.proto)
+                    .await?;
             },
             Pf::REMOVE => {
-                self.shared_state.packet_filter_remove(message.addr).await?;
+                self.shared_state
+                    .packet_filter_remove(message.addr, message.proto)
+                    .await?;
             },
             op => {
                 bail!("invalid op: {op:?}");
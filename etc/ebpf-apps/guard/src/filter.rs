@@ -24,15 +24,20 @@ pub async fn connect(&self) -> io::Result<()> {
         write(&self.inner, Bytes::from(service.to_vec())).await
     }
 
-    pub async fn add(&self, addr: SocketAddrV4) -> io::Result<()> {
-        let frame = IpcServiceFrame::Pf(Pf { op: Pf::ADD, addr });
+    pub async fn add(&self, addr: SocketAddrV4, proto: u16) -> io::Result<()> {
+        let frame = IpcServiceFrame::Pf(Pf {
+            op: Pf::ADD,
+            addr,
+            proto,
+        });
         write(&self.inner, frame.serialize_len_delimit()).await
     }
 
-    pub async fn remove(&self, addr: SocketAddrV4) -> io::Result<()> {
+    pub async fn remove(&self, addr: SocketAddrV4, proto: u16) -> io::Result<()> {
         let frame = IpcServiceFrame::Pf(Pf {
             op: Pf::REMOVE,
             addr,
+            proto,
         });
         write(&self.inner, frame.serialize_len_delimit()).await
     }
@@ -25,8 +25,8 @@ impl IpcServiceFrame {
     pub fn serialize_len_delimit(self) -> Bytes {
         match self {
             IpcServiceFrame::Pf(pf) => {
-                let mut result = BytesMut::with_capacity(8 + 8);
-                result.put_u64(8);
+                let mut result = BytesMut::with_capacity(8 + 10);
+                result.put_u64(10);
                 result.put_u8(IpcServiceFrame::PF);
                 pf.serialize(&mut result)
                     .expect("Buffer capacity is hard-coded");
@@ -67,6 +67,11 @@ fn try_from(mut value: &[u8]) -> Result<Self, Self::Error> {
 pub struct Pf {
     pub op: u8,
     pub addr: SocketAddrV4,
+    /// Transport protocol.
+    ///
+    /// Uses values from Ipv4 header.
+    /// Use `u16::MAX` to indicate `any`.
+    pub proto: u16,
 }
 
 impl Pf {
@@ -76,7 +81,7 @@ impl Pf {
     pub const REMOVE: u8 = 1;
 
     pub fn serialize<T: BufMut>(self, mut buf: T) -> io::Result<()> {
-        if buf.remaining_mut() < 7 {
+        if buf.remaining_mut() < 9 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "not enough space in buffer",
@@ -85,6 +90,7 @@ pub fn serialize<T: BufMut>(self, mut buf: T) -> io::Result<()> {
         buf.put_u8(self.op);
         buf.put_slice(&self.addr.ip().octets());
         buf.put_u16(self.addr.port());
+        buf.put_u16(self.proto);
         Ok(())
     }
 }
@@ -93,7 +99,7 @@ impl TryFrom<&[u8]> for Pf {
     type Error = io::Error;
 
     fn try_from(mut value: &[u8]) -> Result<Self, Self::Error> {
-        if value.remaining() != 7 {
+        if value.remaining() != 9 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "not enough data to deserialize",
@@ -102,7 +108,8 @@ fn try_from(mut value: &[u8]) -> Result<Self, Self::Error> {
         let op = value.get_u8();
         let ip = Ipv4Addr::from(value.get_u32());
         let port = value.get_u16();
+        let proto = value.get_u16();
         let addr = SocketAddrV4::new(ip, port);
-        Ok(Pf { op, addr })
+        Ok(Pf { op, addr, proto })
     }
 }
@@ -5,4 +5,4 @@
 
 pub use schema::{FileRule, PacketFilterRule, Profile};
 #[cfg(feature = "server")]
-pub use shared::SharedMap;
+pub use shared::{FilterDrift, MapDiff, ReconcileReport, SharedMap};
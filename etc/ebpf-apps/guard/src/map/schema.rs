@@ -43,8 +43,12 @@ pub struct PacketFilterRule {
 }
 
 impl PacketFilterRule {
+    pub const ABORTED: u32 = 0;
     pub const DROP: u32 = 1;
     pub const PASS: u32 = 2;
+    pub const TX: u32 = 3;
+    pub const REDIRECT: u32 = 4;
+    pub const ICMP: u16 = 1;
     pub const TCP: u16 = 6;
     pub const UDP: u16 = 17;
     pub const ANY_PROTO: u16 = u16::MAX;
@@ -52,18 +56,28 @@ impl PacketFilterRule {
 }
 
 impl PacketFilterRule {
+    /// Returns true if the action is one of the XDP actions above.
+    pub fn is_valid_action(action: u32) -> bool {
+        action <= Self::REDIRECT
+    }
+
     pub fn action_str(&self) -> String {
         match self.action {
+            Self::ABORTED => "aborted".to_string(),
             Self::DROP => "drop".to_string(),
             Self::PASS => "pass".to_string(),
+            Self::TX => "tx".to_string(),
+            Self::REDIRECT => "redirect".to_string(),
             _ => "N/A".to_string(),
         }
     }
 
     pub fn proto_str(&self) -> String {
         match self.proto {
+            Self::ICMP => "icmp".to_string(),
             Self::TCP => "tcp".to_string(),
             Self::UDP => "udp".to_string(),
+            Self::ANY_PROTO => "any".to_string(),
             _ => "N/A".to_string(),
         }
     }
@@ -87,6 +101,7 @@ fn from(value: PacketFilterRule) -> Self {
             trigger_event: value.audit as u16,
             shortlived: value.shortlived as u16,
             action: value.action,
+            expires_at: 0,
         }
     }
 }
@@ -148,24 +163,25 @@ impl FileRule {
     pub const OPEN_SYMBOL: char = 'o';
     pub const READ_SYMBOL: char = 'r';
     pub const WRITE_SYMBOL: char = 'w';
+    pub const EXEC_SYMBOL: char = 'x';
 
     pub fn permissions(&self) -> String {
         let mut result = String::new();
 
         if self.permissions & Self::OPEN_MASK == Self::OPEN_MASK {
-            result.push('o');
+            result.push(Self::OPEN_SYMBOL);
         }
 
         if self.permissions & Self::READ_MASK == Self::READ_MASK {
-            result.push('r');
+            result.push(Self::READ_SYMBOL);
         }
 
         if self.permissions & Self::WRITE_MASK == Self::WRITE_MASK {
-            result.push('w');
+            result.push(Self::WRITE_SYMBOL);
         }
 
         if self.permissions & Self::EXEC_MASK == Self::EXEC_MASK {
-            result.push('x');
+            result.push(Self::EXEC_SYMBOL);
         }
 
         if result.is_empty() {
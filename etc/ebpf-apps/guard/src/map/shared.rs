@@ -1,18 +1,26 @@
-use std::net::SocketAddrV4;
+use std::net::{SocketAddrV4, SocketAddrV6};
 use std::os::unix::fs::MetadataExt;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::bail;
+use aya::maps::lpm_trie::{Key, LpmTrie};
 use aya::maps::{HashMap, MapData};
+use ipnet::Ipv4Net;
 use lightning_ebpf_common::{
     File,
     FileRule,
     PacketFilter,
+    PacketFilter6,
+    PacketFilterConfig,
     PacketFilterParams,
     Profile,
+    ProfileKey,
+    SubnetFilterParams,
     MAX_BUFFER_LEN,
     MAX_FILE_RULES,
+    MAX_PROFILE_CHUNKS,
 };
 use log::debug;
 use tokio::fs;
@@ -24,187 +32,693 @@
 #[derive(Clone)]
 pub struct SharedMap {
     packet_filters: Arc<Mutex<HashMap<MapData, PacketFilter, PacketFilterParams>>>,
-    file_open_rules: Arc<Mutex<HashMap<MapData, File, Profile>>>,
+    packet_filters6: Arc<Mutex<HashMap<MapData, PacketFilter6, PacketFilterParams>>>,
+    subnet_filters: Arc<Mutex<LpmTrie<MapData, u32, SubnetFilterParams>>>,
+    packet_filter_config: Arc<Mutex<HashMap<MapData, u32, PacketFilterConfig>>>,
+    file_open_rules: Arc<Mutex<HashMap<MapData, ProfileKey, Profile>>>,
     config_src: ConfigSource,
 }
 
 impl SharedMap {
     pub fn new(
         packet_filters: HashMap<MapData, PacketFilter, PacketFilterParams>,
-        file_open_rules: HashMap<MapData, File, Profile>,
+        packet_filters6: HashMap<MapData, PacketFilter6, PacketFilterParams>,
+        subnet_filters: LpmTrie<MapData, u32, SubnetFilterParams>,
+        packet_filter_config: HashMap<MapData, u32, PacketFilterConfig>,
+        file_open_rules: HashMap<MapData, ProfileKey, Profile>,
         config_src: ConfigSource,
     ) -> Self {
         Self {
             packet_filters: Arc::new(Mutex::new(packet_filters)),
+            packet_filters6: Arc::new(Mutex::new(packet_filters6)),
+            subnet_filters: Arc::new(Mutex::new(subnet_filters)),
+            packet_filter_config: Arc::new(Mutex::new(packet_filter_config)),
             file_open_rules: Arc::new(Mutex::new(file_open_rules)),
             config_src,
         }
     }
 
-    pub async fn packet_filter_add(&mut self, addr: SocketAddrV4) -> anyhow::Result<()> {
+    /// Adds a short-lived filter that drops packets from the given address.
+    ///
+    /// `proto` uses values from the Ipv4 header, see [`PacketFilterRule`].
+    pub async fn packet_filter_add(
+        &mut self,
+        addr: SocketAddrV4,
+        proto: u16,
+    ) -> anyhow::Result<()> {
+        self.packet_filter_add_with_action(addr, proto, PacketFilterRule::DROP)
+            .await
+    }
+
+    /// Adds a short-lived filter that applies the given action to packets from the given address.
+    ///
+    /// `action` is one of the actions of [`PacketFilterRule`], e.g. [`PacketFilterRule::PASS`]
+    /// to allow an address when the default policy drops packets.
+    ///
+    /// Short-lived filters take precedence over the default policy like any other filter, but
+    /// they are not saved in storage and are kept when the filters are updated from storage.
+    pub async fn packet_filter_add_with_action(
+        &mut self,
+        addr: SocketAddrV4,
+        proto: u16,
+        action: u32,
+    ) -> anyhow::Result<()> {
+        self.insert_shortlived_filter(addr, proto, action, 0).await
+    }
+
+    /// Same as [`SharedMap::packet_filter_add_with_action`], but the filter expires after the
+    /// given time to live.
+    ///
+    /// Expired filters are ignored by the packet filter and get removed by
+    /// [`SharedMap::remove_expired_packet_filters`].
+    pub async fn packet_filter_add_with_expiry(
+        &mut self,
+        addr: SocketAddrV4,
+        proto: u16,
+        action: u32,
+        ttl: Duration,
+    ) -> anyhow::Result<()> {
+        let expires_at = expires_at(monotonic_secs()?, ttl);
+        self.insert_shortlived_filter(addr, proto, action, expires_at)
+            .await
+    }
+
+    async fn insert_shortlived_filter(
+        &mut self,
+        addr: SocketAddrV4,
+        proto: u16,
+        action: u32,
+        expires_at: u32,
+    ) -> anyhow::Result<()> {
+        if !PacketFilterRule::is_valid_action(action) {
+            bail!("invalid packet filter action {action}");
+        }
+
         let mut map = self.packet_filters.lock().await;
         map.insert(
             PacketFilter {
                 ip: u32::from_be_bytes(addr.ip().octets()),
                 port: addr.port(),
-                proto: PacketFilterRule::TCP,
+                proto,
             },
             PacketFilterParams {
                 trigger_event: 1,
                 shortlived: 1,
-                action: PacketFilterRule::DROP,
+                action,
+                expires_at,
             },
             0,
         )?;
         Ok(())
     }
 
-    pub async fn packet_filter_remove(&mut self, addr: SocketAddrV4) -> anyhow::Result<()> {
+    pub async fn packet_filter_remove(
+        &mut self,
+        addr: SocketAddrV4,
+        proto: u16,
+    ) -> anyhow::Result<()> {
         let mut map = self.packet_filters.lock().await;
         map.remove(&PacketFilter {
             ip: u32::from_be_bytes(addr.ip().octets()),
             port: addr.port(),
-            proto: PacketFilterRule::TCP,
+            proto,
+        })?;
+        Ok(())
+    }
+
+    /// Adds a short-lived filter that drops packets from the given IPv6 address.
+    ///
+    /// `proto` uses values from the Ipv6 header, see [`PacketFilterRule`].
+    pub async fn packet_filter6_add(
+        &mut self,
+        addr: SocketAddrV6,
+        proto: u16,
+    ) -> anyhow::Result<()> {
+        let mut map = self.packet_filters6.lock().await;
+        map.insert(
+            PacketFilter6 {
+                ip: addr.ip().octets(),
+                port: addr.port(),
+                proto,
+            },
+            PacketFilterParams {
+                trigger_event: 1,
+                shortlived: 1,
+                action: PacketFilterRule::DROP,
+                expires_at: 0,
+            },
+            0,
+        )?;
+        Ok(())
+    }
+
+    pub async fn packet_filter6_remove(
+        &mut self,
+        addr: SocketAddrV6,
+        proto: u16,
+    ) -> anyhow::Result<()> {
+        let mut map = self.packet_filters6.lock().await;
+        map.remove(&PacketFilter6 {
+            ip: addr.ip().octets(),
+            port: addr.port(),
+            proto,
         })?;
         Ok(())
     }
 
+    /// Removes the short-lived filters that have expired.
+    pub async fn remove_expired_packet_filters(&self) -> anyhow::Result<()> {
+        let now = monotonic_secs()?;
+
+        let mut map = self.packet_filters.lock().await;
+        let current = map.iter().collect::<Result<_, _>>()?;
+        for filter in expired(&current, now) {
+            map.remove(&filter)?;
+        }
+        drop(map);
+
+        let mut map = self.packet_filters6.lock().await;
+        let current = map.iter().collect::<Result<_, _>>()?;
+        for filter in expired(&current, now) {
+            map.remove(&filter)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the action applied to packets that match no filter.
+    ///
+    /// The default policy is [`PacketFilterRule::PASS`], which makes the filters a denylist.
+    /// Setting it to [`PacketFilterRule::DROP`] makes them an allowlist, where only packets
+    /// matching a [`PacketFilterRule::PASS`] filter are accepted.
+    pub async fn set_default_policy(&mut self, action: u32) -> anyhow::Result<()> {
+        if !PacketFilterRule::is_valid_action(action) {
+            bail!("invalid packet filter action {action}");
+        }
+
+        let mut map = self.packet_filter_config.lock().await;
+        map.insert(
+            0,
+            PacketFilterConfig {
+                default_action: action,
+            },
+            0,
+        )?;
+        Ok(())
+    }
+
+    /// Returns the action applied to packets that match no filter.
+    pub async fn default_policy(&self) -> anyhow::Result<u32> {
+        let map = self.packet_filter_config.lock().await;
+        match map.get(&0, 0) {
+            Ok(config) => Ok(config.default_action),
+            Err(aya::maps::MapError::KeyNotFound) => Ok(PacketFilterRule::PASS),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Adds a filter for every address in the given IPv4 subnet.
+    ///
+    /// Subnet filters are only consulted when no filter matches the exact address.
+    pub async fn subnet_filter_add(
+        &mut self,
+        cidr: Ipv4Net,
+        params: SubnetFilterParams,
+    ) -> anyhow::Result<()> {
+        let mut map = self.subnet_filters.lock().await;
+        map.insert(&subnet_key(cidr), params, 0)?;
+        Ok(())
+    }
+
+    pub async fn subnet_filter_remove(&mut self, cidr: Ipv4Net) -> anyhow::Result<()> {
+        let mut map = self.subnet_filters.lock().await;
+        map.remove(&subnet_key(cidr))?;
+        Ok(())
+    }
+
+    /// Returns a snapshot of the packet filters that are currently loaded in the kernel.
+    pub async fn list_packet_filters(
+        &self,
+    ) -> anyhow::Result<Vec<(PacketFilter, PacketFilterParams)>> {
+        let map = self.packet_filters.lock().await;
+        Ok(map.iter().collect::<Result<_, _>>()?)
+    }
+
+    /// Returns a snapshot of the file rules that are currently loaded in the kernel,
+    /// grouped by the executable file they apply to.
+    pub async fn list_file_rules(&self) -> anyhow::Result<Vec<(File, Vec<FileRule>)>> {
+        let map = self.file_open_rules.lock().await;
+        let chunks = map.iter().collect::<Result<_, _>>()?;
+        Ok(merge_profile_chunks(chunks))
+    }
+
     /// Updates packet filters.
     ///
     /// Reads from disk so it's a heavy operation.
     pub async fn update_packet_filters(&self) -> anyhow::Result<()> {
-        let filters: Vec<PacketFilterRule> = self.config_src.read_packet_filters().await?;
-        let new_state = filters
-            .into_iter()
-            .map(|filter| (PacketFilter::from(filter), PacketFilterParams::from(filter)))
-            .collect::<std::collections::HashMap<_, _>>();
+        let new_state = self.read_packet_filters().await?;
 
         let mut map = self.packet_filters.lock().await;
-        // Due to a constraint of the aya api, there is no clean method for the maps and
-        // we don't get mutable access as iterator is read only.
-        let mut remove = Vec::new();
-        for result in map.iter() {
-            let (filter, params) = result?;
-            // Filters with shortlived=1 do not get removed.
-            // This is to support dynamic ephemiral rules
-            // that may be produced by rate limiting, for example.
-            if !new_state.contains_key(&filter) && params.shortlived != 1 {
-                remove.push(filter);
-            }
-        }
+        let diff = packet_filters_diff(&map, new_state)?;
 
-        for (filter, params) in new_state {
+        for (filter, params) in diff.insert {
             map.insert(filter, params, 0)?;
         }
 
-        for filter in remove {
+        for filter in diff.remove {
             map.remove(&filter)?;
         }
 
         Ok(())
     }
 
+    /// Returns the changes that [`SharedMap::update_packet_filters`]
+    /// would apply without modifying the map.
+    ///
+    /// Reads from disk so it's a heavy operation.
+    pub async fn diff_packet_filters(
+        &self,
+    ) -> anyhow::Result<MapDiff<PacketFilter, PacketFilterParams>> {
+        let new_state = self.read_packet_filters().await?;
+        let map = self.packet_filters.lock().await;
+        packet_filters_diff(&map, new_state)
+    }
+
+    /// Compares the packet filters loaded in the kernel with the ones in the config.
+    ///
+    /// This covers the IPv4, IPv6 and subnet filters. The config only holds IPv4 filters,
+    /// so every IPv6 and subnet filter that is not short-lived is reported as unmanaged.
+    /// Short-lived filters are not part of the config, so they are never reported.
+    /// If `force` is true, the config is applied as the source of truth afterwards,
+    /// just like [`SharedMap::update_packet_filters`] does.
+    ///
+    /// Reads from disk so it's a heavy operation.
+    pub async fn reconcile(&self, force: bool) -> anyhow::Result<ReconcileReport> {
+        let new_state = self.read_packet_filters().await?;
+
+        let mut map = self.packet_filters.lock().await;
+        let current = map.iter().collect::<Result<_, _>>()?;
+        let packet_filters = drift(current, &new_state, |params| params.shortlived == 1);
+
+        if force && !packet_filters.is_empty() {
+            let diff = packet_filters_diff(&map, new_state)?;
+            for (filter, params) in diff.insert {
+                map.insert(filter, params, 0)?;
+            }
+            for filter in diff.remove {
+                map.remove(&filter)?;
+            }
+        }
+        drop(map);
+
+        // There are no IPv6 or subnet filters in the config, so the only drift
+        // in these maps are unmanaged filters, which get removed when forced.
+        let mut map = self.packet_filters6.lock().await;
+        let current = map.iter().collect::<Result<_, _>>()?;
+        let packet_filters6 = drift(current, &Default::default(), |params| {
+            params.shortlived == 1
+        });
+
+        if force {
+            for (filter, _) in packet_filters6.unmanaged.iter() {
+                map.remove(filter)?;
+            }
+        }
+        drop(map);
+
+        let mut map = self.subnet_filters.lock().await;
+        let current = map
+            .iter()
+            .map(|entry| {
+                let (key, params) = entry?;
+                Ok((subnet_from_key(&key)?, params))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let subnet_filters = drift(current, &Default::default(), |params| {
+            params.extra.shortlived == 1
+        });
+
+        if force {
+            for (cidr, _) in subnet_filters.unmanaged.iter() {
+                map.remove(&subnet_key(*cidr))?;
+            }
+        }
+
+        Ok(ReconcileReport {
+            packet_filters,
+            packet_filters6,
+            subnet_filters,
+        })
+    }
+
+    async fn read_packet_filters(
+        &self,
+    ) -> anyhow::Result<std::collections::HashMap<PacketFilter, PacketFilterParams>> {
+        let filters: Vec<PacketFilterRule> = self.config_src.read_packet_filters().await?;
+        Ok(filters
+            .into_iter()
+            .map(|filter| (PacketFilter::from(filter), PacketFilterParams::from(filter)))
+            .collect())
+    }
+
     /// Updates file rules.
     ///
     /// Reads from disk so it's a heavy operation.
     pub async fn update_all_file_rules(&self) -> anyhow::Result<()> {
+        let new_state = self.read_all_file_rules().await?;
+
+        let mut maps = self.file_open_rules.lock().await;
+        let diff = file_rules_diff(&maps, new_state)?;
+
+        for key in diff.remove {
+            maps.remove(&key)?;
+        }
+
+        for (key, profile) in diff.insert {
+            maps.insert(key, profile, 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the changes that [`SharedMap::update_all_file_rules`]
+    /// would apply without modifying the map.
+    ///
+    /// Reads from disk so it's a heavy operation.
+    pub async fn diff_file_rules(&self) -> anyhow::Result<MapDiff<ProfileKey, Profile>> {
+        let new_state = self.read_all_file_rules().await?;
+        let maps = self.file_open_rules.lock().await;
+        file_rules_diff(&maps, new_state)
+    }
+
+    async fn read_all_file_rules(
+        &self,
+    ) -> anyhow::Result<std::collections::HashMap<ProfileKey, Profile>> {
         let profiles = self.config_src.get_profiles().await?;
 
         let mut new = std::collections::HashMap::new();
         for profile in profiles {
-            let exec_path = profile.name.as_ref().unwrap_or(&GLOBAL_PROFILE);
-            let (exec, _) = file_from_path(exec_path).await?;
-            let mut rules = vec![lightning_ebpf_common::FileRule::default(); MAX_FILE_RULES];
-            for (i, rule) in profile.file_rules.iter().enumerate() {
-                let (file, is_dir) = file_from_path(&rule.file).await?;
-                if exec.dev != file.dev {
-                    // Protecting files in more than one device is not supported yet.
-                    bail!("executable file device and file device do not match");
-                }
-
-                if i >= MAX_FILE_RULES {
-                    bail!("path maximum {MAX_FILE_RULES} execeeded");
-                }
-
-                let mut vector = vec![0u8; MAX_BUFFER_LEN];
-                let path = rule.file.as_path().display().to_string();
-
-                debug!("path {path} for profile {}", exec_path.display());
-
-                vector[..path.len()].copy_from_slice(path.as_bytes());
-
-                rules[i].path = vector.try_into().expect("Size is hardcoded");
-                rules[i].is_dir = if is_dir {
-                    FileRule::IS_DIR
-                } else {
-                    FileRule::IS_FILE
-                };
-                rules[i].permissions = rule.permissions;
-            }
-
-            let rules: [lightning_ebpf_common::FileRule; MAX_FILE_RULES] =
-                rules.try_into().expect("Vec len is hardcoded");
-            new.insert(exec, Profile { rules });
+            let (exec, rules) = read_file_rules(&profile).await?;
+            new.extend(profile_chunks(exec, rules)?);
         }
 
+        Ok(new)
+    }
+
+    pub async fn update_file_rules(&self, path: PathBuf) -> anyhow::Result<()> {
+        let profile = self.config_src.read_profile(Some(path.as_os_str())).await?;
+        let (exec, rules) = read_file_rules(&profile).await?;
+        let chunks = profile_chunks(exec, rules)?;
+
         let mut maps = self.file_open_rules.lock().await;
 
-        // Due to a constraint of the aya api, there is no clean method for the maps
-        // so we remove all of them. Todo: Let's open an issue with aya.
+        // Remove the chunks left over from a previous version of this profile.
         let mut remove = Vec::new();
-        for file in maps.keys() {
-            remove.push(file);
+        for key in maps.keys() {
+            let key = key?;
+            if key.file == exec && key.chunk >= chunks.len() as u64 {
+                remove.push(key);
+            }
         }
-        for file in remove {
-            let f = file?;
-            maps.remove(&f)?;
+        for key in remove {
+            maps.remove(&key)?;
         }
 
-        for (exec, rules) in new {
-            maps.insert(exec, rules, 0)?;
+        for (key, profile) in chunks {
+            maps.insert(key, profile, 0)?;
         }
 
         Ok(())
     }
+}
 
-    pub async fn update_file_rules(&self, path: PathBuf) -> anyhow::Result<()> {
-        let profile = self.config_src.read_profile(Some(path.as_os_str())).await?;
-        let exec_path = profile.name.as_ref().unwrap_or(&GLOBAL_PROFILE);
-        let (exec, _) = file_from_path(exec_path).await?;
-        let mut file_open_rules = vec![lightning_ebpf_common::FileRule::default(); MAX_FILE_RULES];
-        for (i, rule) in profile.file_rules.iter().enumerate() {
-            let (file, is_dir) = file_from_path(&rule.file).await?;
-            if exec.dev != file.dev {
-                // Protecting files in more than one device is not supported yet.
-                bail!("executable file device and file device do not match");
-            }
-            if i >= MAX_FILE_RULES {
-                bail!("path maximum {MAX_FILE_RULES} exceeded");
-            }
+/// Changes to be applied to a map.
+#[derive(Debug)]
+pub struct MapDiff<K, V> {
+    /// Entries that are new or whose value changed.
+    pub insert: Vec<(K, V)>,
+    /// Keys that are no longer present.
+    pub remove: Vec<K>,
+}
 
-            let mut vector = vec![0u8; MAX_BUFFER_LEN];
-            let path = rule.file.as_path().display().to_string();
-            vector[..path.len()].copy_from_slice(path.as_bytes());
+/// Drift between the packet filters in the kernel and the ones in the config.
+pub struct ReconcileReport {
+    /// Drift of the IPv4 filters.
+    pub packet_filters: FilterDrift<PacketFilter, PacketFilterParams>,
+    /// Drift of the IPv6 filters.
+    pub packet_filters6: FilterDrift<PacketFilter6, PacketFilterParams>,
+    /// Drift of the subnet filters.
+    pub subnet_filters: FilterDrift<Ipv4Net, SubnetFilterParams>,
+}
+
+impl ReconcileReport {
+    /// Returns true if the kernel matches the config.
+    pub fn is_empty(&self) -> bool {
+        self.packet_filters.is_empty()
+            && self.packet_filters6.is_empty()
+            && self.subnet_filters.is_empty()
+    }
+}
+
+/// Drift between the filters of a single map and the ones in the config.
+#[derive(Debug)]
+pub struct FilterDrift<K, V> {
+    /// Filters that are in the kernel but not in the config.
+    pub unmanaged: Vec<(K, V)>,
+    /// Filters that are in the config but not in the kernel.
+    pub missing: Vec<(K, V)>,
+    /// Filters whose parameters in the kernel differ from the config,
+    /// as `(filter, kernel, config)`.
+    pub changed: Vec<(K, V, V)>,
+}
+
+impl<K, V> FilterDrift<K, V> {
+    /// Returns true if the map matches the config.
+    pub fn is_empty(&self) -> bool {
+        self.unmanaged.is_empty() && self.missing.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Computes the drift between the `current` filters and the ones in the `config`.
+///
+/// Filters for which `shortlived` returns true are never reported as unmanaged.
+fn drift<K, V>(
+    current: std::collections::HashMap<K, V>,
+    config: &std::collections::HashMap<K, V>,
+    shortlived: impl Fn(&V) -> bool,
+) -> FilterDrift<K, V>
+where
+    K: Copy + Eq + std::hash::Hash,
+    V: Copy + PartialEq,
+{
+    let mut drift = FilterDrift {
+        unmanaged: Vec::new(),
+        missing: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (filter, params) in current.iter() {
+        match config.get(filter) {
+            Some(configured) if configured != params => {
+                drift.changed.push((*filter, *params, *configured))
+            },
+            Some(_) => {},
+            None if shortlived(params) => {},
+            None => drift.unmanaged.push((*filter, *params)),
+        }
+    }
+    drift.missing = config
+        .iter()
+        .filter(|(filter, _)| !current.contains_key(filter))
+        .map(|(filter, params)| (*filter, *params))
+        .collect();
+    drift
+}
+
+fn packet_filters_diff(
+    map: &HashMap<MapData, PacketFilter, PacketFilterParams>,
+    new_state: std::collections::HashMap<PacketFilter, PacketFilterParams>,
+) -> anyhow::Result<MapDiff<PacketFilter, PacketFilterParams>> {
+    // Due to a constraint of the aya api, there is no clean method for the maps and
+    // we don't get mutable access as iterator is read only.
+    let current = map.iter().collect::<Result<_, _>>()?;
+    // Filters with shortlived=1 do not get removed.
+    // This is to support dynamic ephemiral rules
+    // that may be produced by rate limiting, for example.
+    Ok(diff(current, new_state, |params| params.shortlived == 1))
+}
+
+fn file_rules_diff(
+    map: &HashMap<MapData, ProfileKey, Profile>,
+    new_state: std::collections::HashMap<ProfileKey, Profile>,
+) -> anyhow::Result<MapDiff<ProfileKey, Profile>> {
+    let current = map.iter().collect::<Result<_, _>>()?;
+    Ok(diff(current, new_state, |_| false))
+}
+
+/// Computes the changes needed to go from `current` to `new_state`.
+///
+/// Entries for which `preserve` returns true are never removed.
+fn diff<K, V>(
+    current: std::collections::HashMap<K, V>,
+    new_state: std::collections::HashMap<K, V>,
+    preserve: impl Fn(&V) -> bool,
+) -> MapDiff<K, V>
+where
+    K: Copy + Eq + std::hash::Hash,
+    V: PartialEq,
+{
+    let remove = current
+        .iter()
+        .filter(|(key, value)| !new_state.contains_key(key) && !preserve(value))
+        .map(|(key, _)| *key)
+        .collect();
+    let insert = new_state
+        .into_iter()
+        .filter(|(key, value)| current.get(key) != Some(value))
+        .collect();
+    MapDiff { insert, remove }
+}
+
+/// Returns the filters that have expired at the given time.
+fn expired<K: Copy>(
+    current: &std::collections::HashMap<K, PacketFilterParams>,
+    now: u32,
+) -> Vec<K> {
+    current
+        .iter()
+        .filter(|(_, params)| params.is_expired(now))
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Returns the expiry of a filter added at `now` with the given time to live.
+fn expires_at(now: u32, ttl: Duration) -> u32 {
+    // Round up so that a filter is never dropped early and never gets 0,
+    // which would mean that it never expires.
+    let ttl = ttl.as_secs() as u32 + u32::from(ttl.subsec_nanos() > 0);
+    now.saturating_add(ttl.max(1))
+}
+
+/// Returns the time of the monotonic clock in seconds, which is the
+/// clock that the eBPF programs use.
+fn monotonic_secs() -> anyhow::Result<u32> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(ts.tv_sec as u32)
+}
+
+/// Returns the trie key of the given subnet.
+///
+/// The trie matches prefixes on the bytes of the key, so the address
+/// is stored in network byte order.
+fn subnet_key(cidr: Ipv4Net) -> Key<u32> {
+    Key::new(
+        cidr.prefix_len() as u32,
+        u32::from_ne_bytes(cidr.network().octets()),
+    )
+}
+
+/// Returns the subnet of the given trie key, which is the inverse of [`subnet_key`].
+fn subnet_from_key(key: &Key<u32>) -> anyhow::Result<Ipv4Net> {
+    let network = std::net::Ipv4Addr::from(key.data().to_ne_bytes());
+    Ok(Ipv4Net::new(network, key.prefix_len() as u8)?)
+}
+
+/// Reads the executable file and the rules of the given profile from disk.
+async fn read_file_rules(
+    profile: &crate::map::Profile,
+) -> anyhow::Result<(File, Vec<lightning_ebpf_common::FileRule>)> {
+    let exec_path = profile.name.as_ref().unwrap_or(&GLOBAL_PROFILE);
+    let (exec, _) = file_from_path(exec_path).await?;
+    let mut rules = Vec::with_capacity(profile.file_rules.len());
+    for rule in profile.file_rules.iter() {
+        let (_, is_dir) = file_from_path(&rule.file).await?;
+
+        let mut vector = vec![0u8; MAX_BUFFER_LEN];
+        let path = rule.file.as_path().display().to_string();
 
-            debug!("path {path} for profile {}", exec_path.display());
+        debug!("path {path} for profile {}", exec_path.display());
 
-            file_open_rules[i].path = vector.try_into().expect("Size is hardcoded");
-            file_open_rules[i].is_dir = if is_dir {
+        vector[..path.len()].copy_from_slice(path.as_bytes());
+
+        rules.push(lightning_ebpf_common::FileRule {
+            path: vector.try_into().expect("Size is hardcoded"),
+            is_dir: if is_dir {
                 FileRule::IS_DIR
             } else {
                 FileRule::IS_FILE
-            };
-            file_open_rules[i].permissions = rule.permissions;
+            },
+            permissions: rule.permissions,
+        });
+    }
+
+    Ok((exec, rules))
+}
+
+/// Splits the rules into chunks of `MAX_FILE_RULES` rules.
+///
+/// There is always at least one chunk so that the kernel can tell
+/// that a profile exists for the executable.
+fn profile_chunks(
+    exec: File,
+    rules: Vec<lightning_ebpf_common::FileRule>,
+) -> anyhow::Result<Vec<(ProfileKey, Profile)>> {
+    let max_rules = MAX_FILE_RULES * MAX_PROFILE_CHUNKS as usize;
+    if rules.len() > max_rules {
+        bail!("path maximum {max_rules} exceeded");
+    }
+
+    let mut chunks = Vec::new();
+    let mut rules = rules.into_iter().peekable();
+    loop {
+        let mut chunk = [lightning_ebpf_common::FileRule::default(); MAX_FILE_RULES];
+        for (slot, rule) in chunk.iter_mut().zip(rules.by_ref()) {
+            *slot = rule;
         }
+        chunks.push((
+            ProfileKey::new(exec, chunks.len() as u64),
+            Profile { rules: chunk },
+        ));
 
-        let rules: [lightning_ebpf_common::FileRule; MAX_FILE_RULES] =
-            file_open_rules.try_into().expect("Vec len is hardcoded");
+        if rules.peek().is_none() {
+            break;
+        }
+    }
 
-        let mut maps = self.file_open_rules.lock().await;
-        maps.insert(&exec, Profile { rules }, 0)?;
+    Ok(chunks)
+}
 
-        Ok(())
+/// Reassembles the rules of each executable from its profile chunks,
+/// which is the inverse of [`profile_chunks`].
+fn merge_profile_chunks(chunks: Vec<(ProfileKey, Profile)>) -> Vec<(File, Vec<FileRule>)> {
+    let mut profiles = std::collections::HashMap::<File, Vec<(u64, Profile)>>::new();
+    for (key, profile) in chunks {
+        profiles
+            .entry(key.file)
+            .or_default()
+            .push((key.chunk, profile));
     }
+
+    let mut result = profiles
+        .into_iter()
+        .map(|(file, mut chunks)| {
+            chunks.sort_by_key(|(chunk, _)| *chunk);
+            let rules = chunks
+                .into_iter()
+                .flat_map(|(_, profile)| profile.rules)
+                // Unused slots of a chunk have an empty path.
+                .filter(|rule| rule.path[0] != 0)
+                .collect();
+            (file, rules)
+        })
+        .collect::<Vec<_>>();
+    result.sort_by_key(|(file, _)| (file.dev, file.inode));
+    result
 }
 
 async fn file_from_path(path: &PathBuf) -> anyhow::Result<(File, bool)> {
@@ -212,5 +726,391 @@ async fn file_from_path(path: &PathBuf) -> anyhow::Result<(File, bool)> {
     let metadata = file.metadata().await?;
     let is_dir = metadata.is_dir();
     let inode = metadata.ino();
-    Ok((File::new(inode), is_dir))
+    Ok((File::new(inode, kernel_dev(metadata.dev())), is_dir))
+}
+
+/// Converts a device ID as reported by `stat` into the encoding
+/// used internally by the kernel, which is what the eBPF programs see.
+fn kernel_dev(dev: u64) -> u64 {
+    let major = libc::major(dev) as u64;
+    let minor = libc::minor(dev) as u64;
+    (major << 20) | minor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(i: usize) -> lightning_ebpf_common::FileRule {
+        let path = format!("/tmp/file{i}");
+        let mut rule = lightning_ebpf_common::FileRule::default();
+        rule.path[..path.len()].copy_from_slice(path.as_bytes());
+        rule.permissions = FileRule::OPEN_MASK;
+        rule
+    }
+
+    #[test]
+    fn test_profile_chunks() {
+        let exec = File::new(1, 0);
+        let rules = (0..50).map(rule).collect::<Vec<_>>();
+
+        let chunks = profile_chunks(exec, rules.clone()).unwrap();
+        assert_eq!(chunks.len(), 3);
+
+        let mut installed = Vec::new();
+        for (i, (key, profile)) in chunks.iter().enumerate() {
+            assert_eq!(*key, ProfileKey::new(exec, i as u64));
+            installed.extend(
+                profile
+                    .rules
+                    .iter()
+                    .filter(|rule| rule.permissions != FileRule::NO_OPERATION)
+                    .map(|rule| rule.path),
+            );
+        }
+        assert_eq!(
+            installed,
+            rules.iter().map(|rule| rule.path).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_merge_profile_chunks() {
+        let exec1 = File::new(1, 0);
+        let exec2 = File::new(2, 0);
+        let rules1 = (0..50).map(rule).collect::<Vec<_>>();
+        let rules2 = (0..3).map(rule).collect::<Vec<_>>();
+
+        let mut chunks = profile_chunks(exec1, rules1.clone()).unwrap();
+        chunks.extend(profile_chunks(exec2, rules2.clone()).unwrap());
+        chunks.extend(profile_chunks(File::new(3, 0), Vec::new()).unwrap());
+        // The kernel map does not preserve the insertion order.
+        chunks.reverse();
+
+        let merged = merge_profile_chunks(chunks);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0], (exec1, rules1));
+        assert_eq!(merged[1], (exec2, rules2));
+        assert!(merged[2].1.is_empty());
+    }
+
+    #[test]
+    fn test_profile_chunks_empty_profile() {
+        let exec = File::new(1, 0);
+        let chunks = profile_chunks(exec, Vec::new()).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].0, ProfileKey::new(exec, 0));
+    }
+
+    #[test]
+    fn test_profile_chunks_limit() {
+        let exec = File::new(1, 0);
+        let rules = (0..MAX_FILE_RULES * MAX_PROFILE_CHUNKS as usize + 1)
+            .map(rule)
+            .collect::<Vec<_>>();
+        assert!(profile_chunks(exec, rules).is_err());
+    }
+
+    #[test]
+    fn test_write_deny() {
+        let mut rule = rule(0);
+        rule.permissions = FileRule::OPEN_MASK | FileRule::READ_MASK;
+
+        let read = FileRule::required_permissions(FileRule::FMODE_READ);
+        let write = FileRule::required_permissions(FileRule::FMODE_READ | FileRule::FMODE_WRITE);
+        assert!(rule.allows(read));
+        assert!(!rule.allows(write));
+
+        rule.permissions |= FileRule::WRITE_MASK;
+        assert!(rule.allows(write));
+    }
+
+    #[test]
+    fn test_exec_deny() {
+        let mut rule = rule(0);
+        rule.permissions = FileRule::OPEN_MASK | FileRule::READ_MASK;
+
+        let exec = FileRule::required_permissions(FileRule::FMODE_READ | FileRule::FMODE_EXEC);
+        assert!(!rule.allows(exec));
+
+        rule.permissions |= FileRule::EXEC_MASK;
+        assert!(rule.allows(exec));
+    }
+
+    #[test]
+    fn test_open_only_rule_allows_everything() {
+        let rule = rule(0);
+        assert_eq!(rule.permissions, FileRule::OPEN_MASK);
+
+        let read_only = FileRule::required_permissions(FileRule::FMODE_READ);
+        let write = FileRule::required_permissions(FileRule::FMODE_READ | FileRule::FMODE_WRITE);
+        let exec = FileRule::required_permissions(FileRule::FMODE_READ | FileRule::FMODE_EXEC);
+        assert!(rule.allows(read_only));
+        assert!(rule.allows(write));
+        assert!(rule.allows(exec));
+    }
+
+    #[test]
+    fn test_diff_preserves_shortlived() {
+        let params = |shortlived| PacketFilterParams {
+            trigger_event: 0,
+            shortlived,
+            action: PacketFilterRule::DROP,
+            expires_at: 0,
+        };
+        let filter = |port| PacketFilter {
+            ip: 0,
+            port,
+            proto: PacketFilterRule::TCP,
+        };
+
+        let current = std::collections::HashMap::from([
+            (filter(1), params(0)),
+            (filter(2), params(1)),
+            (filter(3), params(0)),
+        ]);
+        let new_state =
+            std::collections::HashMap::from([(filter(3), params(0)), (filter(4), params(0))]);
+
+        let diff = diff(current, new_state, |params| params.shortlived == 1);
+        assert!(diff.remove == vec![filter(1)]);
+        assert_eq!(diff.insert.len(), 1);
+        assert!(diff.insert[0] == (filter(4), params(0)));
+    }
+
+    // Mirrors the longest-prefix match of the kernel on the lookup key of the XDP program.
+    fn subnet_contains(key: &Key<u32>, ip: std::net::Ipv4Addr) -> bool {
+        let data = key.data().to_ne_bytes();
+        let lookup = u32::from_be_bytes(ip.octets()).to_be().to_ne_bytes();
+        (0..key.prefix_len() as usize)
+            .all(|bit| (data[bit / 8] ^ lookup[bit / 8]) & (0x80 >> (bit % 8)) == 0)
+    }
+
+    #[test]
+    fn test_expired_shortlived_filter() {
+        let filter = |port| PacketFilter {
+            ip: 0,
+            port,
+            proto: PacketFilterRule::TCP,
+        };
+        let params = |expires_at| PacketFilterParams {
+            trigger_event: 1,
+            shortlived: 1,
+            action: PacketFilterRule::DROP,
+            expires_at,
+        };
+
+        let now = monotonic_secs().unwrap();
+        let mut current = std::collections::HashMap::from([
+            (filter(1), params(expires_at(now, Duration::from_secs(1)))),
+            (filter(2), params(0)),
+        ]);
+        assert!(expired(&current, monotonic_secs().unwrap()).is_empty());
+
+        std::thread::sleep(Duration::from_secs(2));
+        for filter in expired(&current, monotonic_secs().unwrap()) {
+            current.remove(&filter);
+        }
+        assert_eq!(current.len(), 1);
+        assert!(current.contains_key(&filter(2)));
+    }
+
+    #[test]
+    fn test_expires_at_rounds_up() {
+        assert_eq!(expires_at(10, Duration::from_secs(1)), 11);
+        assert_eq!(expires_at(10, Duration::from_millis(1500)), 12);
+        assert_eq!(expires_at(10, Duration::ZERO), 11);
+        assert_eq!(expires_at(u32::MAX, Duration::from_secs(1)), u32::MAX);
+    }
+
+    #[test]
+    fn test_valid_actions() {
+        for action in [
+            PacketFilterRule::ABORTED,
+            PacketFilterRule::DROP,
+            PacketFilterRule::PASS,
+            PacketFilterRule::TX,
+            PacketFilterRule::REDIRECT,
+        ] {
+            assert!(PacketFilterRule::is_valid_action(action));
+        }
+        assert!(!PacketFilterRule::is_valid_action(
+            PacketFilterRule::REDIRECT + 1
+        ));
+    }
+
+    #[test]
+    fn test_subnet_rule_covers_range() {
+        let key = subnet_key("10.1.2.0/24".parse().unwrap());
+        assert_eq!(key.prefix_len(), 24);
+
+        for host in 0..=255 {
+            assert!(subnet_contains(&key, [10, 1, 2, host].into()));
+        }
+        assert!(!subnet_contains(&key, [10, 1, 3, 0].into()));
+        assert!(!subnet_contains(&key, [10, 1, 1, 255].into()));
+        assert!(!subnet_contains(&key, [11, 1, 2, 0].into()));
+
+        let params = SubnetFilterParams {
+            port: 0,
+            proto: u16::MAX,
+            extra: PacketFilterParams {
+                trigger_event: 0,
+                shortlived: 0,
+                action: PacketFilterRule::DROP,
+                expires_at: 0,
+            },
+        };
+        assert!(params.matches(443, PacketFilterRule::TCP));
+        assert!(params.matches(53, PacketFilterRule::UDP));
+    }
+
+    #[test]
+    fn test_reconcile_flags_unmanaged_rule() {
+        let params = |shortlived| PacketFilterParams {
+            trigger_event: 0,
+            shortlived,
+            action: PacketFilterRule::DROP,
+            expires_at: 0,
+        };
+        let filter = |port| PacketFilter {
+            ip: 0,
+            port,
+            proto: PacketFilterRule::TCP,
+        };
+
+        let config = std::collections::HashMap::from([
+            (filter(1), params(0)),
+            (filter(2), params(0)),
+            (filter(3), params(0)),
+        ]);
+        let mut changed = params(0);
+        changed.action = PacketFilterRule::PASS;
+        let current = std::collections::HashMap::from([
+            (filter(1), params(0)),
+            (filter(2), changed),
+            // Inserted manually, bypassing the config.
+            (filter(4), params(0)),
+            (filter(5), params(1)),
+        ]);
+
+        let shortlived = |params: &PacketFilterParams| params.shortlived == 1;
+        let report = drift(current, &config, shortlived);
+        assert!(!report.is_empty());
+        assert!(report.unmanaged == vec![(filter(4), params(0))]);
+        assert!(report.missing == vec![(filter(3), params(0))]);
+        assert!(report.changed == vec![(filter(2), changed, params(0))]);
+
+        let report = drift(config.clone(), &config, shortlived);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_flags_unmanaged_ipv6_rule() {
+        let params = |shortlived| PacketFilterParams {
+            trigger_event: 0,
+            shortlived,
+            action: PacketFilterRule::DROP,
+            expires_at: 0,
+        };
+        let filter = |port| PacketFilter6 {
+            ip: std::net::Ipv6Addr::LOCALHOST.octets(),
+            port,
+            proto: PacketFilterRule::TCP,
+        };
+
+        // The config has no IPv6 filters.
+        let config = std::collections::HashMap::new();
+        let current =
+            std::collections::HashMap::from([(filter(1), params(0)), (filter(2), params(1))]);
+
+        let report = drift(current, &config, |params| params.shortlived == 1);
+        assert!(!report.is_empty());
+        assert!(report.unmanaged == vec![(filter(1), params(0))]);
+        assert!(report.missing.is_empty());
+        assert!(report.changed.is_empty());
+
+        let current = std::collections::HashMap::from([(filter(2), params(1))]);
+        let report = drift(current, &config, |params| params.shortlived == 1);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_flags_unmanaged_subnet_rule() {
+        let params = |shortlived| SubnetFilterParams {
+            port: 0,
+            proto: PacketFilterRule::ANY_PROTO,
+            extra: PacketFilterParams {
+                trigger_event: 0,
+                shortlived,
+                action: PacketFilterRule::DROP,
+                expires_at: 0,
+            },
+        };
+        let subnet = |cidr: &str| -> Ipv4Net { cidr.parse().unwrap() };
+
+        // Subnets are read back from the keys of the trie.
+        for cidr in ["10.1.2.0/24", "192.168.0.0/16", "0.0.0.0/0", "1.2.3.4/32"] {
+            assert_eq!(
+                subnet_from_key(&subnet_key(subnet(cidr))).unwrap(),
+                subnet(cidr)
+            );
+        }
+
+        // The config has no subnet filters.
+        let config = std::collections::HashMap::new();
+        let current = std::collections::HashMap::from([
+            (subnet("10.1.2.0/24"), params(0)),
+            (subnet("10.2.0.0/16"), params(1)),
+        ]);
+
+        let report = drift(current, &config, |params| params.extra.shortlived == 1);
+        assert!(!report.is_empty());
+        assert!(report.unmanaged == vec![(subnet("10.1.2.0/24"), params(0))]);
+        assert!(report.missing.is_empty());
+        assert!(report.changed.is_empty());
+
+        let current = std::collections::HashMap::from([(subnet("10.2.0.0/16"), params(1))]);
+        let report = drift(current, &config, |params| params.extra.shortlived == 1);
+        assert!(report.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_file_rules_across_devices() {
+        // `/dev/shm` is a tmpfs mount on most systems, while the
+        // temporary directory may or may not live on a different one.
+        let dirs = [std::env::temp_dir(), PathBuf::from("/dev/shm")];
+        let mut file_rules = Vec::new();
+        let mut devs = Vec::new();
+        for (i, dir) in dirs.iter().enumerate() {
+            if !dir.is_dir() {
+                return;
+            }
+            let path = dir.join(format!("lightning-guard-test-{}-{i}", std::process::id()));
+            std::fs::write(&path, b"").unwrap();
+            devs.push(kernel_dev(std::fs::metadata(&path).unwrap().dev()));
+            file_rules.push(crate::map::FileRule {
+                file: path.try_into().unwrap(),
+                permissions: FileRule::OPEN_MASK,
+            });
+        }
+
+        let profile = crate::map::Profile {
+            name: Some(file_rules[0].file.to_path_buf()),
+            file_rules: file_rules.clone(),
+            audit: false,
+        };
+        let result = read_file_rules(&profile).await;
+
+        for rule in &file_rules {
+            let _ = std::fs::remove_file(rule.file.as_path());
+        }
+
+        let (exec, rules) = result.unwrap();
+        assert_eq!(exec.dev, devs[0]);
+        assert_eq!(rules.len(), 2);
+        assert!(rules
+            .iter()
+            .all(|rule| rule.permissions == FileRule::OPEN_MASK));
+    }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::{anyhow, bail};
 use aya::maps::{MapData, RingBuf};
 use lightning_ebpf_common::{EventMessage, EVENT_MESSAGE_LEN};
@@ -14,6 +16,9 @@
 use crate::map::SharedMap;
 use crate::utils;
 
+/// Interval at which expired short-lived packet filters are removed.
+const EXPIRED_FILTERS_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct Server {
     listener: UnixListener,
     shared_state: SharedMap,
@@ -101,8 +106,15 @@ pub async fn start(mut self) -> anyhow::Result<()> {
         self.shared_state.update_packet_filters().await?;
         self.shared_state.update_all_file_rules().await?;
 
+        let mut sweep = tokio::time::interval(EXPIRED_FILTERS_SWEEP_INTERVAL);
+
         loop {
             tokio::select! {
+                _ = sweep.tick() => {
+                    if let Err(e) = self.shared_state.remove_expired_packet_filters().await {
+                        error!("failed to remove expired packet filters: {e:?}");
+                    }
+                }
                 next = self.listener.accept() => {
                     match next {
                         Ok((stream, _addr)) => {
@@ -106,6 +106,7 @@ fn update_filters_from_input(&mut self) -> Result<()> {
             .to_lowercase()
             .as_str()
         {
+            "icmp" => PacketFilterRule::ICMP,
             "tcp" => PacketFilterRule::TCP,
             "udp" => PacketFilterRule::UDP,
             "any" => PacketFilterRule::ANY_PROTO,
@@ -82,6 +82,9 @@ fn update_filters_from_input(&mut self) -> Result<()> {
             if op == FileRule::WRITE_SYMBOL {
                 permissions |= FileRule::WRITE_MASK;
             }
+            if op == FileRule::EXEC_SYMBOL {
+                permissions |= FileRule::EXEC_MASK;
+            }
         }
 
         self.buf.replace(FileRule {
@@ -18,6 +18,25 @@ pub struct ShutdownController {
     backtrace_list: BacktraceList,
 }
 
+/// The error returned by [ShutdownController::wait_for_completion_timeout] when some of the
+/// shutdown futures did not drop in time.
+#[derive(Debug)]
+pub struct StillPendingError {
+    /// The number of shutdown futures that were still pending.
+    pub pending: usize,
+    /// The formatted backtraces of the pending futures. Only present if the controller was
+    /// created with backtrace capturing enabled.
+    pub backtraces: Option<Vec<String>>,
+}
+
+impl std::fmt::Display for StillPendingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} shutdown futures are still pending", self.pending)
+    }
+}
+
+impl std::error::Error for StillPendingError {}
+
 /// A permit to trigger shutdown on a remote [ShutdownController]. This is obtained by calling
 /// [ShutdownController::permit].
 #[derive(Clone)]
@@ -78,6 +97,11 @@ pub fn trigger_shutdown(&self) {
         self.inner.trigger_shutdown()
     }
 
+    /// Returns true if the shutdown has already been triggered.
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.is_shutdown()
+    }
+
     /// Returns a future that is resolved as soon as all of the futures waiting for shutdown
     /// have dropped.
     pub fn wait_for_completion(&self) -> CompletionFuture {
@@ -85,6 +109,40 @@ pub fn wait_for_completion(&self) -> CompletionFuture {
         CompletionFuture::new(&self.inner)
     }
 
+    /// Like [wait_for_completion](Self::wait_for_completion) but gives up after the provided
+    /// duration. On timeout the error contains the number of futures that are still pending and
+    /// their backtraces if backtrace capturing is enabled.
+    ///
+    /// Expects to be called in a tokio runtime.
+    pub async fn wait_for_completion_timeout(
+        &mut self,
+        duration: Duration,
+    ) -> Result<(), StillPendingError> {
+        if tokio::time::timeout(duration, self.wait_for_completion())
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        let pending = self.pending_count();
+        let backtraces = self
+            .pending_backtraces()
+            .map(|iter| iter.map(|trace| format!("{trace:#?}")).collect());
+
+        Err(StillPendingError {
+            pending,
+            backtraces,
+        })
+    }
+
+    /// Returns the number of shutdown futures that are currently pending. Unlike
+    /// [pending_backtraces](Self::pending_backtraces) this is cheap and works regardless of the
+    /// backtrace capturing setting.
+    pub fn pending_count(&self) -> usize {
+        self.inner.pending_futures()
+    }
+
     /// Returns an iterator over all of the currently pending backtraces. This is a very expensive
     /// operation.
     pub fn pending_backtraces(&mut self) -> Option<BacktraceListIter> {
@@ -106,6 +164,15 @@ pub fn pending_backtraces(&mut self) -> Option<BacktraceListIter> {
     ///
     /// Expect this method to be removed in future.
     pub async fn shutdown(&mut self) {
+        self.shutdown_with(Duration::from_secs(5), 2).await
+    }
+
+    /// Trigger the shutdown and wait for it to complete, checking on the progress every
+    /// `poll_interval`. After `escalate_after` intervals have passed without the shutdown being
+    /// complete the backtrace of the pending futures is printed on every interval.
+    ///
+    /// Expects to be called in a tokio runtime.
+    pub async fn shutdown_with(&mut self, poll_interval: Duration, escalate_after: usize) {
         self.trigger_shutdown();
 
         for i in 0.. {
@@ -114,20 +181,18 @@ pub async fn shutdown(&mut self) {
                 _ = self.wait_for_completion() => {
                     return;
                 },
-                _ = tokio::time::sleep(Duration::from_secs(5)) => {
-                    match i {
-                        0 => {
-                            tracing::trace!("Still shutting down...");
-                            continue;
-                        },
-                        1 => {
-                            tracing::warn!("Still shutting down...");
-                            continue;
-                        },
-                        _ => {
-                            tracing::error!("Shutdown taking too long")
+                _ = tokio::time::sleep(poll_interval) => {
+                    let pending = self.pending_count();
+                    if i < escalate_after {
+                        if i == 0 {
+                            tracing::trace!(pending, "Still shutting down...");
+                        } else {
+                            tracing::warn!(pending, "Still shutting down...");
                         }
+                        continue;
                     }
+
+                    tracing::error!(pending, "Shutdown taking too long")
                 }
             }
 
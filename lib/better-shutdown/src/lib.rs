@@ -13,7 +13,7 @@
 
 pub use backtrace_list::BacktraceListIter;
 pub use completion_fut::CompletionFuture;
-pub use controller::ShutdownController;
+pub use controller::{ShutdownController, StillPendingError};
 pub use owned_signal_fut::OwnedShutdownSignal;
 pub use signal_fut::ShutdownSignal;
 pub use waiter::ShutdownWaiter;
@@ -58,7 +58,12 @@ fn poll(
         };
 
         let mut wait_list = wait_list_mutex.lock().unwrap();
-        wait_list.poll(&mut this.list_position, cx)
+        let was_registered = this.list_position.is_some();
+        let result = wait_list.poll(&mut this.list_position, cx);
+        if !was_registered && this.list_position.is_some() {
+            this.state.increment_pending_futures();
+        }
+        result
     }
 }
 
@@ -75,6 +80,7 @@ fn drop(&mut self) {
 
             let mut wait_list = wait_list_mutex.lock().expect("wait_list lock poisoned");
             wait_list.deregister(slot);
+            self.state.decrement_pending_futures();
             if wait_list.is_done() {
                 self.state.decrement_pending_wait_list_count();
             }
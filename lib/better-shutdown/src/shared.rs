@@ -1,5 +1,5 @@
 use std::ops::Deref;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Mutex;
 
 use triomphe::Arc;
@@ -15,10 +15,16 @@ pub(crate) struct SharedState {
     /// Is set to true if we have to force capture backtraces.
     pub capture_backtrace: bool,
 
+    /// Set to true once `trigger_shutdown` is called.
+    pub is_shutdown: AtomicBool,
+
     /// Useless before `trigger_shutdown` is called. But at that stage it is used to keep track
     /// of the number of 'frozen' wait lists that were not empty.
     pub pending_waiting_lists: AtomicUsize,
 
+    /// The number of shutdown signal futures that are currently registered in a wait list.
+    pub pending_futures: AtomicUsize,
+
     /// A series of shared wait lists that are by default used by `ShutdownWaiter`'s that are not
     /// marked as dedicated.
     pub wait_list_shards: [Mutex<WaitList>; NUM_SHARED_SHARDS],
@@ -47,7 +53,9 @@ pub fn new(capture_backtrace: bool) -> Self {
 
         Self {
             capture_backtrace,
+            is_shutdown: AtomicBool::new(false),
             pending_waiting_lists: AtomicUsize::new(0),
+            pending_futures: AtomicUsize::new(0),
             wait_list_shards,
             dedicated_wait_lists: Mutex::new(DedicatedWaitListState {
                 did_shutdown: false,
@@ -67,6 +75,24 @@ pub fn decrement_pending_wait_list_count(&self) {
         }
     }
 
+    /// Should be called when a shutdown signal future registers itself in a wait list.
+    #[inline]
+    pub fn increment_pending_futures(&self) {
+        self.pending_futures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Should be called when a registered shutdown signal future is dropped.
+    #[inline]
+    pub fn decrement_pending_futures(&self) {
+        self.pending_futures.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of shutdown signal futures that are currently registered.
+    #[inline]
+    pub fn pending_futures(&self) -> usize {
+        self.pending_futures.load(Ordering::Relaxed)
+    }
+
     /// Create a new dedicated wait list. This function might return `None` if called during an
     /// active `trigger_shutdown` call.
     pub fn new_dedicated_wait_list(&self) -> Option<Arc<Mutex<WaitList>>> {
@@ -103,6 +129,7 @@ pub fn trigger_shutdown(&self) {
 
         // Tell the `new_dedicated_wait_list` to return `None` at this point.
         dedicated_list_guard.did_shutdown = true;
+        self.is_shutdown.store(true, Ordering::Release);
 
         for wait_list_mutex in self
             .wait_list_shards
@@ -134,6 +161,12 @@ pub fn trigger_shutdown(&self) {
         }
     }
 
+    /// Returns true if `trigger_shutdown` has already been called.
+    #[inline]
+    pub fn is_shutdown(&self) -> bool {
+        self.is_shutdown.load(Ordering::Acquire)
+    }
+
     /// Collect and update the given backtrace list.
     pub fn collect_pending_backtrace(&self, list: &mut BacktraceList) {
         let dedicated_list_guard = self.dedicated_wait_lists.lock().unwrap();
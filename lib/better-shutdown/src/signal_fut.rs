@@ -47,7 +47,12 @@ fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Out
         });
 
         let mut wait_list = shard.lock().unwrap();
-        wait_list.poll(&mut this.list_position, cx)
+        let was_registered = this.list_position.is_some();
+        let result = wait_list.poll(&mut this.list_position, cx);
+        if !was_registered && this.list_position.is_some() {
+            this.state.increment_pending_futures();
+        }
+        result
     }
 }
 
@@ -56,6 +61,7 @@ fn drop(&mut self) {
         if let Some(slot) = self.list_position.take() {
             let mut wait_list = self.shard.unwrap().lock().expect("wait_list lock poisoned");
             wait_list.deregister(slot);
+            self.state.decrement_pending_futures();
             if wait_list.is_done() {
                 self.state.decrement_pending_wait_list_count();
             }
@@ -5,7 +5,7 @@
 use futures::Future;
 use triomphe::Arc;
 
-use crate::shared::{SharedState, NUM_SHARED_SHARDS};
+use crate::shared::SharedState;
 use crate::wait_list::WaitList;
 use crate::{OwnedShutdownSignal, ShutdownSignal};
 
@@ -54,16 +54,11 @@ pub fn mark_busy(&mut self) {
         self.dedicated = self.inner.new_dedicated_wait_list();
     }
 
-    /// Returns true if we have already shutdown.
+    /// Returns true if we have already shutdown. This does not register anything in the wait
+    /// lists and is cheap enough to be called in hot loops.
+    #[inline]
     pub fn is_shutdown(&self) -> bool {
-        let ptr = self as *const _ as *const () as usize as u64;
-        let shard = self.dedicated.as_deref().unwrap_or_else(|| {
-            let out = fxhash::hash32(&ptr) % (NUM_SHARED_SHARDS as u32);
-            &self.inner.wait_list_shards[out as usize]
-        });
-
-        let wait_list = shard.lock().unwrap();
-        wait_list.is_closed()
+        self.inner.is_shutdown()
     }
 
     /// Standalone function to wait until the shutdown signal is received.
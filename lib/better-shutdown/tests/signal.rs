@@ -0,0 +1,43 @@
+//! Lives in its own test binary, since the signal is delivered to the whole process.
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use better_shutdown::ShutdownController;
+use tokio::signal::unix::{signal, SignalKind};
+
+fn send_signal(signal: libc::c_int) {
+    let result = unsafe { libc::kill(libc::getpid(), signal) };
+    assert_eq!(result, 0, "{}", std::io::Error::last_os_error());
+}
+
+#[tokio::test]
+async fn test_sigterm_triggers_graceful_shutdown() {
+    // Register a listener of our own first, so the default disposition of the signal (killing
+    // the process) is replaced before we send anything.
+    let _guard = signal(SignalKind::terminate()).unwrap();
+
+    let ctrl = ShutdownController::new(false);
+    ctrl.install_ctrlc_handlers();
+
+    let waiter = ctrl.waiter();
+    let task = tokio::spawn(async move {
+        waiter.wait_for_shutdown().await;
+    });
+
+    // The handlers are installed from a spawned task, so keep sending the signal until it is
+    // picked up.
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !ctrl.is_shutdown() {
+            send_signal(libc::SIGTERM);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("SIGTERM did not trigger the shutdown.");
+
+    tokio::time::timeout(Duration::from_secs(5), ctrl.wait_for_completion())
+        .await
+        .expect("Shutdown did not complete.");
+    task.await.unwrap();
+}
@@ -384,3 +384,92 @@ fn test_17() {
     assert_eq!(counter.get(), 1);
     assert_eq!(result, Poll::Ready(()));
 }
+
+#[tokio::test]
+async fn test_18() {
+    let mut ctrl = ShutdownController::new(true);
+    let waiter = ctrl.waiter();
+    let (_, waker) = new_waker();
+    let mut future = waiter.wait_for_shutdown_owned();
+    let _ = future.poll_unpin(&mut Context::from_waker(&waker));
+
+    // drop the pending future after a few poll intervals have passed.
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(future);
+    });
+
+    tokio::time::timeout(
+        Duration::from_secs(1),
+        ctrl.shutdown_with(Duration::from_millis(10), 1),
+    )
+    .await
+    .expect("shutdown should complete once the pending future is dropped");
+}
+
+#[test]
+fn test_19() {
+    let ctrl = ShutdownController::new(false);
+    let waiter = ctrl.waiter();
+    let mut busy_waiter = ctrl.waiter();
+    busy_waiter.mark_busy();
+    assert!(!ctrl.is_shutdown());
+    assert!(!waiter.is_shutdown());
+    assert!(!busy_waiter.is_shutdown());
+
+    ctrl.trigger_shutdown();
+    assert!(ctrl.is_shutdown());
+    assert!(waiter.is_shutdown());
+    assert!(busy_waiter.is_shutdown());
+}
+
+#[tokio::test]
+async fn test_20() {
+    let mut ctrl = ShutdownController::new(true);
+    let waiter = ctrl.waiter();
+    let (_, waker) = new_waker();
+    let mut future = waiter.wait_for_shutdown_owned();
+    let _ = future.poll_unpin(&mut Context::from_waker(&waker));
+
+    // the future is never dropped.
+    ctrl.trigger_shutdown();
+    let err = ctrl
+        .wait_for_completion_timeout(Duration::from_millis(10))
+        .await
+        .expect_err("completion should time out");
+    assert_eq!(err.pending, 1);
+    assert_eq!(err.backtraces.map(|traces| traces.len()), Some(1));
+
+    drop(future);
+    assert!(ctrl
+        .wait_for_completion_timeout(Duration::from_millis(10))
+        .await
+        .is_ok());
+}
+
+#[test]
+fn test_21() {
+    let ctrl = ShutdownController::new(false);
+    assert_eq!(ctrl.pending_count(), 0);
+
+    let waiter = ctrl.waiter();
+    let mut busy_waiter = ctrl.waiter();
+    busy_waiter.mark_busy();
+    let (_, waker) = new_waker();
+
+    let mut future1 = waiter.wait_for_shutdown();
+    let mut future2 = busy_waiter.wait_for_shutdown_owned();
+    // futures are only counted once polled.
+    assert_eq!(ctrl.pending_count(), 0);
+    let _ = future1.poll_unpin(&mut Context::from_waker(&waker));
+    let _ = future2.poll_unpin(&mut Context::from_waker(&waker));
+    assert_eq!(ctrl.pending_count(), 2);
+    // polling again should not double count.
+    let _ = future1.poll_unpin(&mut Context::from_waker(&waker));
+    assert_eq!(ctrl.pending_count(), 2);
+
+    drop(future1);
+    assert_eq!(ctrl.pending_count(), 1);
+    drop(future2);
+    assert_eq!(ctrl.pending_count(), 0);
+}
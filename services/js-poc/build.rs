@@ -63,7 +63,7 @@ fn main() {
             DisabledNpmChecker,
             InMemorySysWrapper,
         >(None, memory_fs),
-        fleek::init_ops_and_esm(0),
+        fleek::init_ops_and_esm(0, Default::default(), Default::default()),
     ];
 
     let snapshot = deno_core::snapshot::create_snapshot(
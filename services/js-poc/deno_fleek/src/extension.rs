@@ -23,7 +23,8 @@
     run_task,
     TaskDepth,
 };
-use crate::permissions::Permissions;
+use crate::permissions::{NetAllowlist, Permissions};
+use crate::sandbox_fs::FsAccess;
 
 extension!(
     fleek,
@@ -77,10 +78,10 @@
         "ext:deno_fs/30_fs.js" = "30_fs.js",
         "ext:deno_process/40_process.js" = "40_process.js",
     ],
-    options = { depth: u8 },
+    options = { depth: u8, net_allowlist: NetAllowlist, fs_access: FsAccess },
     state = |state, config| {
         // initialize permissions
-        state.put(Permissions {});
+        state.put(Permissions::new(config.net_allowlist, config.fs_access));
         state.put(TaskDepth(config.depth));
     }
 );
@@ -1,11 +1,13 @@
 mod extension;
 mod ops;
 mod permissions;
+mod sandbox_fs;
 mod transpiler;
 
 pub mod in_memory_fs;
 pub mod node_traits;
 
 pub use extension::fleek;
-pub use permissions::Permissions;
+pub use permissions::{NetAllowlist, Permissions, NET_ALLOWLIST_ENV};
+pub use sandbox_fs::{FsAccess, FsSandboxConfig, SandboxFs, FS_ROOT_ENV, FS_WRITABLE_ENV};
 pub use transpiler::maybe_transpile_source;
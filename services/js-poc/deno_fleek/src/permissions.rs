@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::path::Path;
+use std::sync::Arc;
 
 use deno_core::url::Url;
 use deno_fetch::FetchPermissions;
@@ -11,18 +12,123 @@
 use deno_web::TimersPermission;
 use deno_websocket::WebSocketPermissions;
 
+use crate::sandbox_fs::FsAccess;
+
 pub const FETCH_BLACKLIST: &[&str] = &["localhost", "127.0.0.1", "::1"];
 
-pub struct Permissions {}
+/// The environment variable the network allowlist is read from, as a comma separated list of
+/// `host` or `host:port` entries.
+pub const NET_ALLOWLIST_ENV: &str = "FLEEK_JS_NET_ALLOWLIST";
+
+/// The hosts that services are allowed to connect to. An empty allowlist allows every host that
+/// is not blacklisted.
+#[derive(Clone, Debug, Default)]
+pub struct NetAllowlist {
+    entries: Arc<Vec<(String, Option<u16>)>>,
+}
+
+impl NetAllowlist {
+    /// Read the allowlist from [`NET_ALLOWLIST_ENV`].
+    pub fn from_env() -> Self {
+        std::env::var(NET_ALLOWLIST_ENV)
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    /// Parse a comma separated list of `host` or `host:port` entries. An entry without a port
+    /// allows every port of the host.
+    pub fn parse(value: &str) -> Self {
+        let entries = value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.rsplit_once(':') {
+                Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+                    match port.parse() {
+                        Ok(port) => (host.to_string(), Some(port)),
+                        Err(_) => (entry.to_string(), None),
+                    }
+                },
+                _ => (entry.to_string(), None),
+            })
+            .map(|(host, port)| {
+                let host = host.trim_start_matches('[').trim_end_matches(']');
+                (host.to_ascii_lowercase(), port)
+            })
+            .collect();
+        Self {
+            entries: Arc::new(entries),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns true if a connection to the host and port is allowed.
+    pub fn allows(&self, host: &str, port: Option<u16>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.entries.iter().any(|(allowed_host, allowed_port)| {
+            allowed_host.eq_ignore_ascii_case(host)
+                && (allowed_port.is_none() || *allowed_port == port)
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Permissions {
+    net_allowlist: NetAllowlist,
+    fs_access: FsAccess,
+}
 
 impl Permissions {
-    fn check_net(&mut self, host: &str) -> Result<(), PermissionCheckError> {
+    pub fn new(net_allowlist: NetAllowlist, fs_access: FsAccess) -> Self {
+        Self {
+            net_allowlist,
+            fs_access,
+        }
+    }
+
+    fn check_fs_read(&self) -> Result<(), PermissionCheckError> {
+        if self.fs_access == FsAccess::None {
+            Err(PermissionCheckError::PermissionDenied(
+                deno_permissions::PermissionDeniedError::Fatal {
+                    access: "not allowed".into(),
+                },
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_fs_write(&self) -> Result<(), PermissionCheckError> {
+        if self.fs_access != FsAccess::ReadWrite {
+            Err(PermissionCheckError::PermissionDenied(
+                deno_permissions::PermissionDeniedError::Fatal {
+                    access: "not allowed".into(),
+                },
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_net(&mut self, host: &str, port: Option<u16>) -> Result<(), PermissionCheckError> {
         if FETCH_BLACKLIST.contains(&host) {
             Err(PermissionCheckError::PermissionDenied(
                 deno_permissions::PermissionDeniedError::Fatal {
                     access: "blacklisted".into(),
                 },
             ))
+        } else if !self.net_allowlist.allows(host, port) {
+            Err(PermissionCheckError::PermissionDenied(
+                deno_permissions::PermissionDeniedError::Fatal {
+                    access: format!("{host} not allowed"),
+                },
+            ))
         } else {
             Ok(())
         }
@@ -30,7 +136,7 @@ fn check_net(&mut self, host: &str) -> Result<(), PermissionCheckError> {
 
     fn check_net_url(&mut self, url: &Url, _api_name: &str) -> Result<(), PermissionCheckError> {
         if let Some(host) = url.host_str() {
-            self.check_net(host)?;
+            self.check_net(host, url.port_or_known_default())?;
         }
         Ok(())
     }
@@ -76,16 +182,7 @@ fn check_net<T: AsRef<str>>(
         host: &(T, Option<u16>),
         _api_name: &str,
     ) -> std::result::Result<(), deno_permissions::PermissionCheckError> {
-        // Blacklist hosts
-        if FETCH_BLACKLIST.contains(&host.0.as_ref()) {
-            Err(PermissionCheckError::PermissionDenied(
-                deno_permissions::PermissionDeniedError::Fatal {
-                    access: format!("{} not allowed", host.0.as_ref()),
-                },
-            ))
-        } else {
-            Ok(())
-        }
+        Permissions::check_net(self, host.0.as_ref(), host.1)
     }
     fn check_read(
         &mut self,
@@ -133,50 +230,48 @@ impl FsPermissions for Permissions {
     fn check_open<'a>(
         &mut self,
         _resolved: bool,
-        _read: bool,
-        _write: bool,
-        _path: &'a Path,
+        read: bool,
+        write: bool,
+        path: &'a Path,
         _api_name: &str,
     ) -> Result<Cow<'a, Path>, FsError> {
-        unimplemented!()
+        if (read && self.fs_access == FsAccess::None)
+            || (write && self.fs_access != FsAccess::ReadWrite)
+        {
+            return Err(FsError::Io(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "not allowed",
+            )));
+        }
+        Ok(Cow::Borrowed(path))
     }
 
     fn check_read(
         &mut self,
-        _path: &str,
+        path: &str,
         _api_name: &str,
     ) -> std::result::Result<std::path::PathBuf, deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_read()?;
+        Ok(path.into())
     }
 
     fn check_read_path<'a>(
         &mut self,
-        _path: &'a Path,
+        path: &'a Path,
         _api_name: &str,
     ) -> std::result::Result<
         std::borrow::Cow<'a, std::path::Path>,
         deno_permissions::PermissionCheckError,
     > {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_read()?;
+        Ok(Cow::Borrowed(path))
     }
 
     fn check_read_all(
         &mut self,
         _api_name: &str,
     ) -> std::result::Result<(), deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_read()
     }
 
     fn check_read_blind(
@@ -185,61 +280,44 @@ fn check_read_blind(
         _display: &str,
         _api_name: &str,
     ) -> std::result::Result<(), deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_read()
     }
 
     fn check_write(
         &mut self,
-        _path: &str,
+        path: &str,
         _api_name: &str,
     ) -> std::result::Result<std::path::PathBuf, deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_write()?;
+        Ok(path.into())
     }
 
     fn check_write_path<'a>(
         &mut self,
-        _path: &'a Path,
+        path: &'a Path,
         _api_name: &str,
     ) -> std::result::Result<
         std::borrow::Cow<'a, std::path::Path>,
         deno_permissions::PermissionCheckError,
     > {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_write()?;
+        Ok(Cow::Borrowed(path))
     }
 
     fn check_write_partial(
         &mut self,
-        _path: &str,
+        path: &str,
         _api_name: &str,
     ) -> std::result::Result<std::path::PathBuf, deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_write()?;
+        Ok(path.into())
     }
 
     fn check_write_all(
         &mut self,
         _api_name: &str,
     ) -> std::result::Result<(), deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_write()
     }
 
     fn check_write_blind(
@@ -248,11 +326,7 @@ fn check_write_blind(
         _display: &str,
         _api_name: &str,
     ) -> std::result::Result<(), deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_write()
     }
 }
 
@@ -271,28 +345,22 @@ fn check_net_url(
 
     fn check_read_with_api_name(
         &mut self,
-        _path: &str,
+        path: &str,
         _api_name: Option<&str>,
     ) -> std::result::Result<std::path::PathBuf, deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_read()?;
+        Ok(path.into())
     }
 
     fn check_read_path<'a>(
         &mut self,
-        _path: &'a Path,
+        path: &'a Path,
     ) -> std::result::Result<
         std::borrow::Cow<'a, std::path::Path>,
         deno_permissions::PermissionCheckError,
     > {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_read()?;
+        Ok(Cow::Borrowed(path))
     }
 
     fn query_read_all(&mut self) -> bool {
@@ -313,21 +381,107 @@ fn check_sys(
 
     fn check_write_with_api_name(
         &mut self,
-        _path: &str,
+        path: &str,
         _api_name: Option<&str>,
     ) -> std::result::Result<std::path::PathBuf, deno_permissions::PermissionCheckError> {
-        Err(PermissionCheckError::PermissionDenied(
-            deno_permissions::PermissionDeniedError::Fatal {
-                access: "not allowed".into(),
-            },
-        ))
+        self.check_fs_write()?;
+        Ok(path.into())
     }
 
     fn check_net(
         &mut self,
-        (host, _port): (&str, Option<u16>),
+        (host, port): (&str, Option<u16>),
         _api_name: &str,
     ) -> Result<(), PermissionCheckError> {
-        self.check_net(host)
+        Permissions::check_net(self, host, port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_net_allowlist_parse() {
+        let allowlist = NetAllowlist::parse(" example.com, api.fleek.xyz:443 ,[::2]:80,");
+        assert!(allowlist.allows("example.com", Some(80)));
+        assert!(allowlist.allows("EXAMPLE.com", None));
+        assert!(allowlist.allows("api.fleek.xyz", Some(443)));
+        assert!(!allowlist.allows("api.fleek.xyz", Some(80)));
+        assert!(allowlist.allows("::2", Some(80)));
+        assert!(allowlist.allows("[::2]", Some(80)));
+        assert!(!allowlist.allows("other.com", Some(443)));
+
+        assert!(NetAllowlist::parse("").allows("other.com", Some(443)));
+    }
+
+    #[test]
+    fn test_disallowed_connect_is_rejected() {
+        let mut permissions =
+            Permissions::new(NetAllowlist::parse("example.com:443"), FsAccess::None);
+
+        assert!(NetPermissions::check_net(
+            &mut permissions,
+            &("example.com", Some(443)),
+            "Deno.connect()"
+        )
+        .is_ok());
+        assert!(NetPermissions::check_net(
+            &mut permissions,
+            &("example.com", Some(8080)),
+            "Deno.connect()"
+        )
+        .is_err());
+        assert!(NetPermissions::check_net(
+            &mut permissions,
+            &("other.com", Some(443)),
+            "Deno.connect()"
+        )
+        .is_err());
+
+        let url = Url::parse("https://example.com/path").unwrap();
+        assert!(FetchPermissions::check_net_url(&mut permissions, &url, "fetch()").is_ok());
+        let url = Url::parse("https://other.com/path").unwrap();
+        assert!(FetchPermissions::check_net_url(&mut permissions, &url, "fetch()").is_err());
+    }
+
+    #[test]
+    fn test_fs_access() {
+        let path = Path::new("/data.txt");
+
+        let mut permissions = Permissions::default();
+        assert!(FsPermissions::check_read_path(&mut permissions, path, "Deno.readFile()").is_err());
+        assert!(FsPermissions::check_open(&mut permissions, true, true, false, path, "").is_err());
+
+        let mut permissions = Permissions::new(NetAllowlist::default(), FsAccess::ReadOnly);
+        assert!(FsPermissions::check_read_path(&mut permissions, path, "Deno.readFile()").is_ok());
+        assert!(FsPermissions::check_open(&mut permissions, true, true, false, path, "").is_ok());
+        assert!(
+            FsPermissions::check_write_path(&mut permissions, path, "Deno.writeFile()").is_err()
+        );
+        assert!(FsPermissions::check_open(&mut permissions, true, true, true, path, "").is_err());
+
+        let mut permissions = Permissions::new(NetAllowlist::default(), FsAccess::ReadWrite);
+        assert!(
+            FsPermissions::check_write_path(&mut permissions, path, "Deno.writeFile()").is_ok()
+        );
+        assert!(FsPermissions::check_open(&mut permissions, true, true, true, path, "").is_ok());
+    }
+
+    #[test]
+    fn test_blacklist_applies_without_allowlist() {
+        let mut permissions = Permissions::default();
+        assert!(NetPermissions::check_net(
+            &mut permissions,
+            &("localhost", Some(80)),
+            "Deno.connect()"
+        )
+        .is_err());
+        assert!(NetPermissions::check_net(
+            &mut permissions,
+            &("example.com", Some(80)),
+            "Deno.connect()"
+        )
+        .is_ok());
     }
 }
@@ -0,0 +1,503 @@
+use std::borrow::Cow;
+use std::io::{Error, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use deno_fs::{AccessCheckCb, FileSystem, FsDirEntry, FsFileType, OpenOptions, RealFs};
+use deno_io::fs::{File, FsError, FsResult, FsStat};
+
+/// The environment variable the host directory mapped into the runtime is read from.
+pub const FS_ROOT_ENV: &str = "FLEEK_JS_FS_ROOT";
+
+/// The environment variable that makes the sandbox writable when set to `true`.
+pub const FS_WRITABLE_ENV: &str = "FLEEK_JS_FS_WRITABLE";
+
+/// A host directory mapped to the root of the file system of the runtime.
+#[derive(Clone, Debug)]
+pub struct FsSandboxConfig {
+    pub root: PathBuf,
+    pub writable: bool,
+}
+
+impl FsSandboxConfig {
+    /// Read the sandbox from [`FS_ROOT_ENV`] and [`FS_WRITABLE_ENV`]. Returns `None` if no root
+    /// is configured.
+    pub fn from_env() -> Option<Self> {
+        let root = std::env::var_os(FS_ROOT_ENV)?;
+        let writable = std::env::var(FS_WRITABLE_ENV).is_ok_and(|value| value == "true");
+        Some(Self {
+            root: root.into(),
+            writable,
+        })
+    }
+}
+
+/// The access to the file system that the runtime is permitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FsAccess {
+    #[default]
+    None,
+    ReadOnly,
+    ReadWrite,
+}
+
+impl From<Option<&FsSandboxConfig>> for FsAccess {
+    fn from(config: Option<&FsSandboxConfig>) -> Self {
+        match config {
+            None => FsAccess::None,
+            Some(config) if config.writable => FsAccess::ReadWrite,
+            Some(_) => FsAccess::ReadOnly,
+        }
+    }
+}
+
+/// A file system that exposes a single host directory as its root, chroot-style.
+///
+/// Paths are resolved relative to the root and any path that resolves outside of it, either
+/// through `..` components or through symlinks, is rejected. Links can not be created or read,
+/// since they would allow escaping the sandbox or leak host paths.
+#[derive(Debug)]
+pub struct SandboxFs {
+    root: PathBuf,
+    writable: bool,
+    inner: RealFs,
+}
+
+impl SandboxFs {
+    pub fn new(config: &FsSandboxConfig) -> std::io::Result<Self> {
+        Ok(Self {
+            root: config.root.canonicalize()?,
+            writable: config.writable,
+            inner: RealFs,
+        })
+    }
+
+    /// Map a path of the runtime to the host path it refers to.
+    fn resolve(&self, path: &Path, write: bool) -> FsResult<PathBuf> {
+        if write && !self.writable {
+            return Err(permission_denied("The file system is read-only"));
+        }
+
+        let mut relative = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::Prefix(_) | Component::RootDir | Component::CurDir => {},
+                Component::ParentDir => {
+                    if !relative.pop() {
+                        return Err(permission_denied("Path escapes the sandbox"));
+                    }
+                },
+                Component::Normal(name) => relative.push(name),
+            }
+        }
+        let resolved = self.root.join(relative);
+
+        // The path may contain symlinks that point out of the sandbox, so check where the
+        // longest existing prefix of the path really is.
+        let mut existing = resolved.as_path();
+        while !existing.exists() {
+            existing = existing
+                .parent()
+                .ok_or_else(|| permission_denied("Path escapes the sandbox"))?;
+        }
+        if !existing.canonicalize()?.starts_with(&self.root) {
+            return Err(permission_denied("Path escapes the sandbox"));
+        }
+
+        Ok(resolved)
+    }
+
+    /// Map a host path back to the path of the runtime.
+    fn unresolve(&self, path: PathBuf) -> FsResult<PathBuf> {
+        let relative = path
+            .strip_prefix(&self.root)
+            .map_err(|_| permission_denied("Path escapes the sandbox"))?;
+        Ok(Path::new("/").join(relative))
+    }
+}
+
+fn permission_denied(message: &str) -> FsError {
+    FsError::Io(Error::new(ErrorKind::PermissionDenied, message))
+}
+
+#[async_trait::async_trait(?Send)]
+impl FileSystem for SandboxFs {
+    fn cwd(&self) -> FsResult<PathBuf> {
+        Ok(PathBuf::from("/"))
+    }
+
+    fn tmp_dir(&self) -> FsResult<PathBuf> {
+        Err(FsError::NotSupported)
+    }
+
+    fn chdir(&self, _path: &Path) -> FsResult<()> {
+        Err(FsError::NotSupported)
+    }
+
+    fn umask(&self, _mask: Option<u32>) -> FsResult<u32> {
+        Err(FsError::NotSupported)
+    }
+
+    fn open_sync(
+        &self,
+        path: &Path,
+        options: OpenOptions,
+        access_check: Option<AccessCheckCb>,
+    ) -> FsResult<Rc<dyn File>> {
+        let write = options.write
+            || options.append
+            || options.create
+            || options.create_new
+            || options.truncate;
+        let path = self.resolve(path, write)?;
+        self.inner.open_sync(&path, options, access_check)
+    }
+    async fn open_async<'a>(
+        &'a self,
+        path: PathBuf,
+        options: OpenOptions,
+        access_check: Option<AccessCheckCb<'a>>,
+    ) -> FsResult<Rc<dyn File>> {
+        self.open_sync(&path, options, access_check)
+    }
+
+    fn mkdir_sync(&self, path: &Path, recursive: bool, mode: Option<u32>) -> FsResult<()> {
+        let path = self.resolve(path, true)?;
+        self.inner.mkdir_sync(&path, recursive, mode)
+    }
+    async fn mkdir_async(&self, path: PathBuf, recursive: bool, mode: Option<u32>) -> FsResult<()> {
+        self.mkdir_sync(&path, recursive, mode)
+    }
+
+    fn chmod_sync(&self, _path: &Path, _mode: u32) -> FsResult<()> {
+        Err(FsError::NotSupported)
+    }
+    async fn chmod_async(&self, path: PathBuf, mode: u32) -> FsResult<()> {
+        self.chmod_sync(&path, mode)
+    }
+
+    fn chown_sync(&self, _path: &Path, _uid: Option<u32>, _gid: Option<u32>) -> FsResult<()> {
+        Err(FsError::NotSupported)
+    }
+    async fn chown_async(&self, path: PathBuf, uid: Option<u32>, gid: Option<u32>) -> FsResult<()> {
+        self.chown_sync(&path, uid, gid)
+    }
+
+    fn lchown_sync(&self, _path: &Path, _uid: Option<u32>, _gid: Option<u32>) -> FsResult<()> {
+        Err(FsError::NotSupported)
+    }
+    async fn lchown_async(
+        &self,
+        path: PathBuf,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> FsResult<()> {
+        self.lchown_sync(&path, uid, gid)
+    }
+
+    fn remove_sync(&self, path: &Path, recursive: bool) -> FsResult<()> {
+        let path = self.resolve(path, true)?;
+        if path == self.root {
+            return Err(permission_denied("Can not remove the root of the sandbox"));
+        }
+        self.inner.remove_sync(&path, recursive)
+    }
+    async fn remove_async(&self, path: PathBuf, recursive: bool) -> FsResult<()> {
+        self.remove_sync(&path, recursive)
+    }
+
+    fn copy_file_sync(&self, from: &Path, to: &Path) -> FsResult<()> {
+        let from = self.resolve(from, false)?;
+        let to = self.resolve(to, true)?;
+        self.inner.copy_file_sync(&from, &to)
+    }
+    async fn copy_file_async(&self, from: PathBuf, to: PathBuf) -> FsResult<()> {
+        self.copy_file_sync(&from, &to)
+    }
+
+    fn cp_sync(&self, from: &Path, to: &Path) -> FsResult<()> {
+        let from = self.resolve(from, false)?;
+        let to = self.resolve(to, true)?;
+        self.inner.cp_sync(&from, &to)
+    }
+    async fn cp_async(&self, from: PathBuf, to: PathBuf) -> FsResult<()> {
+        self.cp_sync(&from, &to)
+    }
+
+    fn stat_sync(&self, path: &Path) -> FsResult<FsStat> {
+        let path = self.resolve(path, false)?;
+        self.inner.stat_sync(&path)
+    }
+    async fn stat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+        self.stat_sync(&path)
+    }
+
+    fn lstat_sync(&self, path: &Path) -> FsResult<FsStat> {
+        let path = self.resolve(path, false)?;
+        self.inner.lstat_sync(&path)
+    }
+    async fn lstat_async(&self, path: PathBuf) -> FsResult<FsStat> {
+        self.lstat_sync(&path)
+    }
+
+    fn realpath_sync(&self, path: &Path) -> FsResult<PathBuf> {
+        let path = self.resolve(path, false)?;
+        self.unresolve(self.inner.realpath_sync(&path)?)
+    }
+    async fn realpath_async(&self, path: PathBuf) -> FsResult<PathBuf> {
+        self.realpath_sync(&path)
+    }
+
+    fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
+        let path = self.resolve(path, false)?;
+        self.inner.read_dir_sync(&path)
+    }
+    async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>> {
+        self.read_dir_sync(&path)
+    }
+
+    fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
+        let oldpath = self.resolve(oldpath, true)?;
+        let newpath = self.resolve(newpath, true)?;
+        self.inner.rename_sync(&oldpath, &newpath)
+    }
+    async fn rename_async(&self, oldpath: PathBuf, newpath: PathBuf) -> FsResult<()> {
+        self.rename_sync(&oldpath, &newpath)
+    }
+
+    fn link_sync(&self, _oldpath: &Path, _newpath: &Path) -> FsResult<()> {
+        Err(FsError::NotSupported)
+    }
+    async fn link_async(&self, oldpath: PathBuf, newpath: PathBuf) -> FsResult<()> {
+        self.link_sync(&oldpath, &newpath)
+    }
+
+    fn symlink_sync(
+        &self,
+        _oldpath: &Path,
+        _newpath: &Path,
+        _file_type: Option<FsFileType>,
+    ) -> FsResult<()> {
+        Err(FsError::NotSupported)
+    }
+    async fn symlink_async(
+        &self,
+        oldpath: PathBuf,
+        newpath: PathBuf,
+        file_type: Option<FsFileType>,
+    ) -> FsResult<()> {
+        self.symlink_sync(&oldpath, &newpath, file_type)
+    }
+
+    fn read_link_sync(&self, _path: &Path) -> FsResult<PathBuf> {
+        Err(FsError::NotSupported)
+    }
+    async fn read_link_async(&self, path: PathBuf) -> FsResult<PathBuf> {
+        self.read_link_sync(&path)
+    }
+
+    fn truncate_sync(&self, path: &Path, len: u64) -> FsResult<()> {
+        let path = self.resolve(path, true)?;
+        self.inner.truncate_sync(&path, len)
+    }
+    async fn truncate_async(&self, path: PathBuf, len: u64) -> FsResult<()> {
+        self.truncate_sync(&path, len)
+    }
+
+    fn utime_sync(
+        &self,
+        path: &Path,
+        atime_secs: i64,
+        atime_nanos: u32,
+        mtime_secs: i64,
+        mtime_nanos: u32,
+    ) -> FsResult<()> {
+        let path = self.resolve(path, true)?;
+        self.inner
+            .utime_sync(&path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    }
+    async fn utime_async(
+        &self,
+        path: PathBuf,
+        atime_secs: i64,
+        atime_nanos: u32,
+        mtime_secs: i64,
+        mtime_nanos: u32,
+    ) -> FsResult<()> {
+        self.utime_sync(&path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    }
+
+    fn lutime_sync(
+        &self,
+        path: &Path,
+        atime_secs: i64,
+        atime_nanos: u32,
+        mtime_secs: i64,
+        mtime_nanos: u32,
+    ) -> FsResult<()> {
+        let path = self.resolve(path, true)?;
+        self.inner
+            .lutime_sync(&path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    }
+    async fn lutime_async(
+        &self,
+        path: PathBuf,
+        atime_secs: i64,
+        atime_nanos: u32,
+        mtime_secs: i64,
+        mtime_nanos: u32,
+    ) -> FsResult<()> {
+        self.lutime_sync(&path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    }
+
+    fn write_file_sync(
+        &self,
+        path: &Path,
+        options: OpenOptions,
+        access_check: Option<AccessCheckCb>,
+        data: &[u8],
+    ) -> FsResult<()> {
+        let path = self.resolve(path, true)?;
+        self.inner
+            .write_file_sync(&path, options, access_check, data)
+    }
+    async fn write_file_async<'a>(
+        &'a self,
+        path: PathBuf,
+        options: OpenOptions,
+        access_check: Option<AccessCheckCb<'a>>,
+        data: Vec<u8>,
+    ) -> FsResult<()> {
+        self.write_file_sync(&path, options, access_check, &data)
+    }
+
+    fn read_file_sync(
+        &self,
+        path: &Path,
+        access_check: Option<AccessCheckCb>,
+    ) -> FsResult<Cow<'static, [u8]>> {
+        let path = self.resolve(path, false)?;
+        self.inner.read_file_sync(&path, access_check)
+    }
+    async fn read_file_async<'a>(
+        &'a self,
+        path: PathBuf,
+        access_check: Option<AccessCheckCb<'a>>,
+    ) -> FsResult<Cow<'static, [u8]>> {
+        self.read_file_sync(&path, access_check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(root: &Path, writable: bool) -> SandboxFs {
+        SandboxFs::new(&FsSandboxConfig {
+            root: root.to_path_buf(),
+            writable,
+        })
+        .unwrap()
+    }
+
+    fn is_permission_denied(result: FsResult<impl std::fmt::Debug>) -> bool {
+        matches!(result, Err(FsError::Io(e)) if e.kind() == ErrorKind::PermissionDenied)
+    }
+
+    #[test]
+    fn test_read_inside_sandbox() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("input")).unwrap();
+        std::fs::write(dir.path().join("input/data.txt"), b"hello").unwrap();
+        let fs = sandbox(dir.path(), false);
+
+        let content = fs
+            .read_file_sync(Path::new("/input/data.txt"), None)
+            .unwrap();
+        assert_eq!(&*content, b"hello");
+        let content = fs
+            .read_file_sync(Path::new("input/../input/./data.txt"), None)
+            .unwrap();
+        assert_eq!(&*content, b"hello");
+        assert_eq!(
+            fs.realpath_sync(Path::new("/input/data.txt")).unwrap(),
+            PathBuf::from("/input/data.txt")
+        );
+    }
+
+    #[test]
+    fn test_path_traversal_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("root")).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), b"secret").unwrap();
+        let fs = sandbox(&dir.path().join("root"), true);
+
+        assert!(is_permission_denied(
+            fs.read_file_sync(Path::new("/../secret.txt"), None)
+        ));
+        assert!(is_permission_denied(
+            fs.read_file_sync(Path::new("a/../../secret.txt"), None)
+        ));
+        assert!(is_permission_denied(fs.write_file_sync(
+            Path::new("../escape.txt"),
+            OpenOptions::write(true, false, false, None),
+            None,
+            b"data",
+        )));
+        assert!(!dir.path().join("escape.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_escape_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("root")).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), b"secret").unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("root/outside")).unwrap();
+        let fs = sandbox(&dir.path().join("root"), false);
+
+        assert!(is_permission_denied(
+            fs.read_file_sync(Path::new("/outside/secret.txt"), None)
+        ));
+    }
+
+    #[test]
+    fn test_read_only_rejects_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.txt"), b"hello").unwrap();
+
+        let fs = sandbox(dir.path(), false);
+        assert!(is_permission_denied(fs.write_file_sync(
+            Path::new("/data.txt"),
+            OpenOptions::write(true, false, false, None),
+            None,
+            b"changed",
+        )));
+        assert!(is_permission_denied(
+            fs.remove_sync(Path::new("/data.txt"), false)
+        ));
+        assert!(is_permission_denied(fs.mkdir_sync(
+            Path::new("/output"),
+            false,
+            None
+        )));
+        assert_eq!(
+            std::fs::read(dir.path().join("data.txt")).unwrap(),
+            b"hello"
+        );
+
+        let fs = sandbox(dir.path(), true);
+        fs.mkdir_sync(Path::new("/output"), false, None).unwrap();
+        fs.write_file_sync(
+            Path::new("/output/result.txt"),
+            OpenOptions::write(true, false, false, None),
+            None,
+            b"result",
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read(dir.path().join("output/result.txt")).unwrap(),
+            b"result"
+        );
+    }
+}
@@ -1,14 +1,31 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 
+use std::num::NonZeroUsize;
 use std::path::Path;
+use std::sync::{Arc, LazyLock};
 
 use deno_ast::{MediaType, ParseParams, SourceMapOption};
+use deno_core::parking_lot::Mutex;
 use deno_core::{ModuleCodeString, ModuleName, SourceMapData};
 use deno_error::JsErrorBox;
+use lru::LruCache;
 
 deno_error::js_error_wrapper!(deno_ast::ParseDiagnostic, JsParseDiagnostic, "Error");
 deno_error::js_error_wrapper!(deno_ast::TranspileError, JsTranspileError, "Error");
 
+/// The maximum number of transpiled modules kept in memory.
+const CACHE_CAPACITY: usize = 256;
+
+type CachedModule = (Arc<str>, Option<Arc<[u8]>>);
+
+/// Transpiled modules keyed by the blake3 hash of their name and source, shared by all of the
+/// runtimes of the process.
+static CACHE: LazyLock<Mutex<LruCache<[u8; 32], CachedModule>>> =
+    LazyLock::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(CACHE_CAPACITY).unwrap())));
+
+#[cfg(test)]
+static PARSE_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
 pub fn maybe_transpile_source(
     name: ModuleName,
     source: ModuleCodeString,
@@ -30,6 +47,22 @@ pub fn maybe_transpile_source(
         ),
     }
 
+    let mut hasher = fleek_blake3::Hasher::new();
+    hasher.update(name.as_str().as_bytes());
+    hasher.update(&[0]);
+    hasher.update(source.as_str().as_bytes());
+    let key = *hasher.finalize().as_bytes();
+
+    if let Some((text, source_map)) = CACHE.lock().get(&key) {
+        return Ok((
+            text.clone().into(),
+            source_map.as_ref().map(|sm| sm.to_vec().into()),
+        ));
+    }
+
+    #[cfg(test)]
+    PARSE_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
     let parsed = deno_ast::parse_module(ParseParams {
         specifier: deno_core::url::Url::parse(&name).unwrap(),
         text: source.into(),
@@ -58,9 +91,48 @@ pub fn maybe_transpile_source(
         .map_err(|e| JsErrorBox::from_err(JsTranspileError(e)))?
         .into_source();
 
-    let maybe_source_map: Option<SourceMapData> = transpiled_source
+    let source_text: Arc<str> = transpiled_source.text.into();
+    let source_map: Option<Arc<[u8]>> = transpiled_source
         .source_map
         .map(|sm| sm.into_bytes().into());
-    let source_text = transpiled_source.text;
+    CACHE
+        .lock()
+        .put(key, (source_text.clone(), source_map.clone()));
+
+    let maybe_source_map: Option<SourceMapData> = source_map.map(|sm| sm.to_vec().into());
     Ok((source_text.into(), maybe_source_map))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    fn transpile(source: &'static str) -> String {
+        let (code, _) = maybe_transpile_source(
+            ModuleName::from_static("file:///cache_test.ts"),
+            ModuleCodeString::from_static(source),
+        )
+        .unwrap();
+        code.as_str().to_string()
+    }
+
+    #[test]
+    fn test_transpile_cache() {
+        let parses = PARSE_COUNT.load(Ordering::Relaxed);
+        let first = transpile("const cached: number = 1;");
+        assert_eq!(PARSE_COUNT.load(Ordering::Relaxed), parses + 1);
+        assert!(!first.contains("number"));
+
+        // The second load of the same module is served from the cache.
+        let second = transpile("const cached: number = 1;");
+        assert_eq!(PARSE_COUNT.load(Ordering::Relaxed), parses + 1);
+        assert_eq!(first, second);
+
+        // A changed source is transpiled again.
+        let changed = transpile("const cached: number = 2;");
+        assert_eq!(PARSE_COUNT.load(Ordering::Relaxed), parses + 2);
+        assert_ne!(first, changed);
+    }
+}
@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+/// An execution that was aborted because it exceeded the limits of the service.
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("Execution timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("Execution ran out of memory (heap limit of {limit} bytes)")]
+    OutOfMemory { limit: usize },
+}
@@ -1,8 +1,11 @@
+use std::time::Duration;
+
 use anyhow::{bail, Context};
 use deno_core::futures::stream::FuturesUnordered;
 use deno_core::futures::StreamExt;
-use deno_core::v8::{Global, IsolateHandle, Value};
+use deno_core::v8::{Global, Value};
 use deno_core::{serde_v8, v8, JsRuntime, ModuleSpecifier};
+use deno_fleek::{FsSandboxConfig, NetAllowlist};
 use fn_sdk::connection::Connection;
 use fn_sdk::header::TransportDetail;
 use fn_sdk::http_util::{respond, respond_with_error, respond_with_http_response};
@@ -10,10 +13,12 @@
 use tokio_util::task::LocalPoolHandle;
 use tracing::{debug, error, info};
 
+pub use crate::error::ServiceError;
 use crate::runtime::guard::IsolateGuard;
-use crate::runtime::Runtime;
+use crate::runtime::{Runtime, Watchdog};
 use crate::stream::{Origin, Request};
 
+mod error;
 mod http;
 mod runtime;
 pub mod stream;
@@ -26,30 +31,90 @@ pub mod params {
     pub const REQ_TIMEOUT: Duration = Duration::from_secs(15);
 }
 
+/// The environment variable overriding the execution timeout of each invocation, in milliseconds.
+pub const EXECUTION_TIMEOUT_ENV: &str = "FLEEK_JS_EXECUTION_TIMEOUT_MS";
+
+/// The environment variable overriding the heap limit of each invocation, in bytes.
+pub const HEAP_LIMIT_ENV: &str = "FLEEK_JS_HEAP_LIMIT_BYTES";
+
+/// The restrictions of the runtime, read from the environment of the service.
+#[derive(Clone, Debug)]
+pub struct ServiceConfig {
+    pub net_allowlist: NetAllowlist,
+    pub fs_sandbox: Option<FsSandboxConfig>,
+    /// The maximum wall clock time of a single invocation.
+    pub execution_timeout: Duration,
+    /// The maximum size of the v8 heap of a single invocation.
+    pub heap_limit_bytes: usize,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            net_allowlist: NetAllowlist::default(),
+            fs_sandbox: None,
+            execution_timeout: params::REQ_TIMEOUT,
+            heap_limit_bytes: params::HEAP_LIMIT,
+        }
+    }
+}
+
+impl ServiceConfig {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            net_allowlist: NetAllowlist::from_env(),
+            fs_sandbox: FsSandboxConfig::from_env(),
+            execution_timeout: parse_env::<u64>(EXECUTION_TIMEOUT_ENV)
+                .map(Duration::from_millis)
+                .unwrap_or(default.execution_timeout),
+            heap_limit_bytes: parse_env(HEAP_LIMIT_ENV).unwrap_or(default.heap_limit_bytes),
+        }
+    }
+}
+
+fn parse_env<T: std::str::FromStr>(key: &str) -> Option<T> {
+    let value = std::env::var(key).ok()?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            error!("Ignoring invalid value {value:?} for {key}");
+            None
+        },
+    }
+}
+
 #[tokio::main]
 pub async fn main() {
     fn_sdk::ipc::init_from_env();
 
     info!("Initialized POC JS service!");
 
+    let config = ServiceConfig::from_env();
+    if !config.net_allowlist.is_empty() {
+        info!("Restricting network access to {:?}", config.net_allowlist);
+    }
+    if let Some(sandbox) = &config.fs_sandbox {
+        info!("Mapping {sandbox:?} as the file system");
+    }
+    info!(
+        "Limiting executions to {:?} and {} heap bytes",
+        config.execution_timeout, config.heap_limit_bytes
+    );
+
     let mut listener = fn_sdk::ipc::conn_bind().await;
 
     // Explicitly initialize the v8 platform on the main thread
     JsRuntime::init_platform(None, false);
 
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<IsolateHandle>();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Watchdog>();
     tokio::task::spawn(async move {
         let mut isolates = FuturesUnordered::new();
         loop {
             tokio::select! {
                 next = rx.recv() => {
                     match next {
-                        Some(isolate) => {
-                            isolates.push(async move {
-                                tokio::time::sleep(params::REQ_TIMEOUT).await;
-                                isolate.terminate_execution();
-                            })
-                        }
+                        Some(watchdog) => isolates.push(watchdog.run()),
                         None => break,
                     }
                 }
@@ -61,9 +126,10 @@ pub async fn main() {
     let pool = LocalPoolHandle::new(num_cpus::get());
     while let Ok(conn) = listener.accept().await {
         let tx_clone = tx.clone();
+        let config = config.clone();
         pool.spawn_pinned(|| {
             tokio::task::spawn_local(async move {
-                if let Err(e) = handle_connection(conn, tx_clone).await {
+                if let Err(e) = handle_connection(conn, tx_clone, config).await {
                     error!("session failed: {e:?}");
                 }
             })
@@ -73,7 +139,8 @@ pub async fn main() {
 
 async fn handle_connection(
     mut connection: Connection,
-    tx: UnboundedSender<IsolateHandle>,
+    tx: UnboundedSender<Watchdog>,
+    config: ServiceConfig,
 ) -> anyhow::Result<()> {
     match &connection.header.transport_detail {
         TransportDetail::HttpRequest { .. } => {
@@ -93,14 +160,14 @@ async fn handle_connection(
             let request = http::request::extract(url, header, method, body.to_vec())
                 .context("failed to parse request")?;
 
-            if let Err(e) = handle_request(0, &mut connection, tx, request).await {
+            if let Err(e) = handle_request(0, &mut connection, tx, &config, request).await {
                 respond_with_error(&mut connection, format!("{e:?}").as_bytes(), 400).await?;
                 return Err(e);
             }
         },
         TransportDetail::Task { depth, payload } => {
             let request: Request = serde_json::from_slice(payload)?;
-            if let Err(e) = handle_request(*depth, &mut connection, tx, request).await {
+            if let Err(e) = handle_request(*depth, &mut connection, tx, &config, request).await {
                 respond_with_error(&mut connection, e.to_string().as_bytes(), 400).await?;
                 return Err(e);
             }
@@ -108,7 +175,9 @@ async fn handle_connection(
         TransportDetail::Other => {
             while let Some(payload) = connection.read_payload().await {
                 let request: Request = serde_json::from_slice(&payload)?;
-                if let Err(e) = handle_request(0, &mut connection, tx.clone(), request).await {
+                if let Err(e) =
+                    handle_request(0, &mut connection, tx.clone(), &config, request).await
+                {
                     respond_with_error(&mut connection, e.to_string().as_bytes(), 400).await?;
                     return Err(e);
                 };
@@ -122,7 +191,8 @@ async fn handle_connection(
 async fn handle_request(
     depth: u8,
     connection: &mut Connection,
-    tx: UnboundedSender<IsolateHandle>,
+    tx: UnboundedSender<Watchdog>,
+    config: &ServiceConfig,
     request: Request,
 ) -> anyhow::Result<()> {
     let Request {
@@ -156,12 +226,13 @@ async fn handle_request(
     let mut runtime = Runtime::new(
         location.clone(),
         depth,
+        config,
         otel_endpoint.map(|u| u.to_string()),
         otel_headers,
         otel_tags,
     )
     .context("Failed to initialize runtime")?;
-    tx.send(runtime.deno.v8_isolate().thread_safe_handle())?;
+    tx.send(runtime.watchdog())?;
 
     unsafe {
         runtime.deno.v8_isolate().exit();
@@ -182,6 +253,8 @@ async fn handle_request(
         runtime.deno.v8_isolate().enter();
     }
 
+    // A terminated isolate only surfaces as a generic exception, so report the limit instead.
+    runtime.check_limits()?;
     res?;
 
     let feed = runtime.end();
@@ -205,10 +278,11 @@ async fn handle_request_and_respond(
 
     // Resolve async if applicable
     // TODO: figure out why `deno.resolve` doesn't drive async functions
+    let timeout = runtime.execution_timeout();
     #[allow(deprecated)]
-    let res = tokio::time::timeout(params::REQ_TIMEOUT, runtime.deno.resolve_value(res))
+    let res = tokio::time::timeout(timeout, runtime.deno.resolve_value(res))
         .await
-        .context("Execution timeout")??;
+        .map_err(|_| ServiceError::Timeout(timeout))??;
 
     parse_and_respond(connection, runtime, res).await?;
 
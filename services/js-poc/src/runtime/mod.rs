@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use ::deno_fetch::{deno_fetch, Options};
 use ::deno_net::deno_net;
@@ -14,13 +15,14 @@
 use deno_console::deno_console;
 use deno_core::serde_v8::{self, to_v8};
 use deno_core::url::Url;
-use deno_core::v8::{self, CreateParams, Global, Value};
+use deno_core::v8::{self, CreateParams, Global, IsolateHandle, Value};
 use deno_core::{JsRuntime, ModuleSpecifier, PollEventLoopOptions, RuntimeOptions};
 use deno_crypto::deno_crypto;
 use deno_fleek::in_memory_fs::InMemoryFs;
 use deno_fleek::node_traits::{DisabledNpmChecker, InMemorySysWrapper};
-use deno_fleek::{fleek, maybe_transpile_source, Permissions};
+use deno_fleek::{fleek, maybe_transpile_source, FsAccess, Permissions, SandboxFs};
 use deno_fs::sync::MaybeArc;
+use deno_fs::FileSystemRc;
 use deno_telemetry::deno_telemetry;
 use deno_url::deno_url;
 use deno_webgpu::deno_webgpu;
@@ -28,7 +30,8 @@
 
 use self::module_loader::FleekModuleLoader;
 use self::tape::{Punch, Tape};
-use crate::params::{HEAP_INIT, HEAP_LIMIT};
+use crate::params::HEAP_INIT;
+use crate::{ServiceConfig, ServiceError};
 
 pub mod guard;
 pub mod module_loader;
@@ -40,6 +43,31 @@
 pub struct Runtime {
     pub deno: JsRuntime,
     tape: Tape,
+    execution_timeout: Duration,
+    heap_limit: usize,
+    timed_out: Arc<AtomicBool>,
+    out_of_memory: Arc<AtomicBool>,
+}
+
+/// Terminates the execution of a runtime once its timeout has elapsed.
+pub struct Watchdog {
+    isolate: IsolateHandle,
+    timeout: Duration,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl Watchdog {
+    /// Wait for the timeout and terminate the execution.
+    pub async fn run(self) {
+        tokio::time::sleep(self.timeout).await;
+        self.fire();
+    }
+
+    /// Terminate the execution immediately, marking it as timed out.
+    pub fn fire(&self) {
+        self.timed_out.store(true, Ordering::Relaxed);
+        self.isolate.terminate_execution();
+    }
 }
 
 impl Runtime {
@@ -47,11 +75,16 @@ impl Runtime {
     pub fn new(
         location: Url,
         depth: u8,
+        service_config: &ServiceConfig,
         otel_endpoint: Option<String>,
         otel_headers: HashMap<String, String>,
         otel_tags: HashMap<String, String>,
     ) -> Result<Self> {
-        let memory_fs = MaybeArc::new(InMemoryFs::default());
+        let fs: FileSystemRc = match &service_config.fs_sandbox {
+            Some(sandbox) => MaybeArc::new(SandboxFs::new(sandbox)?),
+            None => MaybeArc::new(InMemoryFs::default()),
+        };
+        let fs_access = FsAccess::from(service_config.fs_sandbox.as_ref());
         let tape = Tape::new(location.clone());
 
         let otel_config = OtelConfig {
@@ -92,19 +125,21 @@ pub fn new(
                 deno_webgpu::init_ops(),
                 deno_canvas::init_ops(),
                 deno_io::deno_io::init_ops(Some(Default::default())),
-                deno_fs::deno_fs::init_ops::<Permissions>(memory_fs.clone()),
+                deno_fs::deno_fs::init_ops::<Permissions>(fs.clone()),
                 deno_node::deno_node::init_ops::<
                     Permissions,
                     DisabledNpmChecker,
                     DisabledNpmChecker,
                     InMemorySysWrapper,
-                >(Default::default(), memory_fs),
+                >(Default::default(), fs),
                 // Fleek runtime
-                fleek::init_ops(depth),
+                fleek::init_ops(depth, service_config.net_allowlist.clone(), fs_access),
             ],
             startup_snapshot: Some(SNAPSHOT),
             op_metrics_factory_fn: Some(tape.op_metrics_factory_fn()),
-            create_params: Some(CreateParams::default().heap_limits(HEAP_INIT, HEAP_LIMIT)),
+            create_params: Some(
+                CreateParams::default().heap_limits(HEAP_INIT, service_config.heap_limit_bytes),
+            ),
             module_loader: Some(Rc::new(FleekModuleLoader::new())),
             extension_transpiler: Some(Rc::new(|specifier, source| {
                 maybe_transpile_source(specifier, source)
@@ -112,6 +147,19 @@ pub fn new(
             ..Default::default()
         });
 
+        // Abort the execution instead of letting v8 crash the process when the heap is full.
+        let out_of_memory = Arc::new(AtomicBool::new(false));
+        {
+            let out_of_memory = out_of_memory.clone();
+            let isolate = deno.v8_isolate().thread_safe_handle();
+            deno.add_near_heap_limit_callback(move |current, _initial| {
+                out_of_memory.store(true, Ordering::Relaxed);
+                isolate.terminate_execution();
+                // Give the isolate enough room to unwind the terminated execution.
+                current * 2
+            });
+        }
+
         {
             // Get global scope
             let context = deno.main_context();
@@ -140,7 +188,41 @@ pub fn new(
                 .expect("Failed to execute bootstrap");
         }
 
-        Ok(Self { deno, tape })
+        Ok(Self {
+            deno,
+            tape,
+            execution_timeout: service_config.execution_timeout,
+            heap_limit: service_config.heap_limit_bytes,
+            timed_out: Arc::new(AtomicBool::new(false)),
+            out_of_memory,
+        })
+    }
+
+    /// The maximum wall clock time of the execution.
+    pub fn execution_timeout(&self) -> Duration {
+        self.execution_timeout
+    }
+
+    /// Create the watchdog enforcing the execution timeout of this runtime.
+    pub fn watchdog(&mut self) -> Watchdog {
+        Watchdog {
+            isolate: self.deno.v8_isolate().thread_safe_handle(),
+            timeout: self.execution_timeout,
+            timed_out: self.timed_out.clone(),
+        }
+    }
+
+    /// Returns an error if the execution was terminated for exceeding one of its limits.
+    pub fn check_limits(&self) -> Result<(), ServiceError> {
+        if self.out_of_memory.load(Ordering::Relaxed) {
+            return Err(ServiceError::OutOfMemory {
+                limit: self.heap_limit,
+            });
+        }
+        if self.timed_out.load(Ordering::Relaxed) {
+            return Err(ServiceError::Timeout(self.execution_timeout));
+        }
+        Ok(())
     }
 
     /// Execute javascript source on the runtime
@@ -200,3 +282,62 @@ pub fn end(mut self) -> Vec<Punch> {
         self.tape.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ALIGNED_SNAPSHOT_SIZE;
+
+    fn runtime(config: &ServiceConfig) -> Runtime {
+        let location = Url::parse("blake3://test").unwrap();
+        Runtime::new(location, 0, config, None, HashMap::new(), HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn test_infinite_loop_times_out() {
+        let config = ServiceConfig {
+            execution_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let mut runtime = runtime(&config);
+
+        let watchdog = runtime.watchdog();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(watchdog.timeout);
+            watchdog.fire();
+        });
+
+        assert!(runtime
+            .deno
+            .execute_script("<test>", "while (true) {}")
+            .is_err());
+        handle.join().unwrap();
+        assert!(matches!(
+            runtime.check_limits(),
+            Err(ServiceError::Timeout(timeout)) if timeout == config.execution_timeout
+        ));
+    }
+
+    #[test]
+    fn test_allocation_bomb_runs_out_of_memory() {
+        let config = ServiceConfig {
+            heap_limit_bytes: ALIGNED_SNAPSHOT_SIZE + (16 << 20),
+            ..Default::default()
+        };
+        let mut runtime = runtime(&config);
+
+        let source = "const a = []; while (true) { a.push(new Array(1 << 16).fill(0)); }";
+        assert!(runtime.deno.execute_script("<test>", source).is_err());
+        assert!(matches!(
+            runtime.check_limits(),
+            Err(ServiceError::OutOfMemory { limit }) if limit == config.heap_limit_bytes
+        ));
+    }
+
+    #[test]
+    fn test_check_limits_ok() {
+        let mut runtime = runtime(&ServiceConfig::default());
+        runtime.deno.execute_script("<test>", "1 + 1").unwrap();
+        assert!(runtime.check_limits().is_ok());
+    }
+}